@@ -1,14 +1,14 @@
 use bevy::{
     color::palettes,
     diagnostic::{EntityCountDiagnosticsPlugin, FrameTimeDiagnosticsPlugin},
-    ecs::system::{StaticSystemParam, SystemParam},
+    ecs::system::StaticSystemParam,
     prelude::*,
 };
 use bevy_editor_pls::{
     editor::EditorInternalState, egui_dock::NodeIndex, prelude::NotInScene, AddEditorWindow,
     EditorPlugin,
 };
-use bevy_reactive_blueprints::{AsChild, BlueprintPlugin, BlueprintsPlugin, FromBlueprint};
+use bevy_reactive_blueprints::prelude::*;
 
 use bevy_reactive_blueprints_editor_window::BlueprintSceneWindow;
 use bevy_reactive_blueprints_editor_window::*;
@@ -31,12 +31,20 @@ pub fn main() {
     let mut internal_state = app.world_mut().resource_mut::<EditorInternalState>();
     internal_state.split_below::<BlueprintSceneWindow>(NodeIndex::root().left().left(), 0.6);
 
+    // clicking a rect's mesh selects the blueprint entity rather than the child holding the mesh
+    #[cfg(feature = "picking")]
+    app.add_plugins(BlueprintPickingPlugin);
+
     // register some blueprint
     app.add_plugins(BlueprintPlugin::<RectBlueprint, RectBundle>::default())
         .add_plugins(BlueprintPlugin::<RectBlueprint, RectBundle, AsChild>::default())
-        .register_blueprint::<RectBlueprint>()
+        .add_plugins(BlueprintNamePlugin::<RectBlueprint>::default())
+        .register_blueprint_in::<RectBlueprint>("Shapes")
         .register_type::<RectSize>();
 
+    // blueprints defined in assets/blueprints/*.blueprint.ron, listed in the Blueprint Scenes window
+    app.add_plugins(DynamicBlueprintPlugin::default());
+
     app.world_mut().spawn((
         NotInScene,
         Camera3dBundle {
@@ -50,6 +58,7 @@ pub fn main() {
 
 #[derive(Reflect)]
 struct RectBlueprint {
+    label: String,
     origin: Vec2,
     size: Vec2,
     color: Color,
@@ -58,6 +67,7 @@ struct RectBlueprint {
 impl Default for RectBlueprint {
     fn default() -> Self {
         RectBlueprint {
+            label: String::new(),
             size: 4. * Vec2::ONE,
             color: Color::Srgba(palettes::css::BLUE),
             origin: Default::default(),
@@ -65,6 +75,13 @@ impl Default for RectBlueprint {
     }
 }
 
+// the label (when set) names the entity in the editor hierarchy
+impl NamedBlueprint for RectBlueprint {
+    fn blueprint_name(&self) -> Option<String> {
+        (!self.label.is_empty()).then(|| self.label.clone())
+    }
+}
+
 // marker component useful for queries
 #[derive(Component)]
 struct Rect;
@@ -82,14 +99,8 @@ struct RectBundle {
     pbr: PbrBundle,
 }
 
-#[derive(SystemParam)]
-struct RectBlueprintParams<'w> {
-    meshes: ResMut<'w, Assets<Mesh>>,
-    materials: ResMut<'w, Assets<StandardMaterial>>,
-}
-
 impl FromBlueprint<RectBlueprint> for RectBundle {
-    type Params<'w, 's> = RectBlueprintParams<'w>;
+    type Params<'w, 's> = MeshMaterialParams<'w, StandardMaterial>;
     fn from_blueprint(
         blueprint: &RectBlueprint,
         params: &mut StaticSystemParam<Self::Params<'_, '_>>,
@@ -99,10 +110,8 @@ impl FromBlueprint<RectBlueprint> for RectBundle {
             size: RectSize(blueprint.size),
             rect: Rect,
             pbr: PbrBundle {
-                mesh: params
-                    .meshes
-                    .add(Cuboid::new(blueprint.size.x, blueprint.size.y, 1.0).mesh()),
-                material: params.materials.add(blueprint.color),
+                mesh: params.add_box(blueprint.size.extend(1.0)),
+                material: params.add_material(blueprint.color),
                 transform: Transform::from_xyz(blueprint.origin.x, blueprint.origin.y, 0.0),
                 ..default()
             },
@@ -1,14 +1,33 @@
-use std::path::Path;
+use std::{
+    any::TypeId,
+    path::{Path, PathBuf},
+};
 
-use bevy::{ecs::entity::EntityHashMap, prelude::*};
+use bevy::{
+    asset::ron,
+    ecs::entity::EntityHashMap,
+    prelude::*,
+    reflect::{serde::TypedReflectSerializer, ReflectRef},
+};
 use bevy_editor_pls::{
-    default_windows::add::{AddItem, AddWindow},
+    default_windows::{
+        add::{AddItem, AddWindow},
+        hierarchy::HierarchyWindow,
+    },
     editor::Editor,
     editor_window::{EditorWindow, EditorWindowContext},
     egui_dock::egui,
     prelude::NotInScene,
 };
-use bevy_reactive_blueprints::{Blueprint, IsBlueprint};
+use bevy_reactive_blueprints::{
+    assign_blueprint_scene_ids, merge_blueprint_scene, Blueprint, BlueprintBundle,
+    BlueprintRegistry, BlueprintSceneId, DynamicBlueprint, IsBlueprint, RawBlueprint,
+};
+
+#[cfg(feature = "picking")]
+mod picking;
+#[cfg(feature = "picking")]
+pub use picking::*;
 
 pub enum EditorOpenSetting {
     Windowed,
@@ -18,13 +37,142 @@ pub enum EditorOpenSetting {
 const ASSETS_PATH: &str = "editor-scenes";
 const DEFAULT_FILENAME: &str = "scene";
 const EXTENSION: &str = "scn.ron";
+// where exported blueprints are written, relative to the assets folder
+const EXPORT_PATH: &str = "exported-blueprints";
+// not `blueprint.ron`, which `DynamicBlueprintLoader` loads as definitions
+const BLUEPRINT_EXTENSION: &str = "raw-blueprint.ron";
+// where the window state is kept between sessions, relative to the working directory
+const CONFIG_PATH: &str = "blueprint-editor.cfg";
+// saved scenes start with a header comment (which the scene loader skips) recording the version
+// of their format, for migrations; files without one are format 0
+const SCENE_HEADER: &str = "// blueprint scene";
+const SCENE_FORMAT_VERSION: u32 = 1;
 
-#[derive(Default)]
 pub struct BlueprintSceneWindowState {
     filename: String,
+    directory: String,
+    // the directory being typed, applied once the field loses focus
+    directory_input: String,
     play_scene_request:
         Option<Result<Handle<DynamicScene>, Box<dyn std::error::Error + Send + Sync>>>,
+    // whether the requested scene is merged into the current one rather than replacing it
+    merge_scene: bool,
     scene_save_result: Option<Result<(), Box<dyn std::error::Error + Send + Sync>>>,
+    // the selected entity and its blueprint types, when it has several to choose from
+    export_prompt: Option<(Entity, Vec<(&'static str, TypeId)>)>,
+    export_result: Option<Result<PathBuf, Box<dyn std::error::Error + Send + Sync>>>,
+    // the blueprint types of the last played scene that no `BlueprintPlugin` builds
+    unbuildable_blueprints: Vec<String>,
+    // why the last played scene may not load as it was saved
+    compatibility_warning: Option<String>,
+}
+
+impl Default for BlueprintSceneWindowState {
+    fn default() -> Self {
+        let persisted = PersistedWindowState::default();
+        BlueprintSceneWindowState {
+            filename: persisted.filename,
+            directory_input: persisted.directory.clone(),
+            directory: persisted.directory,
+            play_scene_request: None,
+            merge_scene: false,
+            scene_save_result: None,
+            export_prompt: None,
+            export_result: None,
+            unbuildable_blueprints: Vec::new(),
+            compatibility_warning: None,
+        }
+    }
+}
+
+impl BlueprintSceneWindowState {
+    fn persist(&self) {
+        let persisted = PersistedWindowState {
+            filename: self.filename.clone(),
+            directory: self.directory.clone(),
+        };
+        if let Err(error) = persisted.save(Path::new(CONFIG_PATH)) {
+            warn!("failed to save the blueprint editor state to {CONFIG_PATH}: {error}");
+        }
+    }
+}
+
+// restores the window state of the last session, once the editor exists
+fn load_window_state(mut editor: ResMut<Editor>) {
+    let Some(persisted) = PersistedWindowState::load(Path::new(CONFIG_PATH)) else {
+        return;
+    };
+    let state = editor
+        .window_state_mut::<BlueprintSceneWindow>()
+        .expect("BlueprintSceneWindow should exist");
+    state.filename = persisted.filename;
+    state.directory_input = persisted.directory.clone();
+    state.directory = persisted.directory;
+}
+
+// keeps the last edits, which are otherwise saved when a field loses focus
+fn save_window_state_on_exit(mut exit: EventReader<AppExit>, editor: Res<Editor>) {
+    if exit.read().next().is_none() {
+        return;
+    }
+    if let Some(state) = editor.window_state::<BlueprintSceneWindow>() {
+        state.persist();
+    }
+}
+
+// the parts of the window state that survive restarts, stored as `key = value` lines
+#[derive(Debug, PartialEq)]
+struct PersistedWindowState {
+    filename: String,
+    directory: String,
+}
+
+impl Default for PersistedWindowState {
+    fn default() -> Self {
+        PersistedWindowState {
+            filename: String::new(),
+            directory: ASSETS_PATH.to_string(),
+        }
+    }
+}
+
+impl PersistedWindowState {
+    fn load(path: &Path) -> Option<Self> {
+        let contents = std::fs::read_to_string(path).ok()?;
+        let persisted = Self::parse(&contents);
+        if persisted.is_none() {
+            warn!(
+                "ignoring the corrupt blueprint editor state in {}",
+                path.display()
+            );
+        }
+        persisted
+    }
+
+    // any malformed line discards the whole file
+    fn parse(contents: &str) -> Option<Self> {
+        let mut persisted = Self::default();
+        for line in contents.lines().filter(|line| !line.trim().is_empty()) {
+            let (key, value) = line.split_once('=')?;
+            let value = value.trim().to_string();
+            match key.trim() {
+                "filename" => persisted.filename = value,
+                "directory" if !value.is_empty() => persisted.directory = value,
+                _ => return None,
+            }
+        }
+        Some(persisted)
+    }
+
+    fn save(&self, path: &Path) -> std::io::Result<()> {
+        std::fs::write(
+            path,
+            format!(
+                "filename = {}\ndirectory = {}\n",
+                self.filename, self.directory
+            ),
+        )
+    }
 }
 
 pub struct BlueprintSceneWindow;
@@ -33,16 +181,48 @@ impl EditorWindow for BlueprintSceneWindow {
     type State = BlueprintSceneWindowState;
     const NAME: &'static str = "Blueprint Scenes";
 
+    fn app_setup(app: &mut App) {
+        app.add_systems(Startup, load_window_state)
+            .add_systems(Last, save_window_state_on_exit);
+    }
+
     fn ui(world: &mut World, mut cx: EditorWindowContext, ui: &mut egui::Ui) {
+        let selected = cx.state::<HierarchyWindow>().and_then(|hierarchy| {
+            match hierarchy.selected.as_slice() {
+                [entity] => Some(*entity),
+                _ => None,
+            }
+        });
         let state = cx.state_mut::<BlueprintSceneWindow>().unwrap();
 
-        let assets_path = std::path::Path::new("assets").join(&ASSETS_PATH);
+        let assets_path = std::path::Path::new("assets").join(&state.directory);
         // TODO: path pulls files from a different cwd than asset server
         let directory = std::fs::read_dir(&assets_path).unwrap_or_else(|_| {
-            std::fs::create_dir(&assets_path).unwrap();
+            std::fs::create_dir_all(&assets_path).unwrap();
             std::fs::read_dir(&assets_path).unwrap()
         });
 
+        ui.horizontal(|ui| {
+            ui.label("Folder");
+            let res = ui
+                .add(
+                    egui::TextEdit::singleline(&mut state.directory_input)
+                        .hint_text(ASSETS_PATH)
+                        .desired_width(120.0),
+                )
+                .on_hover_text("The scenes folder, in the assets folder");
+            if res.lost_focus() {
+                let directory = state.directory_input.trim();
+                state.directory = if directory.is_empty() {
+                    ASSETS_PATH.to_string()
+                } else {
+                    directory.to_string()
+                };
+                state.directory_input = state.directory.clone();
+                state.persist();
+            }
+        });
+
         ui.horizontal(|ui| {
             let res = egui::TextEdit::singleline(&mut state.filename)
                 .hint_text(DEFAULT_FILENAME)
@@ -52,6 +232,9 @@ impl EditorWindow for BlueprintSceneWindow {
             if res.response.changed() {
                 state.scene_save_result = None;
             }
+            if res.response.lost_focus() {
+                state.persist();
+            }
 
             if ui.button("Save").clicked() {
                 let filename = if state.filename.is_empty() {
@@ -63,6 +246,8 @@ impl EditorWindow for BlueprintSceneWindow {
                     .join(filename)
                     .with_extension(EXTENSION);
 
+                // so that merging the scene later finds the entities it was saved from
+                assign_blueprint_scene_ids(world);
                 let mut query = world.query_filtered::<Entity, With<IsBlueprint>>();
                 let entities = query.iter(world).collect();
                 state.scene_save_result =
@@ -81,6 +266,100 @@ impl EditorWindow for BlueprintSceneWindow {
             }
         }
 
+        // exports the selected entity's blueprint, asking which one if it has several
+        if state
+            .export_prompt
+            .as_ref()
+            .is_some_and(|(entity, _)| Some(*entity) != selected)
+        {
+            state.export_prompt = None;
+        }
+        ui.horizontal(|ui| {
+            let export = ui.add_enabled(selected.is_some(), egui::Button::new("Export Blueprint"));
+            if let (true, Some(entity)) = (export.clicked(), selected) {
+                let mut blueprints = entity_blueprints(world, entity);
+                state.export_result = None;
+                match blueprints.len() {
+                    0 => {
+                        state.export_result =
+                            Some(Err("the selected entity has no registered blueprint".into()));
+                    }
+                    1 => {
+                        let (type_path, component_type_id) = blueprints.remove(0);
+                        state.export_result = Some(export_blueprint(
+                            world,
+                            &Path::new("assets").join(EXPORT_PATH),
+                            entity,
+                            type_path,
+                            component_type_id,
+                        ));
+                    }
+                    _ => state.export_prompt = Some((entity, blueprints)),
+                }
+            }
+        });
+        if let Some((entity, blueprints)) = &state.export_prompt {
+            let entity = *entity;
+            let mut chosen = None;
+            ui.label("Export which blueprint?");
+            for (type_path, component_type_id) in blueprints {
+                if ui.button(*type_path).clicked() {
+                    chosen = Some((*type_path, *component_type_id));
+                }
+            }
+            if let Some((type_path, component_type_id)) = chosen {
+                state.export_prompt = None;
+                state.export_result = Some(export_blueprint(
+                    world,
+                    &Path::new("assets").join(EXPORT_PATH),
+                    entity,
+                    type_path,
+                    component_type_id,
+                ));
+            }
+        }
+        if let Some(status) = &state.export_result {
+            match status {
+                Ok(path) => {
+                    ui.label(
+                        egui::RichText::new(format!("Exported to {}", path.display()))
+                            .color(egui::Color32::GREEN),
+                    );
+                }
+                Err(error) => {
+                    ui.label(egui::RichText::new(error.to_string()).color(egui::Color32::RED));
+                }
+            }
+        }
+
+        // blueprints loaded from definition files, which cannot be added to the Add window
+        let dynamic_blueprints = world
+            .get_resource::<BlueprintRegistry>()
+            .map(|registry| {
+                registry
+                    .dynamic_blueprints()
+                    .map(str::to_string)
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        if !dynamic_blueprints.is_empty() {
+            ui.separator();
+            ui.label("Dynamic blueprints");
+            for name in dynamic_blueprints {
+                ui.horizontal(|ui| {
+                    ui.label(&name);
+                    if ui.button("Add").clicked() {
+                        world.spawn((
+                            Name::new(name.clone()),
+                            DynamicBlueprint(name),
+                            SpatialBundle::default(),
+                        ));
+                    }
+                });
+            }
+            ui.separator();
+        }
+
         for entry in directory {
             let entry = entry.unwrap();
 
@@ -91,7 +370,14 @@ impl EditorWindow for BlueprintSceneWindow {
                 let file_stem = stripped_path.file_name().unwrap().to_str().unwrap();
 
                 ui.label(file_stem);
-                if ui.button("Play").clicked() {
+                let play = ui.button("Play").clicked();
+                let merge = ui
+                    .button("Merge")
+                    .on_hover_text(
+                        "Load over the current scene, only rebuilding the blueprints that changed",
+                    )
+                    .clicked();
+                if play {
                     // despawn the previous scene
                     type NotRelevant = (Without<NotInScene>, Without<Window>);
                     let mut query = world.query_filtered::<Entity, NotRelevant>();
@@ -99,8 +385,13 @@ impl EditorWindow for BlueprintSceneWindow {
                         // TODO Some sort of despawn bug?
                         world.despawn(entity);
                     }
+                }
+                if play || merge {
                     // load the new scene
-                    let scene_filename = Path::new(&ASSETS_PATH)
+                    state.merge_scene = merge;
+                    state.unbuildable_blueprints.clear();
+                    state.compatibility_warning = None;
+                    let scene_filename = Path::new(&state.directory)
                         .join(file_stem)
                         .with_extension(EXTENSION);
                     state.play_scene_request = Some(load_scene(
@@ -115,7 +406,20 @@ impl EditorWindow for BlueprintSceneWindow {
         if let Some(status) = &state.play_scene_request {
             match status {
                 Ok(scene) => {
-                    if poll_loading_scene(world, scene).is_ok() {
+                    if let Ok((unbuildable, compatibility_warning)) =
+                        poll_loading_scene(world, scene, state.merge_scene)
+                    {
+                        if !unbuildable.is_empty() {
+                            warn!(
+                                "the scene has blueprints that no BlueprintPlugin builds: {}",
+                                unbuildable.join(", ")
+                            );
+                        }
+                        if let Some(compatibility_warning) = &compatibility_warning {
+                            warn!("{compatibility_warning}");
+                        }
+                        state.unbuildable_blueprints = unbuildable;
+                        state.compatibility_warning = compatibility_warning;
                         state.play_scene_request = None;
                     }
                 }
@@ -124,6 +428,18 @@ impl EditorWindow for BlueprintSceneWindow {
                 }
             }
         }
+        if let Some(compatibility_warning) = &state.compatibility_warning {
+            ui.label(egui::RichText::new(compatibility_warning).color(egui::Color32::YELLOW));
+        }
+        if !state.unbuildable_blueprints.is_empty() {
+            ui.label(
+                egui::RichText::new(format!(
+                    "These blueprints have no BlueprintPlugin and will not build: {}",
+                    state.unbuildable_blueprints.join(", ")
+                ))
+                .color(egui::Color32::YELLOW),
+            );
+        }
     }
 }
 
@@ -132,6 +448,12 @@ type AnyResult<T> = Result<T, Box<dyn std::error::Error + Send + Sync>>;
 #[derive(Resource)]
 struct BlueprintsFilter(SceneFilter);
 
+// blueprint types already added to the editor's Add window
+#[derive(Default, Resource)]
+struct AddedBlueprints(std::collections::HashSet<std::any::TypeId>);
+
+const DEFAULT_CATEGORY: &str = "Blueprints";
+
 fn save_world(
     world: &World,
     name: &str,
@@ -140,8 +462,12 @@ fn save_world(
     let type_registry = world.get_resource::<AppTypeRegistry>().unwrap();
     let blueprints_filter = world.get_resource::<BlueprintsFilter>().unwrap();
 
-    let mut scene_builder =
-        DynamicSceneBuilder::from_world(world).with_filter(blueprints_filter.0.clone());
+    let filter = blueprints_filter
+        .0
+        .clone()
+        .allow::<DynamicBlueprint>()
+        .allow::<BlueprintSceneId>();
+    let mut scene_builder = DynamicSceneBuilder::from_world(world).with_filter(filter);
     scene_builder = scene_builder
         .extract_entities(entities.into_iter())
         .remove_empty_entities();
@@ -149,28 +475,194 @@ fn save_world(
 
     let type_registry = type_registry.read();
     let ron = scene.serialize(&type_registry)?;
-    std::fs::write(name, ron)?;
+    std::fs::write(name, format!("{}\n{ron}", scene_header()))?;
     Ok(())
 }
 
+fn scene_header() -> String {
+    format!(
+        "{SCENE_HEADER}: format {SCENE_FORMAT_VERSION}, {} {}",
+        env!("CARGO_PKG_NAME"),
+        env!("CARGO_PKG_VERSION"),
+    )
+}
+
+// the format version and the crate version (if any) in the header of a saved scene, or format 0
+// for legacy files saved without one
+fn parse_scene_header(contents: &str) -> (u32, Option<&str>) {
+    let Some(header) = contents
+        .lines()
+        .next()
+        .and_then(|line| line.strip_prefix(SCENE_HEADER))
+        .and_then(|header| header.strip_prefix(": format "))
+    else {
+        return (0, None);
+    };
+    let (version, saved_with) = header.split_once(", ").unwrap_or((header, ""));
+    let crate_version = saved_with
+        .strip_prefix(env!("CARGO_PKG_NAME"))
+        .map(str::trim);
+    (version.trim().parse().unwrap_or(0), crate_version)
+}
+
+// why the scene read from `path` may not load as it was saved, if it may not
+fn scene_compatibility_warning(path: &Path, contents: &str) -> Option<String> {
+    let path = path.display();
+    match parse_scene_header(contents) {
+        (0, _) => Some(format!(
+            "{path} has no version header; loading it as a legacy (format 0) scene"
+        )),
+        (version, _) if version != SCENE_FORMAT_VERSION => Some(format!(
+            "{path} was saved in scene format {version}, but this editor uses format \
+             {SCENE_FORMAT_VERSION}"
+        )),
+        (_, Some(crate_version)) if crate_version != env!("CARGO_PKG_VERSION") => Some(format!(
+            "{path} was saved with version {crate_version} of the editor, but this is version {}",
+            env!("CARGO_PKG_VERSION"),
+        )),
+        _ => None,
+    }
+}
+
+// the registered blueprint types on `entity`, as the type path of `B` and the `TypeId` of
+// `Blueprint<B>`
+fn entity_blueprints(world: &World, entity: Entity) -> Vec<(&'static str, TypeId)> {
+    let (Some(registry), Some(entity)) = (
+        world.get_resource::<BlueprintRegistry>(),
+        world.get_entity(entity),
+    ) else {
+        return Vec::new();
+    };
+    let mut blueprints = registry
+        .iter()
+        .filter(|registration| {
+            world
+                .components()
+                .get_id(registration.component_type_id)
+                .is_some_and(|id| entity.contains_id(id))
+        })
+        .map(|registration| (registration.type_path, registration.component_type_id))
+        .collect::<Vec<_>>();
+    blueprints.sort_by_key(|(type_path, _)| *type_path);
+    blueprints
+}
+
+// writes the `B` value of the entity's `Blueprint<B>` into `directory` as a `RawBlueprint`, with
+// the type path of `B`, named after the entity if it has a `Name` and after the type path otherwise
+fn export_blueprint(
+    world: &World,
+    directory: &Path,
+    entity: Entity,
+    type_path: &str,
+    component_type_id: TypeId,
+) -> AnyResult<PathBuf> {
+    let type_registry = world.resource::<AppTypeRegistry>().read();
+    let blueprint = type_registry
+        .get_type_data::<ReflectComponent>(component_type_id)
+        .and_then(|reflect_component| reflect_component.reflect(world.entity(entity)))
+        .ok_or("the blueprint is not registered for reflection")?;
+    let ReflectRef::TupleStruct(blueprint) = blueprint.reflect_ref() else {
+        return Err("the blueprint component is not a tuple struct".into());
+    };
+    let value = blueprint
+        .field(0)
+        .ok_or("the blueprint component is empty")?;
+    let serializer = TypedReflectSerializer::new(value, &type_registry);
+    let raw = RawBlueprint::new(type_path, ron::ser::to_string(&serializer)?);
+    let ron = ron::ser::to_string_pretty(&raw, ron::ser::PrettyConfig::default())?;
+
+    // the full type path, so that blueprints with the same name in different modules do not
+    // overwrite each other
+    let file_stem = world
+        .get::<Name>(entity)
+        .and_then(|name| export_file_stem(name.as_str()))
+        .or_else(|| export_file_stem(&type_path.to_lowercase()))
+        .unwrap_or_else(|| "blueprint".to_string());
+    std::fs::create_dir_all(directory)?;
+    let path = directory.join(format!("{file_stem}.{BLUEPRINT_EXTENSION}"));
+    std::fs::write(&path, ron)?;
+    Ok(path)
+}
+
+// the name as a file stem that stays in the export directory: path separators, dots (which
+// `..` and extensions are made of) and other punctuation become `_`, or `None` if nothing is left
+fn export_file_stem(name: &str) -> Option<String> {
+    let stem = name
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' || c == ' ' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+    stem.chars().any(char::is_alphanumeric).then_some(stem)
+}
+
 fn load_scene(world: &mut World, name: &str) -> AnyResult<Handle<DynamicScene>> {
     let asset_server = world.resource::<AssetServer>();
     let scene: Handle<DynamicScene> = asset_server.load(name.to_string());
     Ok(scene)
 }
 
-fn poll_loading_scene(world: &mut World, scene: &Handle<DynamicScene>) -> AnyResult<()> {
-    world.resource_scope(
-        |world: &mut World, scenes: Mut<Assets<DynamicScene>>| -> AnyResult<()> {
+// the `Blueprint<B>` types in `scene` that no `BlueprintPlugin` builds, e.g. because the scene was
+// authored with a newer content set: they are reflected (or the scene would not load), but not
+// registered as blueprints. The scene must have been written to the world, so that its blueprint
+// types are recorded as spawned.
+fn unbuildable_blueprints(world: &World, scene: &DynamicScene) -> Vec<String> {
+    let Some(registry) = world.get_resource::<BlueprintRegistry>() else {
+        return Vec::new();
+    };
+    let mut unbuildable = scene
+        .entities
+        .iter()
+        .flat_map(|entity| &entity.components)
+        .filter_map(|component| component.get_represented_type_info())
+        .filter(|info| registry.is_unregistered(world.components(), info.type_id()))
+        .map(|info| info.type_path().to_string())
+        .collect::<Vec<_>>();
+    unbuildable.sort();
+    unbuildable.dedup();
+    unbuildable
+}
+
+// writes the scene to the world once loaded (or merges it into the entities loaded from it
+// before), returning its unbuildable blueprint types, and a warning if its header does not match
+// this version of the editor
+fn poll_loading_scene(
+    world: &mut World,
+    scene: &Handle<DynamicScene>,
+    merge: bool,
+) -> AnyResult<(Vec<String>, Option<String>)> {
+    let unbuildable = world.resource_scope(
+        |world: &mut World, scenes: Mut<Assets<DynamicScene>>| -> AnyResult<Vec<String>> {
             let scene = match scenes.get(scene) {
                 Some(scene) => Ok(scene),
                 None => Err("Not ready yet!"),
             }?;
-            world.resource_scope(|world: &mut World, registry: Mut<AppTypeRegistry>| {
-                Ok(scene.write_to_world_with(world, &mut EntityHashMap::default(), &registry)?)
-            })
+            if merge {
+                merge_blueprint_scene(world, scene)?;
+            } else {
+                world.resource_scope(|world: &mut World, registry: Mut<AppTypeRegistry>| {
+                    scene.write_to_world_with(world, &mut EntityHashMap::default(), &registry)
+                })?;
+            }
+            Ok(unbuildable_blueprints(world, scene))
         },
-    )
+    )?;
+    // the asset server only keeps the parsed scene, so the header is read from the file
+    let compatibility_warning =
+        world
+            .resource::<AssetServer>()
+            .get_path(scene)
+            .and_then(|asset_path| {
+                let path = Path::new("assets").join(asset_path.path());
+                let contents = std::fs::read_to_string(&path).ok()?;
+                scene_compatibility_warning(&path, &contents)
+            });
+    Ok((unbuildable, compatibility_warning))
 }
 
 pub trait AppBlueprintExt {
@@ -178,6 +670,11 @@ pub trait AppBlueprintExt {
     where
         B: Default + TypePath + Send + Sync + 'static;
 
+    // the category is the group that the blueprint is listed under in the Add window
+    fn register_blueprint_in<B>(self, category: &'static str) -> Self
+    where
+        B: Default + TypePath + Send + Sync + 'static;
+
     fn ignore_all_component<C>(self) -> Self
     where
         C: Component + Send + Sync + 'static;
@@ -188,6 +685,22 @@ impl AppBlueprintExt for &mut App {
     where
         B: Default + TypePath + Send + Sync + 'static,
     {
+        self.register_blueprint_in::<B>(DEFAULT_CATEGORY)
+    }
+
+    fn register_blueprint_in<B>(self, category: &'static str) -> Self
+    where
+        B: Default + TypePath + Send + Sync + 'static,
+    {
+        let newly_added = self
+            .world_mut()
+            .get_resource_or_insert_with(AddedBlueprints::default)
+            .0
+            .insert(std::any::TypeId::of::<B>());
+        if !newly_added {
+            return self;
+        }
+
         let mut editor = self
             .world_mut()
             .get_resource_mut::<Editor>()
@@ -196,8 +709,8 @@ impl AppBlueprintExt for &mut App {
             .window_state_mut::<AddWindow>()
             .expect("AddWindow should exist");
         state.add(
-            "Blueprints",
-            AddItem::component_named::<Blueprint<B>>(B::type_path().into()),
+            category,
+            AddItem::bundle_named::<BlueprintBundle<B>>(B::type_path().into()),
         );
         let mut filter = self
             .world_mut()
@@ -236,3 +749,109 @@ fn collect_hierarchy(buffer: &mut Vec<Entity>, entity: Entity, children_query: &
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default, Reflect)]
+    struct Rect {
+        size: f32,
+    }
+
+    #[test]
+    fn window_state_round_trips() {
+        let persisted = PersistedWindowState {
+            filename: "level 1".to_string(),
+            directory: "levels/forest".to_string(),
+        };
+        let path = std::env::temp_dir().join("blueprint-editor-round-trip.cfg");
+        persisted.save(&path).unwrap();
+        assert_eq!(PersistedWindowState::load(&path), Some(persisted));
+        std::fs::remove_file(&path).unwrap();
+
+        // an empty directory falls back to the default, and missing keys keep their defaults
+        assert_eq!(
+            PersistedWindowState::parse("filename = level 1\ndirectory =\n"),
+            Some(PersistedWindowState {
+                filename: "level 1".to_string(),
+                ..default()
+            })
+        );
+        assert_eq!(
+            PersistedWindowState::parse(""),
+            Some(PersistedWindowState::default())
+        );
+    }
+
+    #[test]
+    fn corrupt_window_state_is_ignored() {
+        assert_eq!(PersistedWindowState::parse("filename"), None);
+        assert_eq!(
+            PersistedWindowState::parse("filename = level 1\ncolor = red\n"),
+            None
+        );
+
+        let path = std::env::temp_dir().join("blueprint-editor-corrupt.cfg");
+        std::fs::write(&path, [0xff, 0xfe, 0x00]).unwrap();
+        assert_eq!(PersistedWindowState::load(&path), None);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(PersistedWindowState::load(&path), None);
+    }
+
+    #[test]
+    fn exported_blueprints_stay_in_the_directory() {
+        let mut world = World::new();
+        world.init_resource::<AppTypeRegistry>();
+        world
+            .resource::<AppTypeRegistry>()
+            .write()
+            .register::<Blueprint<Rect>>();
+        let entity = world
+            .spawn((
+                Blueprint::new(Rect { size: 2. }),
+                Name::new("../walls/big.door"),
+            ))
+            .id();
+        let directory = std::env::temp_dir().join("blueprint-editor-export-test");
+        let path = export_blueprint(
+            &world,
+            &directory,
+            entity,
+            Rect::type_path(),
+            TypeId::of::<Blueprint<Rect>>(),
+        )
+        .unwrap();
+        assert_eq!(
+            path,
+            directory.join(format!("___walls_big_door.{BLUEPRINT_EXTENSION}"))
+        );
+
+        // written with its type path, so that it can be loaded as a `RawBlueprint`
+        let raw: RawBlueprint = ron::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(raw, RawBlueprint::new(Rect::type_path(), "(size:2.0)"));
+        std::fs::remove_dir_all(&directory).unwrap();
+
+        // unnamed blueprints are named after their full type path, so a `Rect` from another module
+        // does not overwrite them
+        world.entity_mut(entity).remove::<Name>();
+        let path = export_blueprint(
+            &world,
+            &directory,
+            entity,
+            Rect::type_path(),
+            TypeId::of::<Blueprint<Rect>>(),
+        )
+        .unwrap();
+        assert_eq!(
+            path,
+            directory.join(format!(
+                "bevy_reactive_blueprints_editor_window__tests__rect.{BLUEPRINT_EXTENSION}"
+            ))
+        );
+        std::fs::remove_dir_all(&directory).unwrap();
+
+        assert_eq!(export_file_stem(".."), None);
+        assert_eq!(export_file_stem(" Lamp 2 "), Some("Lamp 2".to_string()));
+    }
+}
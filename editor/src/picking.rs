@@ -0,0 +1,82 @@
+use bevy::prelude::*;
+use bevy_editor_pls::{default_windows::hierarchy::HierarchyWindow, editor::Editor};
+use bevy_mod_picking::prelude::*;
+use bevy_reactive_blueprints::{nearest_blueprint, BlueprintAncestry, BlueprintSource};
+
+/// Makes picking an entity built by a blueprint (e.g. the mesh of an `AsChild` prefab) select
+/// the nearest blueprint entity instead, both for `PickSelection` and in the editor's hierarchy.
+///
+/// Entities spawned for a blueprint that is `Pickable` are made pickable too.
+pub struct BlueprintPickingPlugin;
+
+impl Plugin for BlueprintPickingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(PostUpdate, (propagate_pickable, propagate_pick_selection))
+            // the editor selects whatever was clicked, so this has to run after it
+            .add_systems(Last, select_picked_blueprints);
+    }
+}
+
+fn propagate_pickable(
+    mut commands: Commands,
+    spawned_query: Query<(Entity, &BlueprintSource), (Added<BlueprintSource>, Without<Pickable>)>,
+    pickable_query: Query<(), With<Pickable>>,
+) {
+    for (entity, source) in spawned_query.iter() {
+        if pickable_query.contains(source.0) {
+            commands.entity(entity).insert(PickableBundle::default());
+        }
+    }
+}
+
+fn propagate_pick_selection(
+    mut commands: Commands,
+    mut selection_query: Query<(Entity, &mut PickSelection)>,
+    ancestry_query: Query<BlueprintAncestry>,
+) {
+    let mut redirected = vec![];
+    for (entity, selection) in selection_query.iter_mut() {
+        if !selection.is_changed() || !selection.is_selected {
+            continue;
+        }
+        match nearest_blueprint(entity, &ancestry_query) {
+            Some(blueprint) if blueprint != entity => redirected.push((entity, blueprint)),
+            _ => {}
+        }
+    }
+    for (entity, blueprint) in redirected {
+        if let Ok((_, mut selection)) = selection_query.get_mut(entity) {
+            selection.is_selected = false;
+        }
+        match selection_query.get_mut(blueprint) {
+            Ok((_, mut selection)) => selection.is_selected = true,
+            Err(_) => {
+                commands
+                    .entity(blueprint)
+                    .insert(PickSelection { is_selected: true });
+            }
+        }
+    }
+}
+
+fn select_picked_blueprints(
+    mut click_events: EventReader<Pointer<Click>>,
+    mut editor: ResMut<Editor>,
+    ancestry_query: Query<BlueprintAncestry>,
+) {
+    for click in click_events.read() {
+        let Some(blueprint) = nearest_blueprint(click.target, &ancestry_query) else {
+            continue;
+        };
+        if blueprint == click.target {
+            continue;
+        }
+        let Some(state) = editor.window_state_mut::<HierarchyWindow>() else {
+            return;
+        };
+        if state.selected.contains(click.target) {
+            state.selected.remove(click.target);
+            state.selected.select_maybe_add(blueprint, true);
+        }
+    }
+}
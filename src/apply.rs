@@ -0,0 +1,137 @@
+use std::marker::PhantomData;
+
+use bevy::{
+    ecs::system::{StaticSystemParam, SystemParam},
+    prelude::*,
+    reflect::GetTypeRegistration,
+};
+
+use crate::{Blueprint, BlueprintSet, BlueprintsPlugin};
+
+/// Writes blueprint data into a component the entity already has, rather than building a prefab
+/// that replaces it, e.g. setting only `Transform::translation` so that the rotation and scale
+/// are kept. Implemented on the blueprint type for each target component `C`.
+pub trait ApplyBlueprint<C> {
+    type Params<'w, 's>: SystemParam;
+
+    fn apply(&self, target: &mut C, params: &mut StaticSystemParam<Self::Params<'_, '_>>);
+}
+
+// the changed blueprints, with the component they are applied to (if the entity has one yet)
+type ApplyQuery<'w, 's, B, C> =
+    Query<'w, 's, (Entity, &'static Blueprint<B>, Option<&'static mut C>), Changed<Blueprint<B>>>;
+
+/// Applies each `Blueprint<B>` to the `C` component of its entity whenever the blueprint changes,
+/// inserting `C::default()` first if the entity has no `C` yet.
+///
+/// Nothing is inserted or removed otherwise: removing the blueprint leaves `C` as it was last
+/// applied.
+pub struct ApplyBlueprintPlugin<B, C> {
+    blueprint_marker: PhantomData<B>,
+    component_marker: PhantomData<C>,
+}
+
+impl<B, C> Default for ApplyBlueprintPlugin<B, C> {
+    fn default() -> Self {
+        Self {
+            blueprint_marker: PhantomData::<B>,
+            component_marker: PhantomData::<C>,
+        }
+    }
+}
+
+impl<B, C> ApplyBlueprintPlugin<B, C>
+where
+    B: ApplyBlueprint<C> + Default + Send + Sync + 'static,
+    C: Component + Default,
+{
+    fn apply_blueprints(
+        mut commands: Commands,
+        mut query: ApplyQuery<B, C>,
+        mut system_params: StaticSystemParam<B::Params<'_, '_>>,
+    ) {
+        for (entity, blueprint, target) in query.iter_mut() {
+            match target {
+                Some(mut target) => blueprint.0.apply(&mut target, &mut system_params),
+                None => {
+                    let mut target = C::default();
+                    blueprint.0.apply(&mut target, &mut system_params);
+                    commands.entity(entity).insert(target);
+                }
+            }
+        }
+    }
+}
+
+impl<B, C> Plugin for ApplyBlueprintPlugin<B, C>
+where
+    B: ApplyBlueprint<C> + Default + GetTypeRegistration + FromReflect + TypePath,
+    B: Send + Sync + 'static,
+    C: Component + Default,
+{
+    fn build(&self, app: &mut App) {
+        BlueprintsPlugin::add_once(app);
+        app.add_systems(Update, Self::apply_blueprints.in_set(BlueprintSet::Sync))
+            .register_type::<Blueprint<B>>()
+            .register_type::<B>();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BlueprintsPlugin;
+
+    #[derive(Default, Reflect)]
+    struct Placement {
+        origin: Vec3,
+    }
+
+    impl ApplyBlueprint<Transform> for Placement {
+        type Params<'w, 's> = ();
+
+        fn apply(&self, target: &mut Transform, _: &mut StaticSystemParam<Self::Params<'_, '_>>) {
+            target.translation = self.origin;
+        }
+    }
+
+    #[test]
+    fn applies_to_existing_component() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(ApplyBlueprintPlugin::<Placement, Transform>::default());
+        let transform =
+            Transform::from_rotation(Quat::from_rotation_z(1.)).with_scale(Vec3::ONE * 2.);
+        let entity = app
+            .world_mut()
+            .spawn((Blueprint::new(Placement { origin: Vec3::X }), transform))
+            .id();
+        app.update();
+        assert_eq!(
+            app.world().get::<Transform>(entity),
+            Some(&transform.with_translation(Vec3::X))
+        );
+
+        app.world_mut()
+            .get_mut::<Blueprint<Placement>>(entity)
+            .unwrap()
+            .0
+            .origin = Vec3::Y;
+        app.update();
+        assert_eq!(
+            app.world().get::<Transform>(entity),
+            Some(&transform.with_translation(Vec3::Y))
+        );
+
+        // a missing component is inserted
+        let entity = app
+            .world_mut()
+            .spawn(Blueprint::new(Placement { origin: Vec3::Z }))
+            .id();
+        app.update();
+        assert_eq!(
+            app.world().get::<Transform>(entity),
+            Some(&Transform::from_translation(Vec3::Z))
+        );
+    }
+}
@@ -0,0 +1,162 @@
+use std::marker::PhantomData;
+
+use bevy::{ecs::system::StaticSystemParam, prelude::*};
+
+use crate::{Blueprint, BlueprintTrigger, TryFromBlueprint};
+
+/// One-off data for building the blueprint on this entity that is not part of the blueprint
+/// itself, e.g. a faction color chosen when spawning. Plugins with `BlueprintPlugin::with_args`
+/// pass it to `FromBlueprintWithArgs::from_blueprint_with_args`.
+///
+/// Changing the args rebuilds the blueprint. Removing them leaves the target as it was built
+/// until the blueprint changes.
+#[derive(Clone, Copy, Debug, Default, Component, Deref, DerefMut)]
+pub struct BlueprintArgs<A: Send + Sync + 'static>(pub A);
+
+/// Builds a prefab from blueprint data of type `T` and the entity's `BlueprintArgs<A>`, for a
+/// `BlueprintPlugin` with `with_args`. Entities without args are built with `from_blueprint` (or
+/// `try_from_blueprint`) as usual.
+pub trait FromBlueprintWithArgs<T, A>: TryFromBlueprint<T> {
+    fn from_blueprint_with_args(
+        blueprint: &T,
+        args: &A,
+        params: &mut StaticSystemParam<Self::Params<'_, '_>>,
+    ) -> Self;
+}
+
+// builds blueprints when they or their args change
+pub(crate) struct ArgsTrigger<A>(PhantomData<A>);
+
+impl<B, A> BlueprintTrigger<B> for ArgsTrigger<A>
+where
+    B: Default + Send + Sync + 'static,
+    A: Send + Sync + 'static,
+{
+    type Filter = Or<(Changed<Blueprint<B>>, Changed<BlueprintArgs<A>>)>;
+}
+
+// how the sync system builds the prefab `P` of blueprint `B`, given the entity's `BlueprintArgs`
+pub(crate) trait PrefabArgs<B, P: TryFromBlueprint<B>>: 'static {
+    type Args: Send + Sync + 'static;
+
+    fn build(
+        blueprint: &B,
+        args: Option<&Self::Args>,
+        params: &mut StaticSystemParam<P::Params<'_, '_>>,
+    ) -> Result<P, P::Error>;
+}
+
+// for plugins without `with_args`
+pub(crate) struct NoArgs;
+
+impl<B, P: TryFromBlueprint<B>> PrefabArgs<B, P> for NoArgs {
+    type Args = ();
+
+    fn build(
+        blueprint: &B,
+        _: Option<&()>,
+        params: &mut StaticSystemParam<P::Params<'_, '_>>,
+    ) -> Result<P, P::Error> {
+        P::try_from_blueprint(blueprint, params)
+    }
+}
+
+pub(crate) struct WithArgs<A>(PhantomData<A>);
+
+impl<B, A, P> PrefabArgs<B, P> for WithArgs<A>
+where
+    A: Send + Sync + 'static,
+    P: FromBlueprintWithArgs<B, A>,
+{
+    type Args = A;
+
+    fn build(
+        blueprint: &B,
+        args: Option<&A>,
+        params: &mut StaticSystemParam<P::Params<'_, '_>>,
+    ) -> Result<P, P::Error> {
+        match args {
+            Some(args) => Ok(P::from_blueprint_with_args(blueprint, args, params)),
+            None => P::try_from_blueprint(blueprint, params),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use super::*;
+    use crate::{BlueprintPlugin, BlueprintsPlugin, FromBlueprint};
+
+    #[derive(Default, Reflect)]
+    struct Soldier {
+        size: f32,
+    }
+
+    #[derive(Component, Debug, PartialEq)]
+    struct Uniform {
+        size: f32,
+        color: Color,
+    }
+
+    impl FromBlueprint<Soldier> for Uniform {
+        type Params<'w, 's> = ();
+        fn from_blueprint(
+            blueprint: &Soldier,
+            _: &mut StaticSystemParam<Self::Params<'_, '_>>,
+        ) -> Self {
+            Uniform {
+                size: blueprint.size,
+                color: Color::WHITE,
+            }
+        }
+    }
+
+    // the faction color
+    impl FromBlueprintWithArgs<Soldier, Color> for Uniform {
+        fn from_blueprint_with_args(
+            blueprint: &Soldier,
+            color: &Color,
+            _: &mut StaticSystemParam<Self::Params<'_, '_>>,
+        ) -> Self {
+            Uniform {
+                size: blueprint.size,
+                color: *color,
+            }
+        }
+    }
+
+    fn color(app: &App, entity: Entity) -> Color {
+        app.world().get::<Uniform>(entity).unwrap().color
+    }
+
+    #[test]
+    fn args_are_passed_to_the_build() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Soldier, Uniform>::default().with_args::<Color>());
+        let spawn = |app: &mut App, args: Option<Color>| {
+            let mut entity = app.world_mut().spawn(Blueprint::new(Soldier { size: 1. }));
+            if let Some(color) = args {
+                entity.insert(BlueprintArgs(color));
+            }
+            entity.id()
+        };
+        let red = spawn(&mut app, Some(Color::srgb(1., 0., 0.)));
+        let blue = spawn(&mut app, Some(Color::srgb(0., 0., 1.)));
+        let plain = spawn(&mut app, None);
+        app.update();
+        assert_eq!(color(&app, red), Color::srgb(1., 0., 0.));
+        assert_eq!(color(&app, blue), Color::srgb(0., 0., 1.));
+        assert_eq!(color(&app, plain), Color::WHITE);
+
+        // changing the args rebuilds
+        app.world_mut()
+            .get_mut::<BlueprintArgs<Color>>(red)
+            .unwrap()
+            .0 = Color::BLACK;
+        app.update();
+        assert_eq!(color(&app, red), Color::BLACK);
+    }
+}
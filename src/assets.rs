@@ -0,0 +1,108 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+/// Creates assets from `from_blueprint` without mutable access to `Assets<A>`.
+///
+/// Handles are reserved immediately, and the assets themselves are inserted when commands are
+/// applied in `BlueprintSet::Flush`. Since this only reads `Assets<A>`, the sync systems of
+/// blueprints that all create the same kind of asset can run in parallel, unlike with
+/// `ResMut<Assets<A>>`. Reserving handles is thread-safe.
+#[derive(SystemParam)]
+pub struct BlueprintAssets<'w, 's, A: Asset> {
+    assets: Res<'w, Assets<A>>,
+    commands: Commands<'w, 's>,
+}
+
+impl<'w, 's, A: Asset> BlueprintAssets<'w, 's, A> {
+    pub fn add(&mut self, asset: impl Into<A>) -> Handle<A> {
+        let handle = self.assets.reserve_handle();
+        let id = handle.id();
+        let asset = asset.into();
+        self.commands.add(move |world: &mut World| {
+            world.resource_mut::<Assets<A>>().insert(id, asset);
+        });
+        handle
+    }
+
+    pub fn get(&self, id: impl Into<AssetId<A>>) -> Option<&A> {
+        self.assets.get(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        ecs::schedule::{LogLevel, ScheduleBuildSettings},
+        ecs::system::StaticSystemParam,
+        prelude::*,
+    };
+
+    use super::*;
+    use crate::{Blueprint, BlueprintPlugin, BlueprintsPlugin, FromBlueprint};
+
+    #[derive(Asset, TypePath)]
+    struct Shape(f32);
+
+    #[derive(Default, Reflect)]
+    struct Rect(f32);
+
+    #[derive(Default, Reflect)]
+    struct Circle(f32);
+
+    #[derive(Component)]
+    struct ShapeHandle(Handle<Shape>);
+
+    impl FromBlueprint<Rect> for ShapeHandle {
+        type Params<'w, 's> = BlueprintAssets<'w, 's, Shape>;
+        fn from_blueprint(
+            blueprint: &Rect,
+            shapes: &mut StaticSystemParam<Self::Params<'_, '_>>,
+        ) -> Self {
+            ShapeHandle(shapes.add(Shape(blueprint.0)))
+        }
+    }
+
+    impl FromBlueprint<Circle> for ShapeHandle {
+        type Params<'w, 's> = BlueprintAssets<'w, 's, Shape>;
+        fn from_blueprint(
+            blueprint: &Circle,
+            shapes: &mut StaticSystemParam<Self::Params<'_, '_>>,
+        ) -> Self {
+            ShapeHandle(shapes.add(Shape(blueprint.0)))
+        }
+    }
+
+    #[test]
+    fn assets_are_added_at_flush() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default(), BlueprintsPlugin))
+            .init_asset::<Shape>()
+            .add_plugins(BlueprintPlugin::<Rect, ShapeHandle>::default())
+            .add_plugins(BlueprintPlugin::<Circle, ShapeHandle>::default())
+            // the two sync systems only share read access to Assets<Shape>, so they may run in
+            // parallel; any conflict between them would fail to build the schedule
+            .edit_schedule(Update, |schedule| {
+                schedule.set_build_settings(ScheduleBuildSettings {
+                    ambiguity_detection: LogLevel::Error,
+                    ..default()
+                });
+            });
+        app.world_mut().spawn(Blueprint::new(Rect(2.)));
+        app.world_mut().spawn(Blueprint::new(Circle(3.)));
+        app.update();
+
+        let mut sizes = app
+            .world_mut()
+            .query::<&ShapeHandle>()
+            .iter(app.world())
+            .map(|handle| {
+                app.world()
+                    .resource::<Assets<Shape>>()
+                    .get(&handle.0)
+                    .unwrap()
+                    .0
+            })
+            .collect::<Vec<_>>();
+        sizes.sort_by(f32::total_cmp);
+        assert_eq!(sizes, vec![2., 3.]);
+    }
+}
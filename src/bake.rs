@@ -0,0 +1,120 @@
+use bevy::prelude::*;
+
+use crate::{
+    commit_blueprint_transactions, Blueprint, BlueprintBuilt, BlueprintDependencies,
+    BlueprintRegistry, IsBlueprint,
+};
+
+/// Present once `bake_blueprints` has run. While it exists, every system in `BlueprintsSet` is
+/// skipped, so baked targets are never cleaned up or rebuilt.
+#[derive(Debug, Default, Resource)]
+pub struct BlueprintsBaked;
+
+/// Builds every registered blueprint once and then removes the `Blueprint<B>` components,
+/// leaving only the built targets (including `AsChild` children).
+///
+/// This is meant for levels whose blueprints never change at runtime: afterwards, the blueprint
+/// systems no longer run at all. Blueprints spawned while baking (i.e. by other prefabs) are
+/// stripped without being built.
+pub fn bake_blueprints(world: &mut World) {
+    let Some(registry) = world.get_resource::<BlueprintRegistry>() else {
+        return;
+    };
+    let registrations = registry.iter().cloned().collect::<Vec<_>>();
+    // dependencies are not waited for when baking, so they may be built in any order
+    if let Some(mut dependencies) = world.get_resource_mut::<BlueprintDependencies>() {
+        dependencies.clear();
+    }
+    for registration in &registrations {
+        for prefab in &registration.prefabs {
+            (prefab.build_all)(world);
+        }
+    }
+    // there is no retrying transactions once baked
+    commit_blueprint_transactions(world);
+    for registration in &registrations {
+        (registration.strip)(world);
+    }
+    world.insert_resource(BlueprintsBaked);
+}
+
+pub(crate) fn strip_blueprints<B: Default + Send + Sync + 'static>(world: &mut World) {
+    let entities = world
+        .query_filtered::<Entity, With<Blueprint<B>>>()
+        .iter(world)
+        .collect::<Vec<_>>();
+    for entity in entities {
+        world
+            .entity_mut(entity)
+            .remove::<(Blueprint<B>, BlueprintBuilt<B>, IsBlueprint)>();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{ecs::system::StaticSystemParam, prelude::*};
+
+    use super::*;
+    use crate::{AsChild, BlueprintPlugin, BlueprintsPlugin, FromBlueprint};
+
+    #[derive(Default, Reflect)]
+    struct Rect {
+        size: f32,
+    }
+
+    #[derive(Component)]
+    struct RectSize(f32);
+
+    impl FromBlueprint<Rect> for RectSize {
+        type Params<'w, 's> = ();
+        fn from_blueprint(
+            blueprint: &Rect,
+            _: &mut StaticSystemParam<Self::Params<'_, '_>>,
+        ) -> Self {
+            RectSize(blueprint.size)
+        }
+    }
+
+    #[derive(Component)]
+    struct RectArea(f32);
+
+    impl FromBlueprint<Rect> for RectArea {
+        type Params<'w, 's> = ();
+        fn from_blueprint(
+            blueprint: &Rect,
+            _: &mut StaticSystemParam<Self::Params<'_, '_>>,
+        ) -> Self {
+            RectArea(blueprint.size * blueprint.size)
+        }
+    }
+
+    #[test]
+    fn baked_blueprints_keep_targets() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Rect, RectSize>::default())
+            .add_plugins(BlueprintPlugin::<Rect, RectArea, AsChild>::default());
+        let entity = app
+            .world_mut()
+            .spawn(Blueprint::new(Rect { size: 2. }))
+            .id();
+
+        bake_blueprints(app.world_mut());
+        app.update();
+        app.update();
+
+        let world = app.world();
+        assert!(!world.entity(entity).contains::<Blueprint<Rect>>());
+        assert_eq!(world.get::<RectSize>(entity).unwrap().0, 2.);
+        let child = world.get::<Children>(entity).unwrap()[0];
+        assert_eq!(world.get::<RectArea>(child).unwrap().0, 4.);
+
+        // the blueprint systems no longer run
+        let late = app
+            .world_mut()
+            .spawn(Blueprint::new(Rect { size: 1. }))
+            .id();
+        app.update();
+        assert!(!app.world().entity(late).contains::<RectSize>());
+    }
+}
@@ -0,0 +1,222 @@
+use bevy::{
+    ecs::reflect::ReflectComponent,
+    prelude::*,
+    reflect::{ParsedPath, ReflectMut, ReflectPath, ReflectRef, TypePath},
+};
+
+use crate::blueprint_component_type_id;
+
+/// Why a `BlueprintBinding` could not be created, read or written.
+#[derive(Debug, PartialEq, Eq)]
+pub enum BindingError {
+    /// The reflect path does not parse.
+    InvalidPath(String),
+    /// No `BlueprintPlugin` is registered for the blueprint type path.
+    UnregisteredBlueprint(String),
+    /// The entity does not exist, or has no blueprint of the type.
+    NoBlueprint(Entity),
+    /// The path does not lead to a field of the blueprint.
+    NoField(String),
+    /// The written value does not fit the field.
+    InvalidValue(String),
+}
+
+impl std::fmt::Display for BindingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidPath(error) => write!(f, "invalid reflect path: {error}"),
+            Self::UnregisteredBlueprint(type_path) => {
+                write!(f, "no blueprint plugin is registered for {type_path}")
+            }
+            Self::NoBlueprint(entity) => write!(f, "entity {entity} has no blueprint to bind"),
+            Self::NoField(error) => write!(f, "the blueprint has no such field: {error}"),
+            Self::InvalidValue(error) => write!(f, "the value does not fit the field: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for BindingError {}
+
+/// Binds a field of an entity's blueprint, by its reflect path into the blueprint data (e.g.
+/// `size.x`), so that an editor widget such as a slider can read it every frame and write it back
+/// when dragged.
+///
+/// Writing a different value marks the blueprint as changed, which rebuilds it, while reading
+/// always returns the current value, including changes made elsewhere. The blueprint type is
+/// looked up by type path in the `BlueprintRegistry`, so it must have a `BlueprintPlugin`.
+#[derive(Clone, Debug)]
+pub struct BlueprintBinding {
+    pub entity: Entity,
+    type_path: String,
+    path: ParsedPath,
+}
+
+impl BlueprintBinding {
+    /// Binds the field at `path` of the blueprint of type path `type_path` on `entity`.
+    pub fn new(
+        entity: Entity,
+        type_path: impl Into<String>,
+        path: &str,
+    ) -> Result<Self, BindingError> {
+        let path = ParsedPath::parse(path)
+            .map_err(|error| BindingError::InvalidPath(error.to_string()))?;
+        Ok(BlueprintBinding {
+            entity,
+            type_path: type_path.into(),
+            path,
+        })
+    }
+
+    /// Binds the field at `path` of the entity's `Blueprint<B>`.
+    pub fn of<B: TypePath>(entity: Entity, path: &str) -> Result<Self, BindingError> {
+        Self::new(entity, B::type_path(), path)
+    }
+
+    fn reflect_component(&self, world: &World) -> Result<ReflectComponent, BindingError> {
+        let unregistered = || BindingError::UnregisteredBlueprint(self.type_path.clone());
+        let type_id =
+            blueprint_component_type_id(world, &self.type_path).ok_or_else(unregistered)?;
+        world
+            .resource::<AppTypeRegistry>()
+            .read()
+            .get_type_data::<ReflectComponent>(type_id)
+            .cloned()
+            .ok_or_else(unregistered)
+    }
+
+    /// The current value of the field.
+    pub fn get<'w>(&self, world: &'w World) -> Result<&'w dyn Reflect, BindingError> {
+        let reflect_component = self.reflect_component(world)?;
+        let blueprint = world
+            .get_entity(self.entity)
+            .and_then(|entity| reflect_component.reflect(entity))
+            .ok_or(BindingError::NoBlueprint(self.entity))?;
+        // the data of the `Blueprint<B>` tuple struct
+        let ReflectRef::TupleStruct(blueprint) = blueprint.reflect_ref() else {
+            return Err(BindingError::NoBlueprint(self.entity));
+        };
+        let data = blueprint
+            .field(0)
+            .ok_or(BindingError::NoBlueprint(self.entity))?;
+        (&self.path)
+            .reflect_element(data)
+            .map_err(|error| BindingError::NoField(error.to_string()))
+    }
+
+    /// Writes `value` into the field, returning whether it changed. An equal value (according to
+    /// `Reflect::reflect_partial_eq`) leaves the blueprint untouched, so it is not rebuilt.
+    pub fn set(&self, world: &mut World, value: &dyn Reflect) -> Result<bool, BindingError> {
+        if self.get(world)?.reflect_partial_eq(value) == Some(true) {
+            return Ok(false);
+        }
+        let reflect_component = self.reflect_component(world)?;
+        let mut blueprint = reflect_component
+            .reflect_mut(world.entity_mut(self.entity))
+            .ok_or(BindingError::NoBlueprint(self.entity))?;
+        let ReflectMut::TupleStruct(blueprint) = blueprint.reflect_mut() else {
+            return Err(BindingError::NoBlueprint(self.entity));
+        };
+        let data = blueprint
+            .field_mut(0)
+            .ok_or(BindingError::NoBlueprint(self.entity))?;
+        (&self.path)
+            .reflect_element_mut(data)
+            .map_err(|error| BindingError::NoField(error.to_string()))?
+            .try_apply(value)
+            .map_err(|error| BindingError::InvalidValue(error.to_string()))?;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{ecs::system::StaticSystemParam, prelude::*};
+
+    use super::*;
+    use crate::{Blueprint, BlueprintPlugin, BlueprintsPlugin, FromBlueprint};
+
+    #[derive(Default, Reflect)]
+    struct Rect {
+        size: Vec2,
+    }
+
+    #[derive(Component)]
+    struct RectWidth(f32);
+
+    impl FromBlueprint<Rect> for RectWidth {
+        type Params<'w, 's> = ();
+        fn from_blueprint(
+            blueprint: &Rect,
+            _: &mut StaticSystemParam<Self::Params<'_, '_>>,
+        ) -> Self {
+            RectWidth(blueprint.size.x)
+        }
+    }
+
+    fn width(app: &App, entity: Entity) -> f32 {
+        app.world().get::<RectWidth>(entity).unwrap().0
+    }
+
+    #[test]
+    fn bound_fields_are_read_and_written() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Rect, RectWidth>::default());
+        let entity = app
+            .world_mut()
+            .spawn(Blueprint::new(Rect { size: Vec2::ONE }))
+            .id();
+        app.update();
+        // e.g. a slider
+        let binding = BlueprintBinding::of::<Rect>(entity, "size.x").unwrap();
+        assert_eq!(
+            binding.get(app.world()).unwrap().downcast_ref(),
+            Some(&1_f32)
+        );
+
+        assert_eq!(binding.set(app.world_mut(), &3_f32), Ok(true));
+        app.update();
+        assert_eq!(width(&app, entity), 3.);
+        assert_eq!(binding.set(app.world_mut(), &3_f32), Ok(false));
+
+        // changes made elsewhere are read back
+        app.world_mut()
+            .get_mut::<Blueprint<Rect>>(entity)
+            .unwrap()
+            .0
+            .size
+            .x = 2.;
+        assert_eq!(
+            binding.get(app.world()).unwrap().downcast_ref(),
+            Some(&2_f32)
+        );
+    }
+
+    #[test]
+    fn invalid_bindings_are_errors() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Rect, RectWidth>::default());
+        let entity = app.world_mut().spawn(Blueprint::<Rect>::default()).id();
+        assert!(matches!(
+            BlueprintBinding::of::<Rect>(entity, "size["),
+            Err(BindingError::InvalidPath(_))
+        ));
+        let missing = BlueprintBinding::of::<Rect>(entity, "size.w").unwrap();
+        assert!(matches!(
+            missing.get(app.world()),
+            Err(BindingError::NoField(_))
+        ));
+        let binding = BlueprintBinding::of::<Rect>(entity, "size.x").unwrap();
+        assert!(matches!(
+            binding.set(app.world_mut(), &"wide".to_string()),
+            Err(BindingError::InvalidValue(_))
+        ));
+        let empty = app.world_mut().spawn_empty().id();
+        let binding = BlueprintBinding::of::<Rect>(empty, "size.x").unwrap();
+        assert_eq!(
+            binding.get(app.world()).err(),
+            Some(BindingError::NoBlueprint(empty))
+        );
+    }
+}
@@ -0,0 +1,257 @@
+use std::{cmp::Reverse, marker::PhantomData};
+
+use bevy::{
+    ecs::{entity::EntityHashSet, system::SystemParam},
+    prelude::*,
+};
+
+/// Limits how many blueprints each prefab builds per frame, so that a large rebuild (e.g. a theme
+/// swap changing thousands of blueprints) is spread over several frames instead of causing a
+/// hitch.
+///
+/// Builds over the budget are queued and run in later frames, highest `BlueprintPriority` first,
+/// and otherwise first come, first served. A blueprint that changes again while queued keeps its
+/// place, and is built from its latest value. Members of a `BlueprintTransaction` are not budgeted.
+#[derive(Clone, Copy, Debug, Resource)]
+pub struct BlueprintBudget {
+    pub max_builds_per_frame: usize,
+}
+
+/// How urgently the blueprints of this entity are built under a `BlueprintBudget`, e.g. a high
+/// priority for the player and the UI, so they are built before scenery. Entities without one
+/// have priority 0.
+///
+/// Every frame a build waits in the queue raises its priority by one, so that a steady stream of
+/// high-priority builds cannot hold back the others forever.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Component, Reflect)]
+#[reflect(Component)]
+pub struct BlueprintPriority(pub i32);
+
+// the builds of a plugin that did not fit in the budget, with the number of frames each has
+// waited, in the order they will be built
+#[derive(Resource)]
+pub(crate) struct QueuedBuilds<B, P, T> {
+    queue: Vec<(Entity, u32)>,
+    queued: EntityHashSet,
+    marker: PhantomData<(B, P, T)>,
+}
+
+impl<B, P, T> Default for QueuedBuilds<B, P, T> {
+    fn default() -> Self {
+        Self {
+            queue: Vec::new(),
+            queued: EntityHashSet::default(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<B, P, T> QueuedBuilds<B, P, T> {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+}
+
+#[derive(SystemParam)]
+pub(crate) struct BuildQueue<'w, 's, B, P, T>
+where
+    B: Send + Sync + 'static,
+    P: Send + Sync + 'static,
+    T: Send + Sync + 'static,
+{
+    budget: Option<Res<'w, BlueprintBudget>>,
+    queued: ResMut<'w, QueuedBuilds<B, P, T>>,
+    priorities: Query<'w, 's, &'static BlueprintPriority>,
+}
+
+impl<'w, 's, B, P, T> BuildQueue<'w, 's, B, P, T>
+where
+    B: Send + Sync + 'static,
+    P: Send + Sync + 'static,
+    T: Send + Sync + 'static,
+{
+    /// Queues `builds` and returns the ones to run this frame: the queued builds with the highest
+    /// priority that fit in the budget, and the `unbudgeted` ones. Without a budget, everything is
+    /// built.
+    ///
+    /// Queued builds that are no longer `buildable` (e.g. despawned entities) are dropped first,
+    /// so that they do not take up the budget.
+    pub(crate) fn take(
+        &mut self,
+        mut builds: Vec<Entity>,
+        unbudgeted: impl Fn(Entity) -> bool,
+        buildable: impl Fn(Entity) -> bool,
+    ) -> Vec<Entity> {
+        let queued = &mut *self.queued;
+        let Some(budget) = self.budget.as_deref() else {
+            // the budget was removed while builds were queued
+            builds.extend(queued.queue.drain(..).map(|(entity, _)| entity));
+            queued.queued.clear();
+            return builds;
+        };
+        let (mut now, later) = builds
+            .into_iter()
+            .partition::<Vec<_>, _>(|entity| unbudgeted(*entity));
+        for entity in later {
+            if queued.queued.insert(entity) {
+                queued.queue.push((entity, 0));
+            }
+        }
+        queued.queue.retain(|(entity, _)| {
+            let buildable = buildable(*entity);
+            if !buildable {
+                queued.queued.remove(entity);
+            }
+            buildable
+        });
+        // the sort is stable, and older builds have waited longer, so equal priorities are built
+        // first come, first served
+        queued.queue.sort_by_key(|(entity, waited)| {
+            let priority = self
+                .priorities
+                .get(*entity)
+                .map_or(0, |priority| priority.0);
+            Reverse(priority.saturating_add_unsigned(*waited))
+        });
+        let count = budget.max_builds_per_frame.min(queued.queue.len());
+        for (entity, _) in queued.queue.drain(..count) {
+            queued.queued.remove(&entity);
+            now.push(entity);
+        }
+        for (_, waited) in &mut queued.queue {
+            *waited += 1;
+        }
+        now
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{ecs::system::StaticSystemParam, prelude::*};
+
+    use super::*;
+    use crate::{AsSelf, Blueprint, BlueprintPlugin, BlueprintsPlugin, FromBlueprint};
+
+    #[derive(Default, Reflect)]
+    struct Tile {
+        theme: u32,
+    }
+
+    #[derive(Component)]
+    struct Themed(u32);
+
+    impl FromBlueprint<Tile> for Themed {
+        type Params<'w, 's> = ();
+        fn from_blueprint(
+            blueprint: &Tile,
+            _: &mut StaticSystemParam<Self::Params<'_, '_>>,
+        ) -> Self {
+            Themed(blueprint.theme)
+        }
+    }
+
+    fn themes(app: &App, tiles: &[Entity]) -> Vec<Option<u32>> {
+        tiles
+            .iter()
+            .map(|tile| app.world().get::<Themed>(*tile).map(|themed| themed.0))
+            .collect()
+    }
+
+    #[test]
+    fn budget_spreads_builds_over_frames() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Tile, Themed>::default())
+            .insert_resource(BlueprintBudget {
+                max_builds_per_frame: 2,
+            });
+        let tiles = (0..5)
+            .map(|_| app.world_mut().spawn(Blueprint::<Tile>::default()).id())
+            .collect::<Vec<_>>();
+        for built in [2, 4, 5] {
+            app.update();
+            let themes = themes(&app, &tiles);
+            assert_eq!(themes.iter().filter(|theme| theme.is_some()).count(), built);
+        }
+
+        // a theme swap
+        for tile in &tiles {
+            app.world_mut()
+                .get_mut::<Blueprint<Tile>>(*tile)
+                .unwrap()
+                .0
+                .theme = 1;
+        }
+        app.update();
+        // changing a queued blueprint again keeps its place in the queue
+        app.world_mut()
+            .get_mut::<Blueprint<Tile>>(tiles[4])
+            .unwrap()
+            .0
+            .theme = 2;
+        app.update();
+        assert_eq!(
+            themes(&app, &tiles),
+            vec![Some(1), Some(1), Some(1), Some(1), Some(0)]
+        );
+        app.update();
+        assert_eq!(
+            themes(&app, &tiles),
+            vec![Some(1), Some(1), Some(1), Some(1), Some(2)]
+        );
+    }
+
+    #[test]
+    fn dropped_builds_do_not_take_up_the_budget() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Tile, Themed>::default())
+            .insert_resource(BlueprintBudget {
+                max_builds_per_frame: 2,
+            });
+        let tiles = (0..6)
+            .map(|_| app.world_mut().spawn(Blueprint::<Tile>::default()).id())
+            .collect::<Vec<_>>();
+        app.update();
+        assert_eq!(themes(&app, &tiles[..2]), vec![Some(0), Some(0)]);
+
+        // the next two queued builds are no longer needed
+        app.world_mut().despawn(tiles[2]);
+        app.world_mut()
+            .entity_mut(tiles[3])
+            .remove::<Blueprint<Tile>>();
+        app.update();
+        assert_eq!(themes(&app, &tiles[4..]), vec![Some(0), Some(0)]);
+        assert!(app
+            .world()
+            .resource::<QueuedBuilds<Tile, Themed, AsSelf>>()
+            .is_empty());
+    }
+
+    #[test]
+    fn higher_priorities_build_first() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Tile, Themed>::default())
+            .insert_resource(BlueprintBudget {
+                max_builds_per_frame: 1,
+            });
+        let scenery = app.world_mut().spawn(Blueprint::<Tile>::default()).id();
+        let player = app
+            .world_mut()
+            .spawn((Blueprint::<Tile>::default(), BlueprintPriority(2)))
+            .id();
+        app.update();
+        assert_eq!(themes(&app, &[scenery, player]), vec![None, Some(0)]);
+
+        // urgent builds every frame only hold the scenery back until it has waited long enough
+        let mut frames = 1;
+        while app.world().get::<Themed>(scenery).is_none() {
+            app.world_mut()
+                .spawn((Blueprint::<Tile>::default(), BlueprintPriority(2)));
+            app.update();
+            frames += 1;
+        }
+        assert_eq!(frames, 3);
+    }
+}
@@ -0,0 +1,185 @@
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::{BlueprintSet, BlueprintsPlugin};
+
+/// Builds the target of a `ReflectedBlueprint` from its value, on the given entity.
+pub type BlueprintBuilder = Box<dyn Fn(&dyn Reflect, Entity, &mut World) + Send + Sync>;
+
+/// Blueprint data whose type is only known at runtime, e.g. because a mod defines it. It is built
+/// by the builder registered in `BlueprintBuilderRegistry` for the value's type path.
+///
+/// This is the dynamically dispatched counterpart of `Blueprint<B>` and `BlueprintPlugin`. The
+/// builder runs again whenever the component changes, and should insert (replacing) whatever it
+/// built before; nothing is removed for it.
+#[derive(Debug, Component)]
+pub struct ReflectedBlueprint(pub Box<dyn Reflect>);
+
+impl ReflectedBlueprint {
+    pub fn new(value: impl Reflect) -> Self {
+        ReflectedBlueprint(Box::new(value))
+    }
+
+    /// The type path that selects the builder. For dynamic values (e.g. loaded from a scene),
+    /// this is the path of the type they represent.
+    pub fn type_path(&self) -> &str {
+        blueprint_type_path(self.0.as_ref())
+    }
+}
+
+fn blueprint_type_path(value: &dyn Reflect) -> &str {
+    value
+        .get_represented_type_info()
+        .map(|info| info.type_path())
+        .unwrap_or_else(|| value.reflect_type_path())
+}
+
+/// The builders of `ReflectedBlueprint`s, keyed by the type path of the blueprint value. Builders
+/// can be registered at any time, e.g. as mods are loaded.
+#[derive(Default, Resource)]
+pub struct BlueprintBuilderRegistry {
+    builders: HashMap<String, BlueprintBuilder>,
+}
+
+impl BlueprintBuilderRegistry {
+    /// Registers a builder for blueprint values of `type_path`, replacing any previous builder.
+    /// The builder receives the value as it is, so it must check its type itself.
+    pub fn register(
+        &mut self,
+        type_path: impl Into<String>,
+        builder: impl Fn(&dyn Reflect, Entity, &mut World) + Send + Sync + 'static,
+    ) {
+        self.builders.insert(type_path.into(), Box::new(builder));
+    }
+
+    /// Registers a builder for blueprint values of type `B`. Values that cannot be converted into
+    /// `B` with `FromReflect` are logged as errors instead of being built.
+    pub fn register_typed<B: FromReflect + TypePath>(
+        &mut self,
+        builder: impl Fn(&B, Entity, &mut World) + Send + Sync + 'static,
+    ) {
+        self.register(
+            B::type_path(),
+            move |value, entity, world| match B::from_reflect(value) {
+                Some(blueprint) => builder(&blueprint, entity, world),
+                None => error!(
+                    "blueprint on entity {} does not match the builder for {}",
+                    entity,
+                    B::type_path(),
+                ),
+            },
+        );
+    }
+
+    pub fn contains(&self, type_path: &str) -> bool {
+        self.builders.contains_key(type_path)
+    }
+}
+
+fn build_reflected_blueprints(
+    world: &mut World,
+    changed: &mut QueryState<(Entity, &ReflectedBlueprint), Changed<ReflectedBlueprint>>,
+) {
+    let blueprints = changed
+        .iter(world)
+        .map(|(entity, blueprint)| (entity, blueprint.0.clone_value()))
+        .collect::<Vec<_>>();
+    if blueprints.is_empty() {
+        return;
+    }
+    world.resource_scope(|world, registry: Mut<BlueprintBuilderRegistry>| {
+        for (entity, blueprint) in blueprints {
+            let type_path = blueprint_type_path(blueprint.as_ref());
+            match registry.builders.get(type_path) {
+                Some(builder) => builder(blueprint.as_ref(), entity, world),
+                None => {
+                    warn!("no builder is registered for blueprint {type_path} on entity {entity}")
+                }
+            }
+        }
+    });
+}
+
+/// Builds `ReflectedBlueprint`s through the `BlueprintBuilderRegistry`.
+pub struct BlueprintBuilderPlugin;
+
+impl Plugin for BlueprintBuilderPlugin {
+    fn build(&self, app: &mut App) {
+        BlueprintsPlugin::add_once(app);
+        app.init_resource::<BlueprintBuilderRegistry>().add_systems(
+            Update,
+            build_reflected_blueprints.in_set(BlueprintSet::Sync),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::reflect::{DynamicStruct, Typed};
+
+    use super::*;
+    use crate::BlueprintsPlugin;
+
+    #[derive(Default, Reflect)]
+    struct Portal {
+        destination: String,
+    }
+
+    #[derive(Component)]
+    struct PortalDestination(String);
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin, BlueprintBuilderPlugin));
+        app.update();
+        // registered at runtime, as a mod would
+        app.world_mut()
+            .resource_mut::<BlueprintBuilderRegistry>()
+            .register_typed::<Portal>(|portal, entity, world| {
+                world
+                    .entity_mut(entity)
+                    .insert(PortalDestination(portal.destination.clone()));
+            });
+        app
+    }
+
+    fn destination(app: &App, entity: Entity) -> Option<&str> {
+        app.world()
+            .get::<PortalDestination>(entity)
+            .map(|destination| destination.0.as_str())
+    }
+
+    #[test]
+    fn registered_builder_builds_blueprints() {
+        let mut app = app();
+        let entity = app
+            .world_mut()
+            .spawn(ReflectedBlueprint::new(Portal {
+                destination: "dungeon".to_string(),
+            }))
+            .id();
+        app.update();
+        assert_eq!(destination(&app, entity), Some("dungeon"));
+
+        app.world_mut()
+            .get_mut::<ReflectedBlueprint>(entity)
+            .unwrap()
+            .0
+            .apply(&Portal {
+                destination: "town".to_string(),
+            });
+        app.update();
+        assert_eq!(destination(&app, entity), Some("town"));
+    }
+
+    #[test]
+    fn mismatched_values_are_not_built() {
+        let mut app = app();
+        // claims to be a portal, but its destination is a number
+        let mut value = DynamicStruct::default();
+        value.insert("destination", 3u32);
+        value.set_represented_type(Some(<Portal as Typed>::type_info()));
+        let entity = app.world_mut().spawn(ReflectedBlueprint::new(value)).id();
+        app.update();
+        assert_eq!(destination(&app, entity), None);
+    }
+}
@@ -0,0 +1,146 @@
+use std::marker::PhantomData;
+
+use bevy::{
+    ecs::system::{StaticSystemParam, SystemState},
+    prelude::*,
+    utils::get_short_name,
+};
+
+use crate::{AsSelf, BlueprintSet, BlueprintTarget, BlueprintsPlugin, Removals, TryFromBlueprint};
+
+/// Builds `P` from a plain component `B`, for source data that is a meaningful gameplay
+/// component of its own rather than a `Blueprint<B>`.
+///
+/// The prefab is rebuilt whenever `B` changes, and cleaned up when `B` is removed just like a
+/// removed `Blueprint<B>`. The other `BlueprintPlugin` features (lazy building, transactions,
+/// dependencies, budgets, ...) are not available for component blueprints.
+pub struct ComponentBlueprintPlugin<B, P, T = AsSelf> {
+    blueprint_marker: PhantomData<B>,
+    prefab_marker: PhantomData<P>,
+    target_marker: PhantomData<T>,
+}
+
+impl<B, P, T> Default for ComponentBlueprintPlugin<B, P, T> {
+    fn default() -> Self {
+        Self {
+            blueprint_marker: PhantomData::<B>,
+            prefab_marker: PhantomData::<P>,
+            target_marker: PhantomData::<T>,
+        }
+    }
+}
+
+impl<B, P, T> ComponentBlueprintPlugin<B, P, T>
+where
+    B: Component,
+    P: Bundle + TryFromBlueprint<B>,
+    T: BlueprintTarget + Send + Sync + 'static,
+{
+    fn sync_components(
+        mut commands: Commands,
+        changed: Query<(Entity, &B), Changed<B>>,
+        mut system_params: StaticSystemParam<P::Params<'_, '_>>,
+    ) {
+        for (entity, blueprint) in changed.iter() {
+            let mut entity_commands = commands.entity(entity);
+            T::remove_prefab::<B, P>(&mut entity_commands);
+            match P::try_from_blueprint(blueprint, &mut system_params) {
+                Ok(bundle) => {
+                    T::attach_prefab::<B, P>(&mut entity_commands, bundle);
+                }
+                Err(error) => error!(
+                    "component blueprint {} on entity {} failed to build {}: {}",
+                    get_short_name(std::any::type_name::<B>()),
+                    entity,
+                    get_short_name(std::any::type_name::<P>()),
+                    error,
+                ),
+            }
+        }
+    }
+
+    fn handle_removed_components(mut commands: Commands, mut removed: Removals<B, (P, T)>) {
+        let entities = removed.read();
+        if entities.is_empty() {
+            return;
+        }
+        // as for `Blueprint<B>`, whether the entity still exists is decided once commands apply
+        commands.add(move |world: &mut World| {
+            let entities = entities
+                .into_iter()
+                .map(|entity| (entity, world.get_entity(entity).is_some()))
+                .collect::<Vec<_>>();
+            let mut state = SystemState::<(Commands, Query<(Entity, &Parent)>)>::new(world);
+            let (mut commands, child_query) = state.get_mut(world);
+            for (entity, exists) in entities {
+                if exists {
+                    T::remove_prefab::<B, P>(&mut commands.entity(entity));
+                } else {
+                    T::cleanup_despawned_prefabs(&mut commands, entity, &child_query);
+                }
+            }
+            state.apply(world);
+        });
+    }
+}
+
+impl<B, P, T> Plugin for ComponentBlueprintPlugin<B, P, T>
+where
+    B: Component,
+    P: Bundle + TryFromBlueprint<B>,
+    T: BlueprintTarget + Send + Sync + 'static,
+{
+    fn build(&self, app: &mut App) {
+        BlueprintsPlugin::add_once(app);
+        Removals::<B, (P, T)>::buffer_while_paused(app);
+        app.add_systems(
+            Update,
+            (
+                Self::handle_removed_components.in_set(BlueprintSet::Cleanup),
+                Self::sync_components.in_set(BlueprintSet::Sync),
+            ),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use super::*;
+    use crate::FromBlueprint;
+
+    #[derive(Component)]
+    struct Health(u32);
+
+    #[derive(Component, Debug, PartialEq)]
+    struct HealthBar(f32);
+
+    impl FromBlueprint<Health> for HealthBar {
+        type Params<'w, 's> = ();
+        fn from_blueprint(
+            blueprint: &Health,
+            _: &mut StaticSystemParam<Self::Params<'_, '_>>,
+        ) -> Self {
+            HealthBar(blueprint.0 as f32 / 100.)
+        }
+    }
+
+    #[test]
+    fn component_changes_rebuild() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(ComponentBlueprintPlugin::<Health, HealthBar>::default());
+        let entity = app.world_mut().spawn(Health(100)).id();
+        app.update();
+        assert_eq!(app.world().get::<HealthBar>(entity), Some(&HealthBar(1.)));
+
+        app.world_mut().get_mut::<Health>(entity).unwrap().0 = 50;
+        app.update();
+        assert_eq!(app.world().get::<HealthBar>(entity), Some(&HealthBar(0.5)));
+
+        app.world_mut().entity_mut(entity).remove::<Health>();
+        app.update();
+        assert_eq!(app.world().get::<HealthBar>(entity), None);
+    }
+}
@@ -0,0 +1,249 @@
+use std::marker::PhantomData;
+
+use bevy::{
+    ecs::system::{StaticSystemParam, SystemParam, SystemState},
+    prelude::*,
+};
+
+use crate::{
+    insert_spatial_parent, Blueprint, BlueprintRegistry, BlueprintSet, BlueprintSource,
+    BlueprintsPlugin, IsBlueprint, PrefabChild, Removals,
+};
+
+/// Builds a bundle for the blueprint entity itself and a list of bundles for its children from
+/// blueprint data of type `T`, in one call, so that both can share the values computed for them.
+/// Built by a `CompositeBlueprintPlugin`.
+pub trait FromBlueprintComposite<T>: Sized {
+    type Params<'w, 's>: SystemParam;
+    type Child: Bundle;
+
+    fn from_blueprint_composite(
+        blueprint: &T,
+        params: &mut StaticSystemParam<Self::Params<'_, '_>>,
+    ) -> (Self, Vec<Self::Child>);
+}
+
+/// Inserts the bundle `P` built by `FromBlueprintComposite` on the blueprint entity, and spawns its
+/// child bundles as children of it.
+///
+/// On a rebuild, the first children are updated in place, and children are spawned or despawned
+/// as the number of child bundles changes. Removing the blueprint removes `P` and despawns the
+/// children. The other `BlueprintPlugin` features are not available for composite blueprints.
+pub struct CompositeBlueprintPlugin<B, P> {
+    blueprint_marker: PhantomData<B>,
+    prefab_marker: PhantomData<P>,
+}
+
+impl<B, P> Default for CompositeBlueprintPlugin<B, P> {
+    fn default() -> Self {
+        Self {
+            blueprint_marker: PhantomData::<B>,
+            prefab_marker: PhantomData::<P>,
+        }
+    }
+}
+
+type CompositeQuery<'w, 's, B> = Query<
+    'w,
+    's,
+    (Entity, &'static Blueprint<B>, Option<&'static Children>),
+    Changed<Blueprint<B>>,
+>;
+
+impl<B, P> CompositeBlueprintPlugin<B, P>
+where
+    B: Default + Send + Sync + 'static,
+    P: Bundle + FromBlueprintComposite<B>,
+{
+    fn sync_composites(
+        mut commands: Commands,
+        changed: CompositeQuery<B>,
+        prefab_children: Query<(), With<PrefabChild<B, P>>>,
+        mut system_params: StaticSystemParam<P::Params<'_, '_>>,
+    ) {
+        for (entity, blueprint, children) in changed.iter() {
+            let (bundle, child_bundles) =
+                P::from_blueprint_composite(&blueprint.0, &mut system_params);
+            let mut previous = children
+                .into_iter()
+                .flatten()
+                .copied()
+                .filter(|child| prefab_children.contains(*child));
+            for child_bundle in child_bundles {
+                match previous.next() {
+                    Some(child) => {
+                        commands.entity(child).insert(child_bundle);
+                    }
+                    None => {
+                        commands
+                            .spawn((
+                                child_bundle,
+                                BlueprintSource(entity),
+                                PrefabChild::<B, P>::default(),
+                            ))
+                            .set_parent(entity);
+                    }
+                }
+            }
+            // the child count shrank
+            for child in previous {
+                commands.entity(child).despawn_recursive();
+            }
+            commands
+                .entity(entity)
+                .insert((IsBlueprint, bundle))
+                .add(insert_spatial_parent);
+        }
+    }
+
+    fn handle_removed_composites(
+        mut commands: Commands,
+        mut removed: Removals<Blueprint<B>, PrefabChild<B, P>>,
+    ) {
+        let entities = removed.read();
+        if entities.is_empty() {
+            return;
+        }
+        // as for `Blueprint<B>`, whether the entity still exists is decided once commands apply
+        commands.add(move |world: &mut World| {
+            let mut state = SystemState::<(
+                Commands,
+                Query<(Entity, &BlueprintSource), With<PrefabChild<B, P>>>,
+            )>::new(world);
+            let (mut commands, prefab_children) = state.get_mut(world);
+            for (child, source) in prefab_children.iter() {
+                if entities.contains(&source.0) {
+                    commands.entity(child).despawn_recursive();
+                }
+            }
+            for entity in entities {
+                if let Some(mut entity_commands) = commands.get_entity(entity) {
+                    entity_commands.remove::<(IsBlueprint, P)>();
+                }
+            }
+            state.apply(world);
+        });
+    }
+}
+
+impl<B, P> Plugin for CompositeBlueprintPlugin<B, P>
+where
+    B: Default + Send + Sync + 'static,
+    P: Bundle + FromBlueprintComposite<B>,
+{
+    fn build(&self, app: &mut App) {
+        BlueprintsPlugin::add_once(app);
+        Removals::<Blueprint<B>, PrefabChild<B, P>>::buffer_while_paused(app);
+        // built without a `BlueprintPlugin`
+        app.world_mut()
+            .resource_mut::<BlueprintRegistry>()
+            .ignore::<B>();
+        app.add_systems(
+            Update,
+            (
+                Self::handle_removed_composites.in_set(BlueprintSet::Cleanup),
+                Self::sync_composites.in_set(BlueprintSet::Sync),
+            ),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use super::*;
+
+    #[derive(Default, Reflect)]
+    struct Tower {
+        floors: usize,
+        floor_height: f32,
+    }
+
+    #[derive(Component, Debug, PartialEq)]
+    struct TowerHeight(f32);
+
+    #[derive(Component, Debug, PartialEq)]
+    struct Floor(f32);
+
+    impl FromBlueprintComposite<Tower> for TowerHeight {
+        type Params<'w, 's> = ();
+        type Child = Floor;
+
+        fn from_blueprint_composite(
+            blueprint: &Tower,
+            _: &mut StaticSystemParam<Self::Params<'_, '_>>,
+        ) -> (Self, Vec<Floor>) {
+            let floors = (0..blueprint.floors)
+                .map(|floor| Floor(floor as f32 * blueprint.floor_height))
+                .collect::<Vec<_>>();
+            let height = floors.len() as f32 * blueprint.floor_height;
+            (TowerHeight(height), floors)
+        }
+    }
+
+    fn floors(app: &mut App, entity: Entity) -> Vec<(Entity, f32)> {
+        let mut query = app.world_mut().query::<(Entity, &Floor, &Parent)>();
+        let mut floors = query
+            .iter(app.world())
+            .filter(|(_, _, parent)| parent.get() == entity)
+            .map(|(child, floor, _)| (child, floor.0))
+            .collect::<Vec<_>>();
+        floors.sort_by(|a, b| a.1.total_cmp(&b.1));
+        floors
+    }
+
+    #[test]
+    fn composite_builds_self_and_children() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(CompositeBlueprintPlugin::<Tower, TowerHeight>::default());
+        let entity = app
+            .world_mut()
+            .spawn(Blueprint::new(Tower {
+                floors: 3,
+                floor_height: 2.,
+            }))
+            .id();
+        app.update();
+        assert_eq!(
+            app.world().get::<TowerHeight>(entity),
+            Some(&TowerHeight(6.))
+        );
+        let built = floors(&mut app, entity);
+        assert_eq!(
+            built.iter().map(|(_, y)| *y).collect::<Vec<_>>(),
+            vec![0., 2., 4.]
+        );
+
+        // fewer floors despawn the surplus, and the others are updated in place
+        app.world_mut()
+            .get_mut::<Blueprint<Tower>>(entity)
+            .unwrap()
+            .0
+            .floors = 2;
+        app.update();
+        assert_eq!(
+            app.world().get::<TowerHeight>(entity),
+            Some(&TowerHeight(4.))
+        );
+        let rebuilt = floors(&mut app, entity);
+        assert_eq!(rebuilt, built[..2]);
+        assert!(app.world().get_entity(built[2].0).is_none());
+
+        app.world_mut()
+            .get_mut::<Blueprint<Tower>>(entity)
+            .unwrap()
+            .0
+            .floors = 4;
+        app.update();
+        assert_eq!(floors(&mut app, entity).len(), 4);
+
+        app.world_mut()
+            .entity_mut(entity)
+            .remove::<Blueprint<Tower>>();
+        app.update();
+        assert!(app.world().get::<TowerHeight>(entity).is_none());
+        assert!(floors(&mut app, entity).is_empty());
+    }
+}
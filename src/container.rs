@@ -0,0 +1,226 @@
+use std::marker::PhantomData;
+
+use bevy::{
+    ecs::{
+        component::{ComponentHooks, StorageType},
+        system::EntityCommands,
+    },
+    prelude::*,
+};
+
+use crate::{
+    rebuild_prefabs, AsChild, BlueprintSource, BlueprintTarget, IsBlueprint, PrefabChild,
+    TargetKind, TryFromBlueprint,
+};
+
+/// The container child that `AsContainerChild<Self>` prefabs are built under.
+///
+/// The container is spawned with `Self::default()` and a `Name` of `NAME`, so a bundle (e.g. a UI
+/// node) can be used to style and position it independently of the blueprint entity.
+pub trait BlueprintContainer: Bundle + Default {
+    const NAME: &'static str;
+}
+
+/// Builds the prefab as a child of a `C` container, which is itself a child of the blueprint
+/// entity, rather than directly under the blueprint entity.
+///
+/// The container is created by the first build and kept across rebuilds, and is shared by every
+/// prefab of the entity using `C`. If it is despawned, the entity's blueprints are rebuilt, which
+/// recreates it.
+pub struct AsContainerChild<C>(PhantomData<C>);
+
+/// Marks the container spawned for `AsContainerChild<C>` prefabs.
+#[derive(Debug)]
+pub struct PrefabContainer<C>(PhantomData<C>);
+
+impl<C> Default for PrefabContainer<C> {
+    fn default() -> Self {
+        PrefabContainer(PhantomData)
+    }
+}
+
+impl<C: Send + Sync + 'static> Component for PrefabContainer<C> {
+    const STORAGE_TYPE: StorageType = StorageType::Table;
+
+    // a despawned container (and the prefabs in it) is rebuilt with the blueprint entity's
+    // blueprints, unless the blueprint entity is being despawned too
+    fn register_component_hooks(hooks: &mut ComponentHooks) {
+        hooks.on_remove(|mut world, entity, _| {
+            let Some(parent) = world.get::<Parent>(entity).map(Parent::get) else {
+                return;
+            };
+            world.commands().add(move |world: &mut World| {
+                if world.get_entity(parent).is_none() {
+                    return;
+                }
+                rebuild_prefabs(world, parent);
+            });
+        });
+    }
+}
+
+fn containers<C: Send + Sync + 'static>(world: &World, entity: Entity) -> Vec<Entity> {
+    world
+        .get::<Children>(entity)
+        .map(|children| {
+            children
+                .iter()
+                .copied()
+                .filter(|child| world.entity(*child).contains::<PrefabContainer<C>>())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+impl<C: BlueprintContainer> BlueprintTarget for AsContainerChild<C> {
+    const KIND: TargetKind = TargetKind::AsContainerChild;
+
+    fn remove_prefab<T: Send + Sync + 'static, P: Bundle + TryFromBlueprint<T>>(
+        entity: &mut EntityCommands,
+    ) {
+        entity.remove::<IsBlueprint>();
+        // only this prefab's children, leaving the container in place
+        entity.add(|entity: Entity, world: &mut World| {
+            let prefab_children = containers::<C>(world, entity)
+                .into_iter()
+                .filter_map(|container| world.get::<Children>(container))
+                .flat_map(|children| children.iter().copied())
+                .filter(|child| world.entity(*child).contains::<PrefabChild<T, P>>())
+                .collect::<Vec<_>>();
+            for child in prefab_children {
+                world.entity_mut(child).despawn_recursive();
+            }
+        });
+    }
+
+    fn attach_prefab<T: Send + Sync + 'static, P: Bundle + TryFromBlueprint<T>>(
+        entity: &mut EntityCommands,
+        bundle: P,
+    ) -> Option<Entity> {
+        let parent = entity.id();
+        entity.insert(IsBlueprint);
+        let child = entity
+            .commands()
+            .spawn((
+                bundle,
+                BlueprintSource(parent),
+                PrefabChild::<T, P>::default(),
+            ))
+            .id();
+        entity.add(move |entity: Entity, world: &mut World| {
+            let container = match containers::<C>(world, entity).first() {
+                Some(container) => *container,
+                None => world
+                    .spawn((
+                        C::default(),
+                        Name::new(C::NAME),
+                        PrefabContainer::<C>::default(),
+                    ))
+                    .set_parent(entity)
+                    .id(),
+            };
+            world.entity_mut(child).set_parent(container);
+        });
+        Some(child)
+    }
+
+    // despawns the orphaned container along with the prefabs in it
+    fn cleanup_despawned_prefabs(
+        commands: &mut Commands,
+        entity: Entity,
+        query: &Query<(Entity, &Parent)>,
+    ) -> usize {
+        AsChild::cleanup_despawned_prefabs(commands, entity, query)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{ecs::system::StaticSystemParam, prelude::*};
+
+    use super::*;
+    use crate::{Blueprint, BlueprintPlugin, BlueprintsPlugin, FromBlueprint};
+
+    #[derive(Default, Reflect)]
+    struct Label {
+        text: String,
+    }
+
+    #[derive(Component)]
+    struct Text(String);
+
+    impl FromBlueprint<Label> for Text {
+        type Params<'w, 's> = ();
+        fn from_blueprint(
+            blueprint: &Label,
+            _: &mut StaticSystemParam<Self::Params<'_, '_>>,
+        ) -> Self {
+            Text(blueprint.text.clone())
+        }
+    }
+
+    #[derive(Component, Default)]
+    struct Content;
+
+    impl BlueprintContainer for Content {
+        const NAME: &'static str = "content";
+    }
+
+    fn container(app: &App, entity: Entity) -> Entity {
+        let children = app.world().get::<Children>(entity).unwrap();
+        assert_eq!(children.len(), 1);
+        let container = children[0];
+        assert!(app.world().entity(container).contains::<Content>());
+        assert_eq!(
+            app.world().get::<Name>(container).unwrap().as_str(),
+            "content"
+        );
+        container
+    }
+
+    fn texts(app: &App, container: Entity) -> Vec<&str> {
+        app.world()
+            .get::<Children>(container)
+            .map(|children| {
+                children
+                    .iter()
+                    .filter_map(|child| app.world().get::<Text>(*child))
+                    .map(|text| text.0.as_str())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn prefabs_nest_under_container() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Label, Text, AsContainerChild<Content>>::default());
+        let entity = app
+            .world_mut()
+            .spawn(Blueprint::new(Label {
+                text: "hello".to_string(),
+            }))
+            .id();
+        app.update();
+        let content = container(&app, entity);
+        assert_eq!(texts(&app, content), vec!["hello"]);
+
+        // rebuilding keeps the container
+        app.world_mut()
+            .get_mut::<Blueprint<Label>>(entity)
+            .unwrap()
+            .0
+            .text = "goodbye".to_string();
+        app.update();
+        assert_eq!(container(&app, entity), content);
+        assert_eq!(texts(&app, content), vec!["goodbye"]);
+
+        // a despawned container is recreated
+        app.world_mut().entity_mut(content).despawn_recursive();
+        app.update();
+        let recreated = container(&app, entity);
+        assert_ne!(recreated, content);
+        assert_eq!(texts(&app, recreated), vec!["goodbye"]);
+    }
+}
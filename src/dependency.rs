@@ -0,0 +1,269 @@
+use bevy::{
+    ecs::{
+        entity::{EntityHashMap, EntityHashSet, EntityMapper, MapEntities},
+        reflect::ReflectMapEntities,
+    },
+    prelude::*,
+};
+
+use crate::{rebuild_prefabs, BlueprintRegistry};
+
+/// The entities whose blueprints must be built before this entity's blueprints, e.g. because
+/// its prefab reads components built on them.
+///
+/// The entity's blueprints wait (without being built) until none of these entities has a
+/// blueprint left to build. A dependency whose blueprint fails to build, or is lazy and out of
+/// view, keeps its dependents waiting. The entities are mapped when loaded from a scene.
+///
+/// Dependency cycles are broken with a warning, and the entities in them build in arbitrary
+/// order.
+#[derive(Clone, Debug, Default, Component, Reflect)]
+#[reflect(Component, MapEntities)]
+pub struct BlueprintDependsOn(pub Vec<Entity>);
+
+impl MapEntities for BlueprintDependsOn {
+    fn map_entities<M: EntityMapper>(&mut self, entity_mapper: &mut M) {
+        for entity in &mut self.0 {
+            *entity = entity_mapper.map_entity(*entity);
+        }
+    }
+}
+
+/// The blueprint entities that are waiting for their `BlueprintDependsOn` entities to build.
+#[derive(Debug, Default, Resource)]
+pub struct BlueprintDependencies {
+    waiting: EntityHashSet,
+    // entities already warned about, so that a cycle is only reported once
+    cycles: EntityHashSet,
+}
+
+impl BlueprintDependencies {
+    pub fn is_waiting(&self, entity: Entity) -> bool {
+        self.waiting.contains(&entity)
+    }
+
+    // stops waiting, for `bake_blueprints`
+    pub(crate) fn clear(&mut self) {
+        self.waiting.clear();
+    }
+}
+
+enum Visit {
+    InProgress,
+    Done(bool),
+}
+
+// whether `entity` waits on an unbuilt dependency, directly or through its dependencies
+fn visit(
+    entity: Entity,
+    dependencies: &EntityHashMap<Vec<Entity>>,
+    pending: &impl Fn(Entity) -> bool,
+    visits: &mut EntityHashMap<Visit>,
+    cycles: &mut EntityHashSet,
+) -> bool {
+    if let Some(Visit::Done(waiting)) = visits.get(&entity) {
+        return *waiting;
+    }
+    let Some(entity_dependencies) = dependencies.get(&entity) else {
+        return false;
+    };
+    visits.insert(entity, Visit::InProgress);
+    let mut waiting = false;
+    for dependency in entity_dependencies {
+        // the edge back into a cycle is ignored, so some entity in the cycle can build
+        if let Some(Visit::InProgress) = visits.get(dependency) {
+            if cycles.insert(*dependency) {
+                warn!(
+                    "blueprint dependency cycle through {dependency}; building it in arbitrary order"
+                );
+            }
+            continue;
+        }
+        // keep visiting after finding one, so that every cycle is reported
+        waiting |= pending(*dependency);
+        waiting |= visit(*dependency, dependencies, pending, visits, cycles);
+    }
+    visits.insert(entity, Visit::Done(waiting));
+    waiting
+}
+
+pub(crate) fn dependencies_changed(
+    dependencies: Res<BlueprintDependencies>,
+    changed: Query<(), Changed<BlueprintDependsOn>>,
+) -> bool {
+    !dependencies.waiting.is_empty() || !changed.is_empty()
+}
+
+/// Decides which blueprint entities wait for their dependencies this frame, and requests the
+/// build of the blueprints of entities that stopped waiting.
+pub(crate) fn order_blueprint_dependencies(world: &mut World) {
+    let dependencies = world
+        .query::<(Entity, &BlueprintDependsOn)>()
+        .iter(world)
+        .map(|(entity, depends_on)| (entity, depends_on.0.clone()))
+        .collect::<EntityHashMap<_>>();
+    let registrations = world
+        .get_resource::<BlueprintRegistry>()
+        .map(|registry| registry.iter().cloned().collect::<Vec<_>>())
+        .unwrap_or_default();
+    let mut cycles = std::mem::take(&mut world.resource_mut::<BlueprintDependencies>().cycles);
+    cycles.retain(|entity| dependencies.contains_key(entity));
+    let pending = |entity| {
+        registrations
+            .iter()
+            .any(|registration| (registration.pending)(world, entity))
+    };
+
+    let mut visits = EntityHashMap::default();
+    let waiting = dependencies
+        .keys()
+        .copied()
+        .filter(|entity| visit(*entity, &dependencies, &pending, &mut visits, &mut cycles))
+        .collect::<EntityHashSet>();
+
+    let mut state = world.resource_mut::<BlueprintDependencies>();
+    let ready = state
+        .waiting
+        .difference(&waiting)
+        .copied()
+        .collect::<Vec<_>>();
+    state.waiting = waiting;
+    state.cycles = cycles;
+    for entity in ready {
+        rebuild_prefabs(world, entity);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{ecs::system::StaticSystemParam, prelude::*};
+
+    use super::*;
+    use crate::{Blueprint, BlueprintPlugin, BlueprintsPlugin, FromBlueprint};
+
+    #[derive(Default, Reflect)]
+    struct Node {
+        depth: u32,
+    }
+
+    #[derive(Component)]
+    struct NodeMesh;
+
+    // records the order that nodes are built in
+    impl FromBlueprint<Node> for NodeMesh {
+        type Params<'w, 's> = ResMut<'w, BuildLog>;
+        fn from_blueprint(
+            blueprint: &Node,
+            log: &mut StaticSystemParam<Self::Params<'_, '_>>,
+        ) -> Self {
+            log.0.push(blueprint.depth);
+            NodeMesh
+        }
+    }
+
+    #[derive(Default, Resource)]
+    struct BuildLog(Vec<u32>);
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Node, NodeMesh>::default())
+            .init_resource::<BuildLog>();
+        app
+    }
+
+    #[test]
+    fn dependencies_build_first() {
+        let mut app = app();
+        // spawned in reverse, so that an unordered build would start with the leaf
+        let leaf = app
+            .world_mut()
+            .spawn((
+                Blueprint::new(Node { depth: 2 }),
+                BlueprintDependsOn(vec![]),
+            ))
+            .id();
+        let middle = app
+            .world_mut()
+            .spawn((
+                Blueprint::new(Node { depth: 1 }),
+                BlueprintDependsOn(vec![]),
+            ))
+            .id();
+        let root = app
+            .world_mut()
+            .spawn(Blueprint::new(Node { depth: 0 }))
+            .id();
+        app.world_mut()
+            .get_mut::<BlueprintDependsOn>(leaf)
+            .unwrap()
+            .0
+            .push(middle);
+        app.world_mut()
+            .get_mut::<BlueprintDependsOn>(middle)
+            .unwrap()
+            .0
+            .push(root);
+
+        app.update();
+        assert_eq!(app.world().resource::<BuildLog>().0, vec![0]);
+        app.update();
+        assert_eq!(app.world().resource::<BuildLog>().0, vec![0, 1]);
+        app.update();
+        assert_eq!(app.world().resource::<BuildLog>().0, vec![0, 1, 2]);
+        app.update();
+        assert_eq!(app.world().resource::<BuildLog>().0, vec![0, 1, 2]);
+        assert!(!app
+            .world()
+            .resource::<BlueprintDependencies>()
+            .is_waiting(leaf));
+    }
+
+    #[test]
+    fn added_only_dependents_are_built() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Node, NodeMesh>::default().added_only())
+            .init_resource::<BuildLog>();
+        let root = app
+            .world_mut()
+            .spawn(Blueprint::new(Node { depth: 0 }))
+            .id();
+        let leaf = app
+            .world_mut()
+            .spawn((
+                Blueprint::new(Node { depth: 1 }),
+                BlueprintDependsOn(vec![root]),
+            ))
+            .id();
+        app.update();
+        assert_eq!(app.world().resource::<BuildLog>().0, vec![0]);
+        app.update();
+        assert_eq!(app.world().resource::<BuildLog>().0, vec![0, 1]);
+        assert!(app.world().get::<NodeMesh>(leaf).is_some());
+    }
+
+    #[test]
+    fn dependency_cycles_are_broken() {
+        let mut app = app();
+        let first = app
+            .world_mut()
+            .spawn(Blueprint::new(Node { depth: 0 }))
+            .id();
+        let second = app
+            .world_mut()
+            .spawn((
+                Blueprint::new(Node { depth: 0 }),
+                BlueprintDependsOn(vec![first]),
+            ))
+            .id();
+        app.world_mut()
+            .entity_mut(first)
+            .insert(BlueprintDependsOn(vec![second]));
+        for _ in 0..3 {
+            app.update();
+        }
+        assert!(app.world().get::<NodeMesh>(first).is_some());
+        assert!(app.world().get::<NodeMesh>(second).is_some());
+    }
+}
@@ -0,0 +1,165 @@
+use std::any::TypeId;
+
+use bevy::{ecs::component::ComponentId, prelude::*, utils::HashMap};
+
+use crate::BlueprintSource;
+
+// the components inserted on this entity by each prefab built on (or for) it, keyed by the
+// `TypeId` of `(B, P, T)`
+#[derive(Component, Default)]
+pub(crate) struct BuiltComponents(HashMap<TypeId, Vec<ComponentId>>);
+
+// records the components of the prefab `P` of blueprint `B` (with target `T`), once it is attached to the entity
+pub(crate) fn record_components<B: 'static, P: Bundle, T: 'static>(
+    entity: Entity,
+    world: &mut World,
+) {
+    let components = world.init_bundle::<P>().components().to_vec();
+    let Some(mut entity_mut) = world.get_entity_mut(entity) else {
+        return;
+    };
+    match entity_mut.get_mut::<BuiltComponents>() {
+        Some(mut built) => {
+            built.0.insert(TypeId::of::<(B, P, T)>(), components);
+        }
+        None => {
+            let mut built = BuiltComponents::default();
+            built.0.insert(TypeId::of::<(B, P, T)>(), components);
+            entity_mut.insert(built);
+        }
+    }
+}
+
+// forgets the components of the prefab `P` of blueprint `B` when its target is removed
+pub(crate) fn forget_components<B: 'static, P: Bundle, T: 'static>(
+    entity: Entity,
+    world: &mut World,
+) {
+    if let Some(mut built) = world.get_mut::<BuiltComponents>(entity) {
+        built.0.remove(&TypeId::of::<(B, P, T)>());
+    }
+}
+
+// the targets of the prefab `P` of blueprint `B` (with target `T`) built for this entity, with the components it
+// inserted on them
+pub(crate) fn built_targets<B: 'static, P: 'static, T: 'static>(
+    world: &World,
+    entity: Entity,
+) -> Vec<(Entity, Vec<ComponentId>)> {
+    blueprint_targets(world, entity)
+        .into_iter()
+        .filter_map(|target| {
+            let components = target
+                .get::<BuiltComponents>()?
+                .0
+                .get(&TypeId::of::<(B, P, T)>())?;
+            Some((target.id(), components.clone()))
+        })
+        .collect()
+}
+
+// the entity and its descendants spawned for it, such as `AsChild` and `AsContainerChild` targets
+fn blueprint_targets(world: &World, entity: Entity) -> Vec<EntityRef<'_>> {
+    let Some(entity_ref) = world.get_entity(entity) else {
+        return Vec::new();
+    };
+    let mut targets = vec![entity_ref];
+    let mut stack = vec![entity_ref];
+    while let Some(parent) = stack.pop() {
+        for child in parent.get::<Children>().into_iter().flatten() {
+            let Some(child) = world.get_entity(*child) else {
+                continue;
+            };
+            stack.push(child);
+            if child.get::<BlueprintSource>() == Some(&BlueprintSource(entity)) {
+                targets.push(child);
+            }
+        }
+    }
+    targets
+}
+
+/// Lists the type names of the components that blueprints currently provide on the entity,
+/// including those of the children spawned for it (e.g. by `AsChild` prefabs), e.g. for a debug
+/// console.
+///
+/// Components that were removed from the target since it was built are left out. The names are
+/// sorted, and a component provided by several prefabs is listed once.
+pub fn describe_blueprint(world: &World, entity: Entity) -> Vec<&str> {
+    let mut names = blueprint_targets(world, entity)
+        .into_iter()
+        .flat_map(|target| {
+            target
+                .get::<BuiltComponents>()
+                .into_iter()
+                .flat_map(|built| built.0.values().flatten())
+                .filter(move |component_id| target.contains_id(**component_id))
+        })
+        .filter_map(|component_id| world.components().get_info(*component_id))
+        .map(|info| info.name())
+        .collect::<Vec<_>>();
+    names.sort_unstable();
+    names.dedup();
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{ecs::system::StaticSystemParam, prelude::*};
+
+    use super::*;
+    use crate::{AsChild, Blueprint, BlueprintPlugin, BlueprintsPlugin, FromBlueprint};
+
+    #[derive(Default, Reflect)]
+    struct Lamp;
+
+    #[derive(Component)]
+    struct Light;
+
+    #[derive(Component)]
+    struct Glow;
+
+    #[derive(Component)]
+    struct Bulb;
+
+    impl FromBlueprint<Lamp> for (Light, Glow) {
+        type Params<'w, 's> = ();
+        fn from_blueprint(_: &Lamp, _: &mut StaticSystemParam<Self::Params<'_, '_>>) -> Self {
+            (Light, Glow)
+        }
+    }
+
+    impl FromBlueprint<Lamp> for Bulb {
+        type Params<'w, 's> = ();
+        fn from_blueprint(_: &Lamp, _: &mut StaticSystemParam<Self::Params<'_, '_>>) -> Self {
+            Bulb
+        }
+    }
+
+    fn short_names(app: &App, entity: Entity) -> Vec<String> {
+        describe_blueprint(app.world(), entity)
+            .into_iter()
+            .map(bevy::utils::get_short_name)
+            .collect()
+    }
+
+    #[test]
+    fn built_components_are_described() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Lamp, (Light, Glow)>::default())
+            .add_plugins(BlueprintPlugin::<Lamp, Bulb, AsChild>::default());
+        let entity = app.world_mut().spawn(Blueprint::new(Lamp)).id();
+        app.update();
+        assert_eq!(short_names(&app, entity), vec!["Bulb", "Glow", "Light"]);
+
+        app.world_mut().entity_mut(entity).remove::<Glow>();
+        assert_eq!(short_names(&app, entity), vec!["Bulb", "Light"]);
+
+        app.world_mut()
+            .entity_mut(entity)
+            .remove::<Blueprint<Lamp>>();
+        app.update();
+        assert!(describe_blueprint(app.world(), entity).is_empty());
+    }
+}
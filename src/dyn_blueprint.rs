@@ -0,0 +1,153 @@
+use std::sync::Arc;
+
+use bevy::{
+    ecs::{component::ComponentId, system::EntityCommands, world::CommandQueue},
+    prelude::*,
+    utils::HashSet,
+};
+
+use crate::{BlueprintSet, BlueprintsPlugin, Removals};
+
+/// An ad-hoc blueprint that is a closure rather than data, for one-off builds that do not deserve
+/// a blueprint type. Built by the `DynBlueprintPlugin`.
+///
+/// The closure runs whenever the component changes (including when it is added), with the
+/// entity's commands. The components it adds to the entity are removed again before it runs
+/// anew, and when the `DynBlueprint` is removed; components it only replaced, and entities it
+/// spawned, are left as they are. Since builds run in the sync systems, the closure must be `Send`
+/// and `Sync`, so state that is neither cannot be captured.
+#[derive(Clone, Component)]
+pub struct DynBlueprint(Arc<dyn Fn(&mut EntityCommands) + Send + Sync>);
+
+impl DynBlueprint {
+    pub fn new(build: impl Fn(&mut EntityCommands) + Send + Sync + 'static) -> Self {
+        DynBlueprint(Arc::new(build))
+    }
+}
+
+impl std::fmt::Debug for DynBlueprint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("DynBlueprint")
+    }
+}
+
+// the components that the `DynBlueprint` of the entity added to it
+#[derive(Component, Default)]
+struct DynBuiltComponents(Vec<ComponentId>);
+
+fn remove_dyn_built(entity: &mut EntityWorldMut) {
+    if let Some(built) = entity.take::<DynBuiltComponents>() {
+        for component_id in built.0 {
+            entity.remove_by_id(component_id);
+        }
+    }
+}
+
+fn build_dyn_blueprints(
+    world: &mut World,
+    changed: &mut QueryState<(Entity, &DynBlueprint), Changed<DynBlueprint>>,
+) {
+    let blueprints = changed
+        .iter(world)
+        .map(|(entity, blueprint)| (entity, blueprint.clone()))
+        .collect::<Vec<_>>();
+    for (entity, blueprint) in blueprints {
+        let mut entity_mut = world.entity_mut(entity);
+        remove_dyn_built(&mut entity_mut);
+        let before = entity_mut.archetype().components().collect::<HashSet<_>>();
+
+        let mut queue = CommandQueue::default();
+        (blueprint.0)(&mut Commands::new(&mut queue, world).entity(entity));
+        queue.apply(world);
+
+        // the closure may have despawned the entity
+        let Some(mut entity_mut) = world.get_entity_mut(entity) else {
+            continue;
+        };
+        let built = entity_mut
+            .archetype()
+            .components()
+            .filter(|component_id| !before.contains(component_id))
+            .collect();
+        entity_mut.insert(DynBuiltComponents(built));
+    }
+}
+
+fn handle_removed_dyn_blueprints(
+    mut commands: Commands,
+    mut removed: Removals<DynBlueprint, DynBlueprintPlugin>,
+) {
+    for entity in removed.read() {
+        commands.add(move |world: &mut World| {
+            if let Some(mut entity_mut) = world.get_entity_mut(entity) {
+                remove_dyn_built(&mut entity_mut);
+            }
+        });
+    }
+}
+
+/// Builds `DynBlueprint`s.
+pub struct DynBlueprintPlugin;
+
+impl Plugin for DynBlueprintPlugin {
+    fn build(&self, app: &mut App) {
+        BlueprintsPlugin::add_once(app);
+        Removals::<DynBlueprint, Self>::buffer_while_paused(app);
+        app.add_systems(
+            Update,
+            (
+                handle_removed_dyn_blueprints.in_set(BlueprintSet::Cleanup),
+                build_dyn_blueprints.in_set(BlueprintSet::Sync),
+            ),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Component, Debug, PartialEq)]
+    struct Speed(f32);
+
+    #[derive(Component)]
+    struct Boosted;
+
+    #[derive(Component)]
+    struct Slowed;
+
+    #[test]
+    fn closures_build_and_tear_down() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin, DynBlueprintPlugin));
+        let entity = app
+            .world_mut()
+            .spawn((
+                Speed(1.),
+                DynBlueprint::new(|entity| {
+                    entity.insert((Speed(2.), Boosted));
+                }),
+            ))
+            .id();
+        app.update();
+        assert_eq!(app.world().get::<Speed>(entity), Some(&Speed(2.)));
+        assert!(app.world().get::<Boosted>(entity).is_some());
+
+        // a new closure replaces what the previous one added
+        app.world_mut()
+            .entity_mut(entity)
+            .insert(DynBlueprint::new(|entity| {
+                entity.insert((Speed(0.5), Slowed));
+            }));
+        app.update();
+        assert_eq!(app.world().get::<Speed>(entity), Some(&Speed(0.5)));
+        assert!(app.world().get::<Boosted>(entity).is_none());
+        assert!(app.world().get::<Slowed>(entity).is_some());
+
+        // only what it added is removed
+        app.world_mut().entity_mut(entity).remove::<DynBlueprint>();
+        app.update();
+        assert!(app.world().get::<Slowed>(entity).is_none());
+        assert_eq!(app.world().get::<Speed>(entity), Some(&Speed(0.5)));
+    }
+}
@@ -0,0 +1,552 @@
+use std::any::TypeId;
+
+use bevy::{
+    asset::{io::Reader, ron, AssetLoader, AsyncReadExt, LoadContext, LoadedFolder},
+    prelude::*,
+    reflect::{serde::TypedReflectDeserializer, TypeRegistry, TypeRegistryArc},
+    utils::HashMap,
+};
+use serde::{
+    de::{DeserializeSeed, Deserializer, IgnoredAny, MapAccess, Visitor},
+    Deserialize,
+};
+
+use crate::{BlueprintRegistry, BlueprintSet, BlueprintsPlugin, IsBlueprint, Removals};
+
+/// A blueprint defined in a `.blueprint.ron` file instead of in code: a name and the reflected
+/// components to insert on every entity using it.
+///
+/// ```ron
+/// (
+///     name: "Lamp",
+///     components: {
+///         "my_game::Light": (intensity: 2.0),
+///     },
+/// )
+/// ```
+///
+/// Components whose type is not registered (or does not reflect `Component`) are skipped with a
+/// warning.
+#[derive(Asset, TypePath, Debug)]
+pub struct DynamicBlueprintDefinition {
+    pub name: String,
+    pub components: Vec<Box<dyn Reflect>>,
+}
+
+/// Builds the components of the `DynamicBlueprintDefinition` with this name on the entity.
+///
+/// Entities whose definition is not loaded yet are built once it is.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Component, Reflect)]
+#[reflect(Component)]
+pub struct DynamicBlueprint(pub String);
+
+// the component types built from the entity's dynamic blueprint, removed before rebuilding
+#[derive(Component)]
+struct DynamicTargets(Vec<TypeId>);
+
+// loaded definitions by name
+#[derive(Default, Resource)]
+struct DynamicDefinitions {
+    ids: HashMap<String, AssetId<DynamicBlueprintDefinition>>,
+    folder: Option<Handle<LoadedFolder>>,
+}
+
+/// Possible errors when loading a `DynamicBlueprintDefinition`.
+#[derive(Debug)]
+pub enum DynamicBlueprintLoaderError {
+    Io(std::io::Error),
+    Ron(ron::error::SpannedError),
+}
+
+impl std::fmt::Display for DynamicBlueprintLoaderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "could not read the blueprint definition: {error}"),
+            Self::Ron(error) => write!(f, "could not parse the blueprint definition: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for DynamicBlueprintLoaderError {}
+
+#[derive(Debug)]
+pub struct DynamicBlueprintLoader {
+    type_registry: TypeRegistryArc,
+}
+
+impl FromWorld for DynamicBlueprintLoader {
+    fn from_world(world: &mut World) -> Self {
+        DynamicBlueprintLoader {
+            type_registry: world.resource::<AppTypeRegistry>().0.clone(),
+        }
+    }
+}
+
+impl AssetLoader for DynamicBlueprintLoader {
+    type Asset = DynamicBlueprintDefinition;
+    type Settings = ();
+    type Error = DynamicBlueprintLoaderError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader
+            .read_to_end(&mut bytes)
+            .await
+            .map_err(DynamicBlueprintLoaderError::Io)?;
+        parse_definition(&bytes, &self.type_registry.read())
+            .map_err(DynamicBlueprintLoaderError::Ron)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["blueprint.ron"]
+    }
+}
+
+pub(crate) fn parse_definition(
+    bytes: &[u8],
+    type_registry: &TypeRegistry,
+) -> Result<DynamicBlueprintDefinition, ron::error::SpannedError> {
+    ron::Options::default().from_bytes_seed(bytes, DefinitionSeed { type_registry })
+}
+
+#[derive(Deserialize)]
+#[serde(field_identifier, rename_all = "lowercase")]
+enum DefinitionField {
+    Name,
+    Components,
+}
+
+struct DefinitionSeed<'a> {
+    type_registry: &'a TypeRegistry,
+}
+
+impl<'a, 'de> DeserializeSeed<'de> for DefinitionSeed<'a> {
+    type Value = DynamicBlueprintDefinition;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_struct("DynamicBlueprintDefinition", &["name", "components"], self)
+    }
+}
+
+impl<'a, 'de> Visitor<'de> for DefinitionSeed<'a> {
+    type Value = DynamicBlueprintDefinition;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("a blueprint definition")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut name = None;
+        let mut components = None;
+        while let Some(field) = map.next_key::<DefinitionField>()? {
+            match field {
+                DefinitionField::Name => name = Some(map.next_value::<String>()?),
+                DefinitionField::Components => {
+                    components = Some(map.next_value_seed(ComponentsSeed {
+                        type_registry: self.type_registry,
+                    })?);
+                }
+            }
+        }
+        Ok(DynamicBlueprintDefinition {
+            name: name.ok_or_else(|| serde::de::Error::missing_field("name"))?,
+            components: components.unwrap_or_default(),
+        })
+    }
+}
+
+struct ComponentsSeed<'a> {
+    type_registry: &'a TypeRegistry,
+}
+
+impl<'a, 'de> DeserializeSeed<'de> for ComponentsSeed<'a> {
+    type Value = Vec<Box<dyn Reflect>>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'a, 'de> Visitor<'de> for ComponentsSeed<'a> {
+    type Value = Vec<Box<dyn Reflect>>;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("a map of component type paths to values")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut components = Vec::new();
+        while let Some(type_path) = map.next_key::<String>()? {
+            let registration = self
+                .type_registry
+                .get_with_type_path(&type_path)
+                .filter(|registration| registration.data::<ReflectComponent>().is_some());
+            let Some(registration) = registration else {
+                warn!("skipping {type_path} in a blueprint definition: it is not a registered component");
+                map.next_value::<IgnoredAny>()?;
+                continue;
+            };
+            components.push(map.next_value_seed(TypedReflectDeserializer::new(
+                registration,
+                self.type_registry,
+            ))?);
+        }
+        Ok(components)
+    }
+}
+
+/// Loads every `.blueprint.ron` file in a folder of the assets directory (`blueprints` by
+/// default) as a `DynamicBlueprintDefinition`, registers each one by name in the
+/// `BlueprintRegistry`, and builds `DynamicBlueprint` entities from them.
+///
+/// Definitions are rebuilt on every entity using them when they are modified (e.g. with hot
+/// reloading). A rebuild only removes the components dropped from the definition and inserts the
+/// new or changed ones.
+pub struct DynamicBlueprintPlugin {
+    folder: Option<String>,
+}
+
+impl Default for DynamicBlueprintPlugin {
+    fn default() -> Self {
+        DynamicBlueprintPlugin {
+            folder: Some("blueprints".to_string()),
+        }
+    }
+}
+
+impl DynamicBlueprintPlugin {
+    pub fn with_folder(mut self, folder: impl Into<String>) -> Self {
+        self.folder = Some(folder.into());
+        self
+    }
+
+    /// Loads no folder, so that only the definitions added to `Assets<DynamicBlueprintDefinition>`
+    /// by other means (e.g. a mod loader, or `AssetServer::load` of single files) are built.
+    pub fn without_folder() -> Self {
+        DynamicBlueprintPlugin { folder: None }
+    }
+
+    fn track_definitions(
+        mut commands: Commands,
+        mut events: EventReader<AssetEvent<DynamicBlueprintDefinition>>,
+        assets: Res<Assets<DynamicBlueprintDefinition>>,
+        mut definitions: ResMut<DynamicDefinitions>,
+        mut registry: ResMut<BlueprintRegistry>,
+        blueprint_query: Query<(Entity, &DynamicBlueprint)>,
+    ) {
+        for event in events.read() {
+            let (AssetEvent::Added { id } | AssetEvent::Modified { id }) = event else {
+                continue;
+            };
+            let Some(definition) = assets.get(*id) else {
+                continue;
+            };
+            definitions.ids.insert(definition.name.clone(), *id);
+            registry.register_dynamic(definition.name.clone());
+            for (entity, blueprint) in blueprint_query.iter() {
+                if blueprint.0 == definition.name {
+                    commands.add(move |world: &mut World| build_dynamic_blueprint(world, entity));
+                }
+            }
+        }
+    }
+
+    fn sync_dynamic_blueprints(
+        mut commands: Commands,
+        blueprint_query: Query<Entity, Changed<DynamicBlueprint>>,
+        mut removed_query: Removals<DynamicBlueprint, ()>,
+    ) {
+        // without the blueprint, the build only removes the previous targets
+        for entity in blueprint_query.iter().chain(removed_query.read()) {
+            commands.add(move |world: &mut World| build_dynamic_blueprint(world, entity));
+        }
+    }
+}
+
+// diffs the entity's previous targets against the components of its definition (if any): only
+// the components that were dropped are removed, and only the new or changed ones are inserted, to
+// avoid needless archetype moves and hooks
+fn build_dynamic_blueprint(world: &mut World, entity: Entity) {
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+    world.resource_scope(|world, assets: Mut<Assets<DynamicBlueprintDefinition>>| {
+        let definition = world
+            .get::<DynamicBlueprint>(entity)
+            .and_then(|blueprint| world.resource::<DynamicDefinitions>().ids.get(&blueprint.0))
+            .and_then(|id| assets.get(*id));
+        let Some(mut entity_mut) = world.get_entity_mut(entity) else {
+            return;
+        };
+        let previous = entity_mut
+            .get::<DynamicTargets>()
+            .map(|targets| targets.0.clone());
+        let components = definition
+            .map(|definition| {
+                definition
+                    .components
+                    .iter()
+                    .filter_map(|component| {
+                        let registration = component
+                            .get_represented_type_info()
+                            .and_then(|info| type_registry.get(info.type_id()))?;
+                        let reflect_component = registration.data::<ReflectComponent>()?;
+                        Some((
+                            registration.type_id(),
+                            reflect_component,
+                            component.as_ref(),
+                        ))
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        for type_id in previous.iter().flatten() {
+            if components
+                .iter()
+                .any(|(new_type_id, ..)| new_type_id == type_id)
+            {
+                continue;
+            }
+            if let Some(reflect_component) =
+                type_registry.get_type_data::<ReflectComponent>(*type_id)
+            {
+                reflect_component.remove(&mut entity_mut);
+            }
+        }
+        if definition.is_none() {
+            if previous.is_some() {
+                entity_mut.remove::<(IsBlueprint, DynamicTargets)>();
+            }
+            return;
+        }
+        for (_, reflect_component, component) in &components {
+            let unchanged = reflect_component
+                .reflect(&entity_mut)
+                .and_then(|current| current.reflect_partial_eq(*component))
+                .unwrap_or(false);
+            if !unchanged {
+                reflect_component.insert(&mut entity_mut, *component, &type_registry);
+            }
+        }
+        let type_ids = components
+            .iter()
+            .map(|(type_id, ..)| *type_id)
+            .collect::<Vec<_>>();
+        entity_mut.insert((IsBlueprint, DynamicTargets(type_ids)));
+    });
+}
+
+impl Plugin for DynamicBlueprintPlugin {
+    fn build(&self, app: &mut App) {
+        BlueprintsPlugin::add_once(app);
+        Removals::<DynamicBlueprint, ()>::buffer_while_paused(app);
+        app.init_asset::<DynamicBlueprintDefinition>()
+            .init_asset_loader::<DynamicBlueprintLoader>()
+            .init_resource::<DynamicDefinitions>()
+            .register_type::<DynamicBlueprint>();
+        if let Some(folder) = self.folder.clone() {
+            app.add_systems(
+                Startup,
+                move |asset_server: Res<AssetServer>,
+                      mut definitions: ResMut<DynamicDefinitions>| {
+                    definitions.folder = Some(asset_server.load_folder(folder.clone()));
+                },
+            );
+        }
+        app.add_systems(
+            Update,
+            (Self::track_definitions, Self::sync_dynamic_blueprints).in_set(BlueprintSet::Sync),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BlueprintsPlugin;
+
+    #[derive(Component, Default, Reflect)]
+    #[reflect(Component)]
+    struct Light {
+        intensity: f32,
+    }
+
+    #[derive(Component, Default, Reflect)]
+    #[reflect(Component)]
+    struct Flicker;
+
+    const LAMP: &str = r#"(
+        name: "Lamp",
+        components: {
+            "bevy_reactive_blueprints::dynamic::tests::Light": (intensity: 2.0),
+            "my_mod::Unregistered": (speed: 1.0),
+            "bevy_reactive_blueprints::dynamic::tests::Flicker": (),
+        },
+    )"#;
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            BlueprintsPlugin,
+            // definitions are added by hand, rather than loaded from the crate's assets
+            DynamicBlueprintPlugin::without_folder(),
+        ))
+        .register_type::<Light>()
+        .register_type::<Flicker>();
+        app
+    }
+
+    // the handle keeps the definition loaded
+    fn add_definition(app: &mut App, definition: &str) -> Handle<DynamicBlueprintDefinition> {
+        let type_registry = app.world().resource::<AppTypeRegistry>().clone();
+        let definition = parse_definition(definition.as_bytes(), &type_registry.read()).unwrap();
+        app.world_mut()
+            .resource_mut::<Assets<DynamicBlueprintDefinition>>()
+            .add(definition)
+    }
+
+    #[test]
+    fn unregistered_components_are_skipped() {
+        let app = app();
+        let type_registry = app.world().resource::<AppTypeRegistry>().read();
+        let definition = parse_definition(LAMP.as_bytes(), &type_registry).unwrap();
+        assert_eq!(definition.name, "Lamp");
+        assert_eq!(definition.components.len(), 2);
+    }
+
+    #[test]
+    fn dynamic_blueprints_are_built() {
+        let mut app = app();
+        // the entity waits for its definition to load
+        let entity = app
+            .world_mut()
+            .spawn(DynamicBlueprint("Lamp".to_string()))
+            .id();
+        app.update();
+        assert!(!app.world().entity(entity).contains::<Light>());
+
+        let handle = add_definition(&mut app, LAMP);
+        // asset events are sent at the end of the frame
+        app.update();
+        app.update();
+        assert_eq!(app.world().get::<Light>(entity).unwrap().intensity, 2.);
+        assert!(app.world().entity(entity).contains::<Flicker>());
+        assert_eq!(
+            app.world()
+                .resource::<BlueprintRegistry>()
+                .dynamic_blueprints()
+                .collect::<Vec<_>>(),
+            vec!["Lamp"]
+        );
+
+        // modifying the definition rebuilds its entities
+        let type_registry = app.world().resource::<AppTypeRegistry>().clone();
+        let modified = parse_definition(
+            br#"(name: "Lamp", components: {
+                "bevy_reactive_blueprints::dynamic::tests::Light": (intensity: 0.5),
+            })"#,
+            &type_registry.read(),
+        )
+        .unwrap();
+        app.world_mut()
+            .resource_mut::<Assets<DynamicBlueprintDefinition>>()
+            .insert(&handle, modified);
+        app.update();
+        app.update();
+        assert_eq!(app.world().get::<Light>(entity).unwrap().intensity, 0.5);
+        assert!(!app.world().entity(entity).contains::<Flicker>());
+
+        app.world_mut()
+            .entity_mut(entity)
+            .remove::<DynamicBlueprint>();
+        app.update();
+        assert!(!app.world().entity(entity).contains::<Light>());
+        assert!(!app.world().entity(entity).contains::<IsBlueprint>());
+    }
+
+    #[derive(Default, Resource)]
+    struct LightHooks {
+        inserted: usize,
+        removed: usize,
+    }
+
+    fn definition(app: &App, definition: &[u8]) -> DynamicBlueprintDefinition {
+        let type_registry = app.world().resource::<AppTypeRegistry>().clone();
+        let type_registry = type_registry.read();
+        parse_definition(definition, &type_registry).unwrap()
+    }
+
+    #[test]
+    fn resync_only_applies_the_difference() {
+        let mut app = app();
+        app.init_resource::<LightHooks>();
+        app.world_mut().observe(
+            |_: Trigger<OnInsert, Light>, mut hooks: ResMut<LightHooks>| {
+                hooks.inserted += 1;
+            },
+        );
+        app.world_mut().observe(
+            |_: Trigger<OnRemove, Light>, mut hooks: ResMut<LightHooks>| {
+                hooks.removed += 1;
+            },
+        );
+        let lamp = definition(
+            &app,
+            br#"(name: "Lamp", components: {
+                "bevy_reactive_blueprints::dynamic::tests::Light": (intensity: 2.0),
+            })"#,
+        );
+        let handle = app
+            .world_mut()
+            .resource_mut::<Assets<DynamicBlueprintDefinition>>()
+            .add(lamp);
+        let entity = app
+            .world_mut()
+            .spawn(DynamicBlueprint("Lamp".to_string()))
+            .id();
+        app.update();
+        app.update();
+        assert_eq!(app.world().resource::<LightHooks>().inserted, 1);
+
+        // adding a component leaves the unchanged light alone
+        let flickering = definition(
+            &app,
+            br#"(name: "Lamp", components: {
+                "bevy_reactive_blueprints::dynamic::tests::Light": (intensity: 2.0),
+                "bevy_reactive_blueprints::dynamic::tests::Flicker": (),
+            })"#,
+        );
+        app.world_mut()
+            .resource_mut::<Assets<DynamicBlueprintDefinition>>()
+            .insert(&handle, flickering);
+        app.update();
+        app.update();
+        assert!(app.world().entity(entity).contains::<Flicker>());
+        let hooks = app.world().resource::<LightHooks>();
+        assert_eq!((hooks.inserted, hooks.removed), (1, 0));
+
+        // a changed value is inserted again
+        let dimmed = definition(
+            &app,
+            br#"(name: "Lamp", components: {
+                "bevy_reactive_blueprints::dynamic::tests::Light": (intensity: 0.5),
+                "bevy_reactive_blueprints::dynamic::tests::Flicker": (),
+            })"#,
+        );
+        app.world_mut()
+            .resource_mut::<Assets<DynamicBlueprintDefinition>>()
+            .insert(&handle, dimmed);
+        app.update();
+        app.update();
+        assert_eq!(app.world().get::<Light>(entity).unwrap().intensity, 0.5);
+        let hooks = app.world().resource::<LightHooks>();
+        assert_eq!((hooks.inserted, hooks.removed), (2, 0));
+    }
+}
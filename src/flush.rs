@@ -0,0 +1,80 @@
+use bevy::prelude::*;
+
+use crate::{BlueprintSyncSet, BlueprintsPlugin};
+
+pub trait BlueprintFlushExt {
+    /// Syncs the blueprints of `B` after those of `A`, with their commands applied in between, so
+    /// that `B` sees the entities built for `A` in the same frame, e.g. the `Blueprint<B>`
+    /// children spawned by an `AsChild` prefab of `A`. Without it, those are only built in the
+    /// next frame.
+    ///
+    /// Every flush is a sync point at which the sync systems of other blueprints cannot run in
+    /// parallel, so only add them between the types that need one.
+    fn flush_blueprints_between<A, B>(&mut self) -> &mut Self
+    where
+        A: Send + Sync + 'static,
+        B: Send + Sync + 'static;
+}
+
+impl BlueprintFlushExt for App {
+    fn flush_blueprints_between<A, B>(&mut self) -> &mut Self
+    where
+        A: Send + Sync + 'static,
+        B: Send + Sync + 'static,
+    {
+        BlueprintsPlugin::add_once(self);
+        // commands are applied between sets ordered after one another
+        self.configure_sets(
+            Update,
+            BlueprintSyncSet::<B>::default().after(BlueprintSyncSet::<A>::default()),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{ecs::system::StaticSystemParam, prelude::*};
+
+    use super::*;
+    use crate::{AsChild, Blueprint, BlueprintPlugin, FromBlueprint};
+
+    #[derive(Default, Reflect)]
+    struct Shelf;
+
+    #[derive(Default, Reflect)]
+    struct Book;
+
+    #[derive(Component)]
+    struct Cover;
+
+    impl FromBlueprint<Shelf> for Blueprint<Book> {
+        type Params<'w, 's> = ();
+        fn from_blueprint(_: &Shelf, _: &mut StaticSystemParam<Self::Params<'_, '_>>) -> Self {
+            Blueprint::default()
+        }
+    }
+
+    impl FromBlueprint<Book> for Cover {
+        type Params<'w, 's> = ();
+        fn from_blueprint(_: &Book, _: &mut StaticSystemParam<Self::Params<'_, '_>>) -> Self {
+            Cover
+        }
+    }
+
+    fn covers(app: &mut App) -> usize {
+        let mut query = app.world_mut().query_filtered::<(), With<Cover>>();
+        query.iter(app.world()).count()
+    }
+
+    #[test]
+    fn nested_blueprints_build_in_one_frame() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Shelf, Blueprint<Book>, AsChild>::default())
+            .add_plugins(BlueprintPlugin::<Book, Cover, AsChild>::default())
+            .flush_blueprints_between::<Shelf, Book>();
+        app.world_mut().spawn(Blueprint::<Shelf>::default());
+        app.update();
+        assert_eq!(covers(&mut app), 1);
+    }
+}
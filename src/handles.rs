@@ -0,0 +1,247 @@
+use std::marker::PhantomData;
+
+use bevy::{
+    ecs::system::{StaticSystemParam, SystemParam},
+    prelude::*,
+    reflect::GetTypeRegistration,
+};
+
+use crate::{changed_fields, Blueprint, BlueprintSet, BlueprintsPlugin};
+
+/// Builds the `Handle<Mesh>` and `Handle<M>` of an entity from blueprint data, so that editing the
+/// blueprint swaps the handles in place instead of rebuilding a prefab, e.g. replacing only the
+/// material when the blueprint's `color` changes. Implemented on the blueprint type for each
+/// material `M`, and applied by the `ApplyHandlesPlugin`.
+pub trait ApplyHandles<M: Asset> {
+    type Params<'w, 's>: SystemParam;
+
+    /// The top-level fields of the blueprint that the mesh is built from. The mesh is only built
+    /// again when one of them changes, and the material when any other field changes.
+    const MESH_FIELDS: &'static [&'static str];
+
+    fn mesh(&self, params: &mut StaticSystemParam<Self::Params<'_, '_>>) -> Handle<Mesh>;
+
+    fn material(&self, params: &mut StaticSystemParam<Self::Params<'_, '_>>) -> Handle<M>;
+}
+
+// the blueprint as its handles were last applied, to find the fields that changed since (a
+// concrete copy rather than a dynamic one, since fields such as `Color` only compare equal to
+// their own type)
+#[derive(Component)]
+struct AppliedHandles<B, M> {
+    applied: B,
+    marker: PhantomData<M>,
+}
+
+type HandlesQuery<'w, 's, B, M> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static Blueprint<B>,
+        Option<&'static mut Handle<Mesh>>,
+        Option<&'static mut Handle<M>>,
+        Option<&'static mut AppliedHandles<B, M>>,
+    ),
+    Changed<Blueprint<B>>,
+>;
+
+/// Applies each `Blueprint<B>` to the mesh and material handles of its entity whenever the
+/// blueprint changes, through `ApplyHandles<M>`. A handle the entity does not have yet is
+/// inserted, and a handle equal to the current one is not written, so the entity is not marked
+/// as changed.
+///
+/// Only `Handle<Mesh>` and `Handle<M>` are touched, and nothing is removed: removing the blueprint
+/// leaves the handles as they were last applied. Changed fields are found with
+/// `Reflect::reflect_partial_eq`, so this keeps a copy (made with `FromReflect`) of every applied
+/// blueprint; blueprints that are not structs build both handles on every change.
+pub struct ApplyHandlesPlugin<B, M> {
+    blueprint_marker: PhantomData<B>,
+    material_marker: PhantomData<M>,
+}
+
+impl<B, M> Default for ApplyHandlesPlugin<B, M> {
+    fn default() -> Self {
+        Self {
+            blueprint_marker: PhantomData::<B>,
+            material_marker: PhantomData::<M>,
+        }
+    }
+}
+
+impl<B, M> ApplyHandlesPlugin<B, M>
+where
+    B: ApplyHandles<M> + Default + FromReflect,
+    M: Asset,
+{
+    fn apply_handles(
+        mut commands: Commands,
+        mut query: HandlesQuery<B, M>,
+        mut system_params: StaticSystemParam<B::Params<'_, '_>>,
+    ) {
+        let is_mesh_field =
+            |field: &&str| B::MESH_FIELDS.iter().any(|mesh_field| mesh_field == field);
+        for (entity, blueprint, mesh, material, applied) in query.iter_mut() {
+            let data = &blueprint.0;
+            let (mesh_changed, material_changed) = match applied
+                .as_ref()
+                .and_then(|applied| changed_fields(applied.applied.as_reflect(), data.as_reflect()))
+            {
+                Some(fields) => (
+                    fields.iter().any(is_mesh_field),
+                    !fields.iter().all(is_mesh_field),
+                ),
+                None => (true, true),
+            };
+
+            match mesh {
+                Some(mut mesh) if mesh_changed => {
+                    mesh.set_if_neq(data.mesh(&mut system_params));
+                }
+                Some(_) => {}
+                None => {
+                    commands
+                        .entity(entity)
+                        .insert(data.mesh(&mut system_params));
+                }
+            }
+            match material {
+                Some(mut material) if material_changed => {
+                    material.set_if_neq(data.material(&mut system_params));
+                }
+                Some(_) => {}
+                None => {
+                    commands
+                        .entity(entity)
+                        .insert(data.material(&mut system_params));
+                }
+            }
+
+            match applied {
+                Some(mut applied) => applied.applied = B::from_reflect(data).unwrap(),
+                None => {
+                    commands.entity(entity).insert(AppliedHandles::<B, M> {
+                        applied: B::from_reflect(data).unwrap(),
+                        marker: PhantomData,
+                    });
+                }
+            }
+        }
+    }
+}
+
+impl<B, M> Plugin for ApplyHandlesPlugin<B, M>
+where
+    B: ApplyHandles<M> + Default + GetTypeRegistration + FromReflect + TypePath,
+    B: Send + Sync + 'static,
+    M: Asset,
+{
+    fn build(&self, app: &mut App) {
+        BlueprintsPlugin::add_once(app);
+        app.add_systems(Update, Self::apply_handles.in_set(BlueprintSet::Sync))
+            .register_type::<Blueprint<B>>()
+            .register_type::<B>();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MeshMaterialParams;
+
+    #[derive(Asset, TypePath)]
+    struct Paint(Color);
+
+    impl From<Color> for Paint {
+        fn from(color: Color) -> Self {
+            Paint(color)
+        }
+    }
+
+    #[derive(Default, Reflect)]
+    struct Crate {
+        size: f32,
+        color: Color,
+    }
+
+    impl ApplyHandles<Paint> for Crate {
+        type Params<'w, 's> = MeshMaterialParams<'w, Paint>;
+
+        const MESH_FIELDS: &'static [&'static str] = &["size"];
+
+        fn mesh(&self, params: &mut StaticSystemParam<Self::Params<'_, '_>>) -> Handle<Mesh> {
+            params.add_box(Vec3::splat(self.size))
+        }
+
+        fn material(&self, params: &mut StaticSystemParam<Self::Params<'_, '_>>) -> Handle<Paint> {
+            params.add_material(self.color)
+        }
+    }
+
+    fn handles(app: &App, entity: Entity) -> (Handle<Mesh>, Handle<Paint>) {
+        let entity = app.world().entity(entity);
+        (
+            entity.get::<Handle<Mesh>>().unwrap().clone(),
+            entity.get::<Handle<Paint>>().unwrap().clone(),
+        )
+    }
+
+    #[test]
+    fn only_the_affected_handle_is_replaced() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default(), BlueprintsPlugin))
+            .init_asset::<Mesh>()
+            .init_asset::<Paint>()
+            .add_plugins(ApplyHandlesPlugin::<Crate, Paint>::default());
+        // the missing material handle is inserted next to the existing mesh handle
+        let placeholder = app
+            .world_mut()
+            .resource_mut::<Assets<Mesh>>()
+            .add(Cuboid::default());
+        let entity = app
+            .world_mut()
+            .spawn((
+                Blueprint::new(Crate {
+                    size: 2.,
+                    color: Color::WHITE,
+                }),
+                placeholder.clone(),
+            ))
+            .id();
+        app.update();
+        let (mesh, paint) = handles(&app, entity);
+        assert_ne!(mesh, placeholder);
+        assert_eq!(
+            app.world()
+                .resource::<Assets<Paint>>()
+                .get(&paint)
+                .unwrap()
+                .0,
+            Color::WHITE
+        );
+
+        // recoloring only swaps the material
+        app.world_mut()
+            .get_mut::<Blueprint<Crate>>(entity)
+            .unwrap()
+            .0
+            .color = Color::BLACK;
+        app.update();
+        let (new_mesh, new_paint) = handles(&app, entity);
+        assert_eq!(new_mesh, mesh);
+        assert_ne!(new_paint, paint);
+        let paints = app.world().resource::<Assets<Paint>>();
+        assert_eq!(paints.get(&new_paint).unwrap().0, Color::BLACK);
+
+        // resizing only swaps the mesh
+        app.world_mut()
+            .get_mut::<Blueprint<Crate>>(entity)
+            .unwrap()
+            .0
+            .size = 3.;
+        app.update();
+        let (resized_mesh, resized_paint) = handles(&app, entity);
+        assert_ne!(resized_mesh, mesh);
+        assert_eq!(resized_paint, new_paint);
+    }
+}
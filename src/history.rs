@@ -0,0 +1,313 @@
+use std::{collections::VecDeque, marker::PhantomData};
+
+use bevy::{
+    ecs::{system::EntityCommands, world::Command},
+    prelude::*,
+};
+
+use crate::{Blueprint, BlueprintSet, BlueprintsPlugin, Removals};
+
+pub const DEFAULT_HISTORY_CAPACITY: usize = 32;
+
+/// Previous values of an entity's `Blueprint<B>`, oldest first.
+///
+/// Every entry is a full clone of `B`, so each entity can hold up to `capacity` copies of its
+/// blueprint (plus whatever heap data `B` owns). Keep the capacity small for large blueprints.
+#[derive(Debug, Component)]
+pub struct BlueprintHistory<B> {
+    entries: VecDeque<B>,
+    current: Option<B>,
+    capacity: usize,
+    skip_next: bool,
+}
+
+impl<B> BlueprintHistory<B> {
+    fn new(capacity: usize) -> Self {
+        BlueprintHistory {
+            entries: VecDeque::with_capacity(capacity),
+            current: None,
+            capacity,
+            skip_next: false,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &B> {
+        self.entries.iter()
+    }
+
+    fn push(&mut self, value: B) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(value);
+    }
+}
+
+type ChangedHistoryQuery<'w, 's, B> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static Blueprint<B>,
+        Option<&'static mut BlueprintHistory<B>>,
+    ),
+    Changed<Blueprint<B>>,
+>;
+
+#[derive(Resource)]
+struct HistoryCapacity<B> {
+    capacity: usize,
+    marker: PhantomData<B>,
+}
+
+/// Records the previous value of every `Blueprint<B>` whenever it changes so that it can be
+/// restored with `undo_blueprint`.
+pub struct BlueprintHistoryPlugin<B> {
+    capacity: usize,
+    marker: PhantomData<B>,
+}
+
+impl<B> Default for BlueprintHistoryPlugin<B> {
+    fn default() -> Self {
+        Self::with_capacity(DEFAULT_HISTORY_CAPACITY)
+    }
+}
+
+impl<B> BlueprintHistoryPlugin<B> {
+    pub fn with_capacity(capacity: usize) -> Self {
+        BlueprintHistoryPlugin {
+            capacity,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<B> BlueprintHistoryPlugin<B>
+where
+    B: Clone + Default + Send + Sync + 'static,
+{
+    fn record_history(
+        mut commands: Commands,
+        mut blueprint_query: ChangedHistoryQuery<B>,
+        capacity: Res<HistoryCapacity<B>>,
+    ) {
+        for (entity, blueprint, history) in blueprint_query.iter_mut() {
+            let Some(mut history) = history else {
+                let mut history = BlueprintHistory::new(capacity.capacity);
+                history.current = Some(blueprint.0.clone());
+                commands.entity(entity).insert(history);
+                continue;
+            };
+            if history.skip_next {
+                history.skip_next = false;
+                continue;
+            }
+            if let Some(previous) = history.current.replace(blueprint.0.clone()) {
+                history.push(previous);
+            }
+        }
+    }
+
+    fn handle_removed_blueprints(
+        mut commands: Commands,
+        mut removed: Removals<Blueprint<B>, BlueprintHistory<B>>,
+    ) {
+        for entity in removed.read() {
+            if let Some(mut entity_commands) = commands.get_entity(entity) {
+                entity_commands.remove::<BlueprintHistory<B>>();
+            }
+        }
+    }
+}
+
+impl<B> Plugin for BlueprintHistoryPlugin<B>
+where
+    B: Clone + Default + Send + Sync + 'static,
+{
+    fn build(&self, app: &mut App) {
+        BlueprintsPlugin::add_once(app);
+        Removals::<Blueprint<B>, BlueprintHistory<B>>::buffer_while_paused(app);
+        app.insert_resource(HistoryCapacity::<B> {
+            capacity: self.capacity,
+            marker: PhantomData,
+        })
+        .add_systems(
+            Update,
+            (
+                Self::handle_removed_blueprints.in_set(BlueprintSet::Cleanup),
+                Self::record_history.in_set(BlueprintSet::Sync),
+            ),
+        );
+    }
+}
+
+/// Restores the most recent value from the entity's `BlueprintHistory<B>`, rebuilding its target.
+pub struct UndoBlueprint<B> {
+    pub entity: Entity,
+    marker: PhantomData<B>,
+}
+
+impl<B> UndoBlueprint<B> {
+    pub fn new(entity: Entity) -> Self {
+        UndoBlueprint {
+            entity,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<B> Command for UndoBlueprint<B>
+where
+    B: Clone + Default + Send + Sync + 'static,
+{
+    fn apply(self, world: &mut World) {
+        let Some(mut entity) = world.get_entity_mut(self.entity) else {
+            return;
+        };
+        let Some(previous) = entity
+            .get::<BlueprintHistory<B>>()
+            .and_then(|history| history.entries.back().cloned())
+        else {
+            return;
+        };
+        if let Some(mut blueprint) = entity.get_mut::<Blueprint<B>>() {
+            blueprint.0 = previous.clone();
+            let mut history = entity.get_mut::<BlueprintHistory<B>>().unwrap();
+            history.entries.pop_back();
+            history.current = Some(previous);
+            // the undo itself changes the blueprint, which should not be recorded
+            history.skip_next = true;
+        }
+    }
+}
+
+pub trait BlueprintHistoryCommandsExt {
+    fn undo_blueprint<B>(&mut self) -> &mut Self
+    where
+        B: Clone + Default + Send + Sync + 'static;
+}
+
+impl BlueprintHistoryCommandsExt for EntityCommands<'_> {
+    fn undo_blueprint<B>(&mut self) -> &mut Self
+    where
+        B: Clone + Default + Send + Sync + 'static,
+    {
+        let entity = self.id();
+        self.commands().add(UndoBlueprint::<B>::new(entity));
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{ecs::system::StaticSystemParam, prelude::*};
+
+    use super::*;
+    use crate::{BlueprintPlugin, BlueprintsPlugin, FromBlueprint};
+
+    #[derive(Clone, Debug, Default, PartialEq, Reflect)]
+    struct Rect {
+        size: Vec2,
+    }
+
+    #[derive(Component)]
+    struct RectSize(Vec2);
+
+    impl FromBlueprint<Rect> for RectSize {
+        type Params<'w, 's> = ();
+        fn from_blueprint(
+            blueprint: &Rect,
+            _: &mut StaticSystemParam<Self::Params<'_, '_>>,
+        ) -> Self {
+            RectSize(blueprint.size)
+        }
+    }
+
+    fn set_size(app: &mut App, entity: Entity, size: Vec2) {
+        app.world_mut()
+            .get_mut::<Blueprint<Rect>>(entity)
+            .unwrap()
+            .0
+            .size = size;
+        app.update();
+    }
+
+    #[test]
+    fn undo_restores_previous_value() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Rect, RectSize>::default())
+            .add_plugins(BlueprintHistoryPlugin::<Rect>::default());
+        let entity = app.world_mut().spawn(Blueprint::<Rect>::default()).id();
+        app.update();
+        set_size(&mut app, entity, Vec2::ONE);
+        set_size(&mut app, entity, Vec2::splat(2.));
+        assert_eq!(
+            app.world()
+                .get::<BlueprintHistory<Rect>>(entity)
+                .unwrap()
+                .len(),
+            2
+        );
+
+        app.world_mut()
+            .commands()
+            .entity(entity)
+            .undo_blueprint::<Rect>();
+        app.world_mut().flush_commands();
+        app.update();
+        assert_eq!(app.world().get::<RectSize>(entity).unwrap().0, Vec2::ONE);
+        let history = app.world().get::<BlueprintHistory<Rect>>(entity).unwrap();
+        assert_eq!(history.iter().collect::<Vec<_>>(), vec![&Rect::default()]);
+    }
+
+    #[test]
+    fn undo_without_a_blueprint_keeps_the_history() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Rect, RectSize>::default())
+            .add_plugins(BlueprintHistoryPlugin::<Rect>::default());
+        let entity = app.world_mut().spawn(Blueprint::<Rect>::default()).id();
+        app.update();
+        set_size(&mut app, entity, Vec2::ONE);
+
+        // undo after the blueprint is removed, before the history is cleaned up
+        app.world_mut()
+            .entity_mut(entity)
+            .remove::<Blueprint<Rect>>();
+        UndoBlueprint::<Rect>::new(entity).apply(app.world_mut());
+        let history = app.world().get::<BlueprintHistory<Rect>>(entity).unwrap();
+        assert_eq!(history.iter().collect::<Vec<_>>(), vec![&Rect::default()]);
+        assert_eq!(history.current, Some(Rect { size: Vec2::ONE }));
+        assert!(!history.skip_next);
+    }
+
+    #[test]
+    fn history_is_bounded() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Rect, RectSize>::default())
+            .add_plugins(BlueprintHistoryPlugin::<Rect>::with_capacity(2));
+        let entity = app.world_mut().spawn(Blueprint::<Rect>::default()).id();
+        app.update();
+        for size in 1..5 {
+            set_size(&mut app, entity, Vec2::splat(size as f32));
+        }
+        let history = app.world().get::<BlueprintHistory<Rect>>(entity).unwrap();
+        assert_eq!(
+            history.iter().map(|rect| rect.size).collect::<Vec<_>>(),
+            vec![Vec2::splat(2.), Vec2::splat(3.)]
+        );
+    }
+}
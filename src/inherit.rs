@@ -0,0 +1,222 @@
+use std::marker::PhantomData;
+
+use bevy::{
+    ecs::component::{ComponentHooks, StorageType},
+    prelude::*,
+    reflect::DynamicStruct,
+    utils::get_short_name,
+};
+
+use crate::{Blueprint, BlueprintsPlugin, BlueprintsSet};
+
+/// The shared config of a blueprint variant. With a `BlueprintBasePlugin<B>`, the entity's
+/// `Blueprint<B>` is this base with the entity's `BlueprintOverrides<B>` applied, and is updated
+/// whenever either of them changes.
+///
+/// Removing the base leaves the `Blueprint<B>` as it was last merged.
+#[derive(Clone, Debug, Default, Deref, DerefMut)]
+pub struct BlueprintBase<B>(pub B);
+
+impl<B: Send + Sync + 'static> Component for BlueprintBase<B> {
+    const STORAGE_TYPE: StorageType = StorageType::Table;
+}
+
+/// The fields in which a blueprint variant differs from its `BlueprintBase<B>`.
+///
+/// The other fields are inherited from the base. A nested struct can be overridden in part by
+/// passing a `DynamicStruct` of the fields that differ, which is merged recursively.
+#[derive(Debug)]
+pub struct BlueprintOverrides<B> {
+    fields: DynamicStruct,
+    marker: PhantomData<B>,
+}
+
+impl<B> Default for BlueprintOverrides<B> {
+    fn default() -> Self {
+        BlueprintOverrides {
+            fields: DynamicStruct::default(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<B> BlueprintOverrides<B> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(mut self, field: &str, value: impl Reflect) -> Self {
+        self.insert(field, value);
+        self
+    }
+
+    /// Overrides `field`, replacing any previous override of it.
+    pub fn insert(&mut self, field: &str, value: impl Reflect) {
+        self.fields.insert_boxed(field, Box::new(value));
+    }
+
+    pub fn fields(&self) -> &DynamicStruct {
+        &self.fields
+    }
+}
+
+impl<B: Send + Sync + 'static> Component for BlueprintOverrides<B> {
+    const STORAGE_TYPE: StorageType = StorageType::Table;
+
+    // falls back to the base
+    fn register_component_hooks(hooks: &mut ComponentHooks) {
+        hooks.on_remove(|mut world, entity, _| {
+            if let Some(mut base) = world.get_mut::<BlueprintBase<B>>(entity) {
+                base.set_changed();
+            }
+        });
+    }
+}
+
+/// Merges each `BlueprintBase<B>` with its `BlueprintOverrides<B>` into the entity's
+/// `Blueprint<B>`, which is then built by the plugins of `B` as usual.
+pub struct BlueprintBasePlugin<B>(PhantomData<B>);
+
+impl<B> Default for BlueprintBasePlugin<B> {
+    fn default() -> Self {
+        BlueprintBasePlugin(PhantomData)
+    }
+}
+
+type BaseQuery<'w, 's, B> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static BlueprintBase<B>,
+        Option<&'static BlueprintOverrides<B>>,
+        Option<&'static mut Blueprint<B>>,
+    ),
+    Or<(Changed<BlueprintBase<B>>, Changed<BlueprintOverrides<B>>)>,
+>;
+
+impl<B> BlueprintBasePlugin<B>
+where
+    B: Default + FromReflect + Send + Sync + 'static,
+{
+    fn merge_overrides(mut commands: Commands, mut query: BaseQuery<B>) {
+        for (entity, base, overrides, blueprint) in query.iter_mut() {
+            let Some(mut merged) = B::from_reflect(&base.0) else {
+                continue;
+            };
+            if let Some(overrides) = overrides {
+                if let Err(error) = merged.try_apply(&overrides.fields) {
+                    error!(
+                        "overrides of {} on entity {} do not apply to its base: {}",
+                        get_short_name(std::any::type_name::<B>()),
+                        entity,
+                        error,
+                    );
+                    continue;
+                }
+            }
+            match blueprint {
+                Some(mut blueprint) => blueprint.0 = merged,
+                None => {
+                    commands.entity(entity).insert(Blueprint::new(merged));
+                }
+            }
+        }
+    }
+}
+
+impl<B> Plugin for BlueprintBasePlugin<B>
+where
+    B: Default + FromReflect + Send + Sync + 'static,
+{
+    fn build(&self, app: &mut App) {
+        BlueprintsPlugin::add_once(app);
+        app.add_systems(Update, Self::merge_overrides.before(BlueprintsSet));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{ecs::system::StaticSystemParam, prelude::*};
+
+    use super::*;
+    use crate::{BlueprintPlugin, FromBlueprint};
+
+    #[derive(Clone, Default, Reflect)]
+    struct Enemy {
+        size: f32,
+        look: Look,
+    }
+
+    #[derive(Clone, Default, Reflect)]
+    struct Look {
+        hue: f32,
+        outline: f32,
+    }
+
+    #[derive(Component, Debug, PartialEq)]
+    struct EnemyMesh {
+        size: f32,
+        hue: f32,
+        outline: f32,
+    }
+
+    impl FromBlueprint<Enemy> for EnemyMesh {
+        type Params<'w, 's> = ();
+        fn from_blueprint(
+            blueprint: &Enemy,
+            _: &mut StaticSystemParam<Self::Params<'_, '_>>,
+        ) -> Self {
+            EnemyMesh {
+                size: blueprint.size,
+                hue: blueprint.look.hue,
+                outline: blueprint.look.outline,
+            }
+        }
+    }
+
+    #[test]
+    fn variants_inherit_base_fields() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugins(BlueprintBasePlugin::<Enemy>::default())
+            .add_plugins(BlueprintPlugin::<Enemy, EnemyMesh>::default());
+        let base = BlueprintBase(Enemy {
+            size: 2.,
+            look: Look {
+                hue: 0.,
+                outline: 1.,
+            },
+        });
+        let mut look = DynamicStruct::default();
+        look.insert("hue", 120_f32);
+        let variant = app
+            .world_mut()
+            .spawn((base, BlueprintOverrides::<Enemy>::new().with("look", look)))
+            .id();
+        app.update();
+        assert_eq!(
+            app.world().get::<EnemyMesh>(variant),
+            Some(&EnemyMesh {
+                size: 2.,
+                hue: 120.,
+                outline: 1.,
+            })
+        );
+
+        // changes to the base are inherited, and removed overrides fall back to it
+        app.world_mut()
+            .get_mut::<BlueprintBase<Enemy>>(variant)
+            .unwrap()
+            .size = 3.;
+        app.update();
+        assert_eq!(app.world().get::<EnemyMesh>(variant).unwrap().size, 3.);
+        assert_eq!(app.world().get::<EnemyMesh>(variant).unwrap().hue, 120.);
+
+        app.world_mut()
+            .entity_mut(variant)
+            .remove::<BlueprintOverrides<Enemy>>();
+        app.update();
+        assert_eq!(app.world().get::<EnemyMesh>(variant).unwrap().hue, 0.);
+    }
+}
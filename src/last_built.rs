@@ -0,0 +1,87 @@
+use bevy::prelude::*;
+
+use crate::{BlueprintPlugin, BlueprintTarget, TryFromBlueprint};
+
+/// A copy of the `P` bundle last built for this blueprint entity, kept while the target is built
+/// by a `BlueprintPlugin` with `snapshot_last_built`. It is on the blueprint entity even for
+/// `AsChild` targets.
+///
+/// Every build clones the whole bundle, so keep this to debugging and to plugins whose bundles
+/// are small.
+#[derive(Debug, Component, Deref)]
+pub struct LastBuilt<P: Send + Sync + 'static>(pub P);
+
+impl<B, P, T> BlueprintPlugin<B, P, T>
+where
+    P: Bundle + Clone + TryFromBlueprint<B>,
+    T: BlueprintTarget,
+{
+    /// Inserts a `LastBuilt<P>` copy of every bundle this plugin builds.
+    pub fn snapshot_last_built(mut self) -> Self {
+        self.snapshot = Some(P::clone);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{ecs::system::StaticSystemParam, prelude::*};
+
+    use super::*;
+    use crate::{AsChild, Blueprint, BlueprintsPlugin, FromBlueprint};
+
+    #[derive(Default, Reflect)]
+    struct Rect {
+        size: Vec2,
+    }
+
+    #[derive(Clone, Component, Debug, PartialEq)]
+    struct RectSize(Vec2);
+
+    #[derive(Clone, Component, Debug, PartialEq)]
+    struct RectArea(f32);
+
+    #[derive(Bundle, Clone)]
+    struct RectBundle {
+        size: RectSize,
+        area: RectArea,
+    }
+
+    impl FromBlueprint<Rect> for RectBundle {
+        type Params<'w, 's> = ();
+        fn from_blueprint(
+            blueprint: &Rect,
+            _: &mut StaticSystemParam<Self::Params<'_, '_>>,
+        ) -> Self {
+            RectBundle {
+                size: RectSize(blueprint.size),
+                area: RectArea(blueprint.size.x * blueprint.size.y),
+            }
+        }
+    }
+
+    #[test]
+    fn last_built_snapshots_bundle() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(
+                BlueprintPlugin::<Rect, RectBundle, AsChild>::default().snapshot_last_built(),
+            );
+        let entity = app
+            .world_mut()
+            .spawn(Blueprint::new(Rect {
+                size: Vec2::new(2., 3.),
+            }))
+            .id();
+        app.update();
+        let last_built = app.world().get::<LastBuilt<RectBundle>>(entity).unwrap();
+        assert_eq!(last_built.size, RectSize(Vec2::new(2., 3.)));
+        assert_eq!(last_built.area, RectArea(6.));
+
+        app.world_mut()
+            .entity_mut(entity)
+            .remove::<Blueprint<Rect>>();
+        app.update();
+        assert!(app.world().get::<LastBuilt<RectBundle>>(entity).is_none());
+    }
+}
@@ -0,0 +1,397 @@
+use std::{marker::PhantomData, time::Duration};
+
+#[cfg(feature = "render")]
+use bevy::render::view::{check_visibility, VisibilitySystems};
+use bevy::{
+    ecs::entity::{EntityHashMap, EntityHashSet},
+    prelude::*,
+    reflect::GetTypeRegistration,
+};
+
+use crate::{
+    AsSelf, Blueprint, BlueprintBuilt, BlueprintPlugin, BlueprintSet, BlueprintTarget, BuiltValues,
+    Removals, TryFromBlueprint,
+};
+
+/// Marks a blueprint entity as visible, for blueprints added with a `LazyBlueprintPlugin`.
+///
+/// With the `render` feature this is kept in sync with `ViewVisibility`, which the plugin also
+/// computes for blueprint entities without a mesh (Bevy only checks the visibility of entities
+/// with a `Handle<Mesh>`, and an unbuilt blueprint has none). This needs the `VisibilityPlugin`
+/// (part of `DefaultPlugins`), and blueprint entities with visibility components and a
+/// `GlobalTransform`, as spawned by `Blueprint::bundle`. Entities are only culled once they have
+/// an `Aabb`, so give them one covering what they build: those without one (or with
+/// `NoFrustumCulling`) are in view whenever they are visible, and so are always built.
+#[derive(Clone, Copy, Debug, Default, Component, Reflect)]
+#[reflect(Component)]
+pub struct InView;
+
+/// How long an entity stays built after leaving the view, unless set with `with_teardown_delay`.
+pub const DEFAULT_TEARDOWN_DELAY: Duration = Duration::from_millis(500);
+
+// the entities a lazy plugin has built, and when the built ones left the view
+#[derive(Resource)]
+pub(crate) struct LazyTargets<B, P, T> {
+    pub(crate) built: EntityHashSet,
+    left_view: EntityHashMap<Duration>,
+    teardown_delay: Duration,
+    marker: PhantomData<(B, P, T)>,
+}
+
+// what the sync system of a lazy plugin does with a blueprint that it would otherwise build
+pub(crate) enum LazySync {
+    Build,
+    Skip,
+    TearDown,
+}
+
+impl<B, P, T> LazyTargets<B, P, T> {
+    // out of view, a stale target is torn down right away, and rebuilt once back in view; back in
+    // view before being torn down, an unchanged target is kept
+    pub(crate) fn sync(&mut self, entity: Entity, in_view: bool, changed: bool) -> LazySync {
+        if !in_view {
+            if changed && self.built.remove(&entity) {
+                return LazySync::TearDown;
+            }
+            return LazySync::Skip;
+        }
+        if !changed && self.built.contains(&entity) {
+            return LazySync::Skip;
+        }
+        LazySync::Build
+    }
+
+    pub(crate) fn is_built(&self, entity: Entity) -> bool {
+        self.built.contains(&entity)
+    }
+
+    // records whether the entity's target is built after a build
+    pub(crate) fn set_built(&mut self, entity: Entity, built: bool) {
+        if built {
+            self.built.insert(entity);
+        } else {
+            self.built.remove(&entity);
+        }
+    }
+}
+
+// the blueprint entities whose visibility Bevy's check for meshes does not compute
+#[cfg(feature = "render")]
+type WithoutMesh<B> = (With<Blueprint<B>>, Without<Handle<Mesh>>);
+
+#[cfg(feature = "render")]
+type VisibilityQuery<'w, 's, B> =
+    Query<'w, 's, (Entity, &'static ViewVisibility, Has<InView>), With<Blueprint<B>>>;
+
+/// Like `BlueprintPlugin`, but only builds blueprints while their entity is `InView`, and tears
+/// the target down again once the entity has been out of view for the teardown delay.
+///
+/// The delay (`DEFAULT_TEARDOWN_DELAY` by default) keeps entities oscillating at the edge of the
+/// view from being rebuilt every frame.
+pub struct LazyBlueprintPlugin<B, P: Bundle + TryFromBlueprint<B>, T: BlueprintTarget = AsSelf> {
+    plugin: BlueprintPlugin<B, P, T>,
+    teardown_delay: Duration,
+}
+
+impl<B, P, T> Default for LazyBlueprintPlugin<B, P, T>
+where
+    P: Bundle + TryFromBlueprint<B>,
+    T: BlueprintTarget,
+{
+    fn default() -> Self {
+        Self::new(BlueprintPlugin::default())
+    }
+}
+
+impl<B, P, T> LazyBlueprintPlugin<B, P, T>
+where
+    P: Bundle + TryFromBlueprint<B>,
+    T: BlueprintTarget,
+{
+    /// Builds lazily with a configured `BlueprintPlugin`, e.g. one with `on_build_error` set.
+    pub fn new(plugin: BlueprintPlugin<B, P, T>) -> Self {
+        LazyBlueprintPlugin {
+            plugin,
+            teardown_delay: DEFAULT_TEARDOWN_DELAY,
+        }
+    }
+
+    pub fn with_teardown_delay(mut self, delay: Duration) -> Self {
+        self.teardown_delay = delay;
+        self
+    }
+}
+
+impl<B, P, T> LazyBlueprintPlugin<B, P, T>
+where
+    B: Default + Reflect + Send + Sync + 'static,
+    P: Bundle + TryFromBlueprint<B>,
+    T: BlueprintTarget + Send + Sync + 'static,
+{
+    fn tear_down_out_of_view(
+        mut commands: Commands,
+        mut removed_query: Removals<InView, (B, P, T)>,
+        in_view_query: Query<(), With<InView>>,
+        blueprint_query: Query<(), With<Blueprint<B>>>,
+        time: Res<Time>,
+        mut targets: ResMut<LazyTargets<B, P, T>>,
+        mut built_values: ResMut<BuiltValues<B, P, T>>,
+    ) {
+        let now = time.elapsed();
+        let LazyTargets {
+            built,
+            left_view,
+            teardown_delay,
+            ..
+        } = &mut *targets;
+        for entity in removed_query.read() {
+            if built.contains(&entity) {
+                left_view.insert(entity, now);
+            }
+        }
+        left_view.retain(|entity, left_at| {
+            // entities back in view keep their target
+            if in_view_query.contains(*entity) || !built.contains(entity) {
+                return false;
+            }
+            if now.saturating_sub(*left_at) < *teardown_delay {
+                return true;
+            }
+            built.remove(entity);
+            built_values.forget(entity);
+            if blueprint_query.contains(*entity) {
+                let mut entity_commands = commands.entity(*entity);
+                BlueprintPlugin::<B, P, T>::remove_target(&mut entity_commands);
+                entity_commands.remove::<BlueprintBuilt<B>>();
+            }
+            false
+        });
+    }
+
+    #[cfg(feature = "render")]
+    fn update_in_view(mut commands: Commands, blueprint_query: VisibilityQuery<B>) {
+        for (entity, visibility, in_view) in blueprint_query.iter() {
+            if visibility.get() && !in_view {
+                commands.entity(entity).insert(InView);
+            } else if !visibility.get() && in_view {
+                commands.entity(entity).remove::<InView>();
+            }
+        }
+    }
+}
+
+impl<B, P, T> Plugin for LazyBlueprintPlugin<B, P, T>
+where
+    B: Default + GetTypeRegistration + FromReflect + TypePath + Send + Sync + 'static,
+    P: Bundle + TryFromBlueprint<B>,
+    T: BlueprintTarget + Send + Sync + 'static,
+{
+    fn build(&self, app: &mut App) {
+        Removals::<InView, (B, P, T)>::buffer_while_paused(app);
+        app.add_plugins(self.plugin.clone())
+            .insert_resource(LazyTargets::<B, P, T> {
+                built: EntityHashSet::default(),
+                left_view: EntityHashMap::default(),
+                teardown_delay: self.teardown_delay,
+                marker: PhantomData,
+            })
+            .register_type::<InView>()
+            .add_systems(
+                Update,
+                Self::tear_down_out_of_view.in_set(BlueprintSet::Cleanup),
+            );
+
+        #[cfg(feature = "render")]
+        app.add_systems(
+            PostUpdate,
+            (
+                check_visibility::<WithoutMesh<B>>.in_set(VisibilitySystems::CheckVisibility),
+                Self::update_in_view.after(VisibilitySystems::CheckVisibility),
+            ),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{ecs::system::StaticSystemParam, prelude::*, time::TimeUpdateStrategy};
+
+    use super::*;
+    use crate::{BlueprintsPlugin, FromBlueprint};
+
+    #[derive(Default, Reflect)]
+    struct Rect {
+        size: f32,
+    }
+
+    #[derive(Component)]
+    struct RectSize(f32);
+
+    impl FromBlueprint<Rect> for RectSize {
+        type Params<'w, 's> = ();
+        fn from_blueprint(
+            blueprint: &Rect,
+            _: &mut StaticSystemParam<Self::Params<'_, '_>>,
+        ) -> Self {
+            RectSize(blueprint.size)
+        }
+    }
+
+    fn built_count(app: &mut App) -> usize {
+        app.world_mut()
+            .query::<&RectSize>()
+            .iter(app.world())
+            .count()
+    }
+
+    #[test]
+    fn only_visible_blueprints_are_built() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(LazyBlueprintPlugin::<Rect, RectSize>::default())
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+                250,
+            )));
+        let entities = (0..10_000)
+            .map(|index| {
+                let mut entity = app.world_mut().spawn(Blueprint::new(Rect { size: 1. }));
+                if index % 10 == 0 {
+                    entity.insert(InView);
+                }
+                entity.id()
+            })
+            .collect::<Vec<_>>();
+        app.update();
+        assert_eq!(built_count(&mut app), 1_000);
+
+        for entity in entities.iter().step_by(20) {
+            app.world_mut().entity_mut(*entity).remove::<InView>();
+        }
+        for entity in entities.iter().skip(1).step_by(10) {
+            app.world_mut().entity_mut(*entity).insert(InView);
+        }
+        app.update();
+        // the entities that left the view are only torn down after the default delay
+        assert_eq!(built_count(&mut app), 2_000);
+        app.update();
+        app.update();
+        assert_eq!(built_count(&mut app), 1_500);
+    }
+
+    #[test]
+    fn configured_plugins_are_forwarded() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(LazyBlueprintPlugin::new(
+                BlueprintPlugin::<Rect, RectSize>::default().auto_name(),
+            ));
+        let entity = app
+            .world_mut()
+            .spawn((Blueprint::new(Rect { size: 1. }), InView))
+            .id();
+        app.update();
+        assert!(app.world().entity(entity).contains::<RectSize>());
+        assert_eq!(
+            app.world().get::<Name>(entity).map(Name::as_str),
+            Some("Blueprint<Rect>")
+        );
+    }
+
+    #[test]
+    fn teardown_is_delayed() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(
+                LazyBlueprintPlugin::<Rect, RectSize>::default()
+                    .with_teardown_delay(Duration::from_secs(1)),
+            )
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+                250,
+            )));
+        let entity = app
+            .world_mut()
+            .spawn((Blueprint::new(Rect { size: 1. }), InView))
+            .id();
+        app.update();
+        assert!(app.world().entity(entity).contains::<RectSize>());
+
+        // briefly leaving the view does not tear down (or rebuild) the target
+        app.world_mut().entity_mut(entity).remove::<InView>();
+        app.update();
+        app.world_mut().entity_mut(entity).insert(InView);
+        app.world_mut().entity_mut(entity).insert(RectSize(2.));
+        app.update();
+        assert_eq!(app.world().get::<RectSize>(entity).unwrap().0, 2.);
+
+        app.world_mut().entity_mut(entity).remove::<InView>();
+        for _ in 0..3 {
+            app.update();
+        }
+        assert!(app.world().entity(entity).contains::<RectSize>());
+        for _ in 0..2 {
+            app.update();
+        }
+        assert!(!app.world().entity(entity).contains::<RectSize>());
+
+        app.world_mut().entity_mut(entity).insert(InView);
+        app.update();
+        assert_eq!(app.world().get::<RectSize>(entity).unwrap().0, 1.);
+    }
+
+    #[cfg(feature = "render")]
+    #[test]
+    fn blueprints_in_the_camera_view_are_built() {
+        use bevy::render::{
+            camera::Camera,
+            primitives::{Aabb, Frustum},
+            view::{VisibilityPlugin, VisibleEntities},
+        };
+
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            TransformPlugin,
+            VisibilityPlugin,
+            BlueprintsPlugin,
+        ))
+        .init_asset::<Mesh>()
+        .add_plugins(
+            LazyBlueprintPlugin::<Rect, RectSize>::default().with_teardown_delay(Duration::ZERO),
+        );
+        app.world_mut().spawn((
+            Camera::default(),
+            VisibleEntities::default(),
+            GlobalTransform::default(),
+            Frustum::from_clip_from_world(&Mat4::orthographic_rh(-10., 10., -10., 10., 0., 100.)),
+        ));
+        let mut spawn = |x: f32, aabb: Option<Aabb>| {
+            let mut entity = app.world_mut().spawn(Blueprint::bundle(Rect { size: 1. }));
+            entity.insert(Transform::from_xyz(x, 0., -10.));
+            if let Some(aabb) = aabb {
+                entity.insert(aabb);
+            }
+            entity.id()
+        };
+        let aabb = Aabb::from_min_max(Vec3::splat(-0.5), Vec3::splat(0.5));
+        let inside = spawn(0., Some(aabb));
+        let outside = spawn(50., Some(aabb));
+        let unbounded = spawn(50., None);
+
+        // visibility is checked in `PostUpdate`, so blueprints are built in the next frame
+        app.update();
+        app.update();
+        assert!(app.world().entity(inside).contains::<RectSize>());
+        assert!(!app.world().entity(outside).contains::<RectSize>());
+        assert!(app.world().entity(unbounded).contains::<RectSize>());
+
+        app.world_mut()
+            .get_mut::<Transform>(inside)
+            .unwrap()
+            .translation
+            .x = 50.;
+        app.update();
+        app.update();
+        assert!(!app.world().entity(inside).contains::<RectSize>());
+    }
+}
@@ -1,14 +1,172 @@
-use std::marker::PhantomData;
+use std::{marker::PhantomData, sync::Arc};
 
+#[cfg(feature = "render")]
+use bevy::render::view::RenderLayers;
 use bevy::{
-    ecs::system::{EntityCommands, StaticSystemParam, SystemParam},
+    diagnostic::{Diagnostic, DiagnosticPath, Diagnostics, RegisterDiagnostic},
+    ecs::{
+        archetype::Archetypes,
+        component::{ComponentHooks, ComponentId, StorageType, Tick},
+        entity::{Entities, EntityHashMap, EntityHashSet},
+        query::QueryFilter,
+        schedule::{InternedScheduleLabel, InternedSystemSet, ScheduleLabel, SystemConfigs},
+        system::{
+            EntityCommands, RunSystemOnce, StaticSystemParam, SystemBuffer, SystemChangeTick,
+            SystemMeta, SystemParam, SystemState,
+        },
+    },
     prelude::*,
     reflect::GetTypeRegistration,
+    utils::{get_short_name, HashMap, HashSet, Instant},
 };
 
+mod apply;
+pub use apply::*;
+mod args;
+pub use args::*;
+#[cfg(feature = "asset")]
+mod assets;
+#[cfg(feature = "asset")]
+pub use assets::*;
+mod bake;
+pub use bake::*;
+mod binding;
+pub use binding::*;
+mod budget;
+pub use budget::*;
+mod builder;
+pub use builder::*;
+mod component;
+pub use component::*;
+mod composite;
+pub use composite::*;
+mod container;
+pub use container::*;
+mod dependency;
+pub use dependency::*;
+mod describe;
+pub use describe::*;
+mod dyn_blueprint;
+pub use dyn_blueprint::*;
+mod flush;
+pub use flush::*;
+#[cfg(feature = "render")]
+mod handles;
+#[cfg(feature = "render")]
+pub use handles::*;
+#[cfg(feature = "asset")]
+mod dynamic;
+#[cfg(feature = "asset")]
+pub use dynamic::*;
+mod history;
+pub use history::*;
+mod inherit;
+pub use inherit::*;
+#[cfg(feature = "last-built")]
+mod last_built;
+#[cfg(feature = "last-built")]
+pub use last_built::*;
+mod lazy;
+pub use lazy::*;
+mod materialize;
+pub use materialize::*;
+#[cfg(feature = "render")]
+mod mesh;
+#[cfg(feature = "render")]
+pub use mesh::*;
+mod name;
+pub use name::*;
+mod node;
+pub use node::*;
+mod orphan;
+pub use orphan::*;
+mod partial;
+pub use partial::*;
+mod pause;
+pub use pause::*;
+#[cfg(feature = "render")]
+mod preview;
+#[cfg(feature = "render")]
+pub use preview::*;
+#[cfg(feature = "asset")]
+mod raw;
+#[cfg(feature = "asset")]
+pub use raw::*;
+mod registry;
+pub use registry::*;
+mod remove;
+pub use remove::*;
+mod reset;
+pub use reset::*;
+mod resource;
+pub use resource::*;
+#[cfg(feature = "scene")]
+mod scene;
+#[cfg(feature = "scene")]
+pub use scene::*;
+#[cfg(feature = "scene")]
+mod scene_nodes;
+#[cfg(feature = "scene")]
+pub use scene_nodes::*;
+mod sibling;
+pub use sibling::*;
+#[cfg(feature = "asset")]
+mod snapshot;
+#[cfg(feature = "asset")]
+pub use snapshot::*;
+mod spawn;
+pub use spawn::*;
+mod startup;
+pub use startup::*;
+mod transaction;
+pub use transaction::*;
+mod trigger;
+pub use trigger::*;
+mod unwind;
+use unwind::*;
+
+/// The commonly used types and traits: `use bevy_reactive_blueprints::prelude::*;`.
+///
+/// Everything (including the items left out of the prelude) is also exported from the crate root.
+pub mod prelude {
+    pub use crate::{
+        bake_blueprints, build_blueprints_now, pause_blueprints, reset_all_targets,
+        resolve_by_name, resume_blueprints, ApplyBlueprint, ApplyBlueprintPlugin, AsChild,
+        AsContainerChild, AsNothing, AsSelf, AsSibling, AsSiblingUnder, BindingError, Blueprint,
+        BlueprintArgs, BlueprintBase, BlueprintBasePlugin, BlueprintBinding, BlueprintBuildError,
+        BlueprintBuildStats, BlueprintBundle, BlueprintContainer, BlueprintFlushExt,
+        BlueprintHistoryCommandsExt, BlueprintHistoryPlugin, BlueprintNamePlugin,
+        BlueprintOverrides, BlueprintPlugin, BlueprintPriority, BlueprintRegistryExt,
+        BlueprintResourcePlugin, BlueprintSet, BlueprintSpawnEventsExt, BlueprintSpawned,
+        BlueprintStartupPlugin, BlueprintTarget, BlueprintTrigger, BlueprintWorldExt, Blueprints,
+        BlueprintsPaused, BlueprintsPlugin, BlueprintsSet, Built, ChildParent, CleanupPolicy,
+        ComponentBlueprintPlugin, CompositeBlueprintPlugin, DynBlueprint, DynBlueprintPlugin,
+        ForceAsChild, FromBlueprint, FromBlueprintComposite, FromBlueprintWithArgs, InView,
+        LazyBlueprintPlugin, NameTarget, NamedBlueprint, NamedEntities, OnBuildError,
+        OnPermanentFailure, Pending, PersistSibling, RemoveBlueprintsExt, SpawnBlueprint,
+        StrictBlueprintPlugin, TargetKind, TryFromBlueprint,
+    };
+
+    #[cfg(feature = "asset")]
+    pub use crate::{
+        restore_blueprints, snapshot_blueprints, BlueprintAssets, BlueprintSnapshot,
+        DynamicBlueprint, DynamicBlueprintPlugin, RawBlueprint, RawBlueprintPlugin,
+    };
+    #[cfg(feature = "render")]
+    pub use crate::{
+        ApplyHandles, ApplyHandlesPlugin, BlueprintPreview, BlueprintPreviewPlugin,
+        MeshMaterialParams,
+    };
+}
+
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, SystemSet)]
 pub struct BlueprintsSet;
 
+/// The stages of `BlueprintsSet`, which run in order.
+///
+/// `BlueprintsPlugin` adds `apply_deferred` to `BlueprintSet::Flush`, so systems ordered after it
+/// see every target (including `AsChild` children) built this frame, even in a schedule with
+/// `auto_insert_apply_deferred` disabled.
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, SystemSet)]
 pub enum BlueprintSet {
     #[default]
@@ -17,36 +175,424 @@ pub enum BlueprintSet {
     Flush,
 }
 
+/// Builds a prefab from blueprint data of type `T`, with any system params it needs.
+///
+/// `from_blueprint` runs again on every rebuild, so state captured from the params (such as the
+/// elapsed `Time` when the prefab was built) is captured anew whenever the blueprint changes.
 pub trait FromBlueprint<T> {
     type Params<'w, 's>: SystemParam;
 
     fn from_blueprint(blueprint: &T, params: &mut StaticSystemParam<Self::Params<'_, '_>>) -> Self;
 }
 
-#[derive(Debug, Component, Default, Reflect)]
+// a tuple of prefabs is built from the same blueprint in one pass, so that every part is inserted
+// (and removed) together
+macro_rules! impl_from_blueprint_tuple {
+    ($(($P:ident, $p:ident)),*) => {
+        impl<T: 'static, $($P: FromBlueprint<T> + 'static),*> FromBlueprint<T> for ($($P,)*) {
+            type Params<'w, 's> = ($(StaticSystemParam<'w, 's, $P::Params<'static, 'static>>,)*);
+
+            fn from_blueprint(
+                blueprint: &T,
+                params: &mut StaticSystemParam<Self::Params<'_, '_>>,
+            ) -> Self {
+                let ($($p,)*) = &mut **params;
+                ($($P::from_blueprint(blueprint, $p),)*)
+            }
+        }
+    };
+}
+
+bevy::utils::all_tuples!(impl_from_blueprint_tuple, 2, 8, P, p);
+
+/// A fallible version of `FromBlueprint`, for prefabs that cannot always be built (e.g. because an
+/// asset is missing). Every `FromBlueprint` prefab is also a `TryFromBlueprint` prefab which never
+/// fails.
+pub trait TryFromBlueprint<T>: Sized {
+    type Params<'w, 's>: SystemParam;
+    type Error: std::fmt::Display;
+
+    fn try_from_blueprint(
+        blueprint: &T,
+        params: &mut StaticSystemParam<Self::Params<'_, '_>>,
+    ) -> Result<Self, Self::Error>;
+}
+
+impl<T, P: FromBlueprint<T>> TryFromBlueprint<T> for P {
+    type Params<'w, 's> = P::Params<'w, 's>;
+    type Error = std::convert::Infallible;
+
+    fn try_from_blueprint(
+        blueprint: &T,
+        params: &mut StaticSystemParam<Self::Params<'_, '_>>,
+    ) -> Result<Self, Self::Error> {
+        Ok(P::from_blueprint(blueprint, params))
+    }
+}
+
+/// Blueprint data of type `B`, usually on an entity. It can also be stored as a resource for a
+/// `BlueprintResourcePlugin`.
+#[derive(Debug, Resource, Default, Reflect)]
 #[reflect(Component)]
 pub struct Blueprint<B: Default>(B);
 
+impl<B: Default + Send + Sync + 'static> Component for Blueprint<B> {
+    const STORAGE_TYPE: StorageType = StorageType::Table;
+
+    // records the blueprint types that are spawned, for `warn_unregistered_blueprints`
+    fn register_component_hooks(hooks: &mut ComponentHooks) {
+        hooks.on_add(record_spawned_blueprint);
+    }
+}
+
 impl<B: Default> Blueprint<B> {
     pub fn new(data: B) -> Self {
         Blueprint(data)
     }
 }
 
+impl<B: Default + Send + Sync + 'static> Blueprint<B> {
+    /// Wraps the data in a `BlueprintBundle`, named after the blueprint type and placed at the
+    /// origin.
+    pub fn bundle(data: B) -> BlueprintBundle<B> {
+        BlueprintBundle {
+            blueprint: Blueprint(data),
+            name: Name::new(get_short_name(std::any::type_name::<B>())),
+            transform: Transform::default(),
+            global_transform: GlobalTransform::default(),
+            #[cfg(feature = "render")]
+            visibility: VisibilityBundle::default(),
+        }
+    }
+}
+
+/// A blueprint along with a `Name` and spatial components, for spawning blueprints (e.g. from an
+/// editor) that should show up named and positioned.
+///
+/// Note that an `AsSelf` prefab containing a `Transform` will overwrite this transform whenever
+/// it is built, so position such blueprints through the blueprint data instead.
+#[derive(Bundle)]
+pub struct BlueprintBundle<B: Default + Send + Sync + 'static> {
+    pub blueprint: Blueprint<B>,
+    pub name: Name,
+    pub transform: Transform,
+    pub global_transform: GlobalTransform,
+    #[cfg(feature = "render")]
+    pub visibility: VisibilityBundle,
+}
+
+impl<B: Default + Send + Sync + 'static> Default for BlueprintBundle<B> {
+    fn default() -> Self {
+        Blueprint::bundle(B::default())
+    }
+}
+
+impl<B: Default + Send + Sync + 'static> BlueprintBundle<B> {
+    pub fn with_name(mut self, name: impl Into<std::borrow::Cow<'static, str>>) -> Self {
+        self.name = Name::new(name);
+        self
+    }
+
+    pub fn with_transform(mut self, transform: Transform) -> Self {
+        self.transform = transform;
+        self
+    }
+}
+
+/// Reads the data of the entity's `Blueprint<B>`, if it has one.
+pub fn blueprint_data<B>(entity_ref: EntityRef<'_>) -> Option<&B>
+where
+    B: Default + Send + Sync + 'static,
+{
+    entity_ref
+        .get::<Blueprint<B>>()
+        .map(|blueprint| &blueprint.0)
+}
+
 #[derive(Debug, Component, Default, Reflect)]
 #[reflect(Component)]
 pub struct IsBlueprint;
 
+/// Links an entity spawned by a blueprint (e.g. an `AsChild` prefab) back to the blueprint entity
+/// that built it.
+#[derive(Clone, Copy, Debug, Component, Deref, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+pub struct BlueprintSource(pub Entity);
+
+/// Query data needed by `nearest_blueprint`.
+pub type BlueprintAncestry = (
+    Has<IsBlueprint>,
+    Option<&'static BlueprintSource>,
+    Option<&'static Parent>,
+);
+
+/// Finds the blueprint entity that `entity` belongs to: the entity itself if it is a built
+/// blueprint, the blueprint that spawned it, or else the nearest such ancestor.
+///
+/// Nested blueprints resolve to the innermost blueprint.
+pub fn nearest_blueprint(entity: Entity, query: &Query<BlueprintAncestry>) -> Option<Entity> {
+    let mut current = entity;
+    loop {
+        let (is_blueprint, source, parent) = query.get(current).ok()?;
+        if is_blueprint {
+            return Some(current);
+        }
+        if let Some(source) = source {
+            return Some(source.0);
+        }
+        current = parent?.get();
+    }
+}
+
+/// Marks an entity whose `Blueprint<B>` has been built by a `BlueprintPlugin`.
+///
+/// This lives on the blueprint entity for every target, so for `AsChild` it means the child has
+/// been spawned rather than that the entity has any prefab components of its own.
+#[derive(Debug, Component)]
+pub struct BlueprintBuilt<B: Send + Sync + 'static>(PhantomData<B>);
+
+impl<B: Send + Sync + 'static> Default for BlueprintBuilt<B> {
+    fn default() -> Self {
+        BlueprintBuilt(PhantomData)
+    }
+}
+
+/// How many times the blueprints of an entity have been built, e.g. to find entities that rebuild
+/// too often. Every prefab built on the entity counts.
+///
+/// Updating it does not trigger change detection, so it can be watched without causing rebuilds.
+#[derive(Clone, Copy, Debug, Component, Reflect)]
+#[reflect(Component)]
+pub struct BlueprintBuildStats {
+    pub count: u32,
+    /// The change tick of the last build.
+    pub last_tick: Tick,
+}
+
+// for `auto_name`, once the prefab is attached
+fn insert_type_name<B: Default + 'static>(entity: Entity, world: &mut World) {
+    let Some(mut entity_mut) = world.get_entity_mut(entity) else {
+        return;
+    };
+    if !entity_mut.contains::<Name>() {
+        entity_mut.insert(Name::new(get_short_name(
+            std::any::type_name::<Blueprint<B>>(),
+        )));
+    }
+}
+
+fn record_build(entity: Entity, world: &mut World) {
+    let last_tick = world.change_tick();
+    match world.get_mut::<BlueprintBuildStats>(entity) {
+        Some(mut stats) => {
+            let stats = stats.bypass_change_detection();
+            stats.count += 1;
+            stats.last_tick = last_tick;
+        }
+        None => {
+            world.entity_mut(entity).insert(BlueprintBuildStats {
+                count: 1,
+                last_tick,
+            });
+        }
+    }
+}
+
+// gives the parent of a spawned child the components it needs for the child's `GlobalTransform`
+// (and visibility) to propagate, keeping those it already has
+fn insert_spatial_parent(entity: Entity, world: &mut World) {
+    let Some(mut entity_mut) = world.get_entity_mut(entity) else {
+        return;
+    };
+    if !entity_mut.contains::<Transform>() {
+        entity_mut.insert(Transform::default());
+    }
+    if !entity_mut.contains::<GlobalTransform>() {
+        entity_mut.insert(GlobalTransform::default());
+    }
+    #[cfg(feature = "render")]
+    {
+        if !entity_mut.contains::<Visibility>() {
+            entity_mut.insert(Visibility::default());
+        }
+        if !entity_mut.contains::<InheritedVisibility>() {
+            entity_mut.insert(InheritedVisibility::default());
+        }
+        if !entity_mut.contains::<ViewVisibility>() {
+            entity_mut.insert(ViewVisibility::default());
+        }
+    }
+}
+
+// the cleanup of `keep_on_cleanup`: removes the components of the prefab `P` from its targets,
+// except those of `K`
+fn remove_target_except<B: 'static, P: Bundle, T: 'static, K: Bundle>(
+    world: &mut World,
+    entity: Entity,
+) {
+    let kept = world.init_bundle::<K>().components().to_vec();
+    for (target, components) in built_targets::<B, P, T>(world, entity) {
+        let mut target_mut = world.entity_mut(target);
+        for component_id in components {
+            if !kept.contains(&component_id) {
+                target_mut.remove_by_id(component_id);
+            }
+        }
+        forget_components::<B, P, T>(target, world);
+    }
+    world.entity_mut(entity).remove::<IsBlueprint>();
+}
+
+fn warn_kept_outside_prefab<P: Bundle, K: Bundle>(world: &mut World) {
+    let prefab = world.init_bundle::<P>().components().to_vec();
+    let kept = world.init_bundle::<K>().components().to_vec();
+    for component_id in kept {
+        if prefab.contains(&component_id) {
+            continue;
+        }
+        if let Some(info) = world.components().get_info(component_id) {
+            warn!(
+                "{} is kept on cleanup, but is not a component of the prefab {}",
+                get_short_name(info.name()),
+                get_short_name(std::any::type_name::<P>()),
+            );
+        }
+    }
+}
+
+/// Sent whenever a prefab of `Blueprint<B>` is built on an entity.
+///
+/// `child` is the entity spawned to hold the prefab (for `AsChild` and `ForceAsChild` builds), so
+/// that it can be extended right away, e.g. with a collider. The child exists once
+/// `BlueprintSet::Flush` has run. Each prefab sends its own event, so entities with several
+/// `AsChild` prefabs report every child.
+#[derive(Debug, Event)]
+pub struct BlueprintSpawned<B: Send + Sync + 'static> {
+    pub blueprint: Entity,
+    pub child: Option<Entity>,
+    marker: PhantomData<B>,
+}
+
+/// Query filter for entities whose `Blueprint<B>` has been built.
+pub type Built<B> = (With<Blueprint<B>>, With<BlueprintBuilt<B>>);
+/// Query filter for entities whose `Blueprint<B>` is still waiting to be built.
+pub type Pending<B> = (With<Blueprint<B>>, Without<BlueprintBuilt<B>>);
+
+/// All entities with a `Blueprint<B>` (matching the filter `F`, e.g. `Built<B>`).
+///
+/// Mutating blueprints through this param marks them as changed, so they are rebuilt.
+#[derive(SystemParam)]
+pub struct Blueprints<'w, 's, B, F = ()>
+where
+    B: Default + Send + Sync + 'static,
+    F: QueryFilter + 'static,
+{
+    query: Query<'w, 's, (Entity, &'static mut Blueprint<B>), F>,
+}
+
+impl<'w, 's, B, F> Blueprints<'w, 's, B, F>
+where
+    B: Default + Send + Sync + 'static,
+    F: QueryFilter + 'static,
+{
+    pub fn iter(&self) -> impl Iterator<Item = (Entity, &B)> {
+        self.query
+            .iter()
+            .map(|(entity, blueprint)| (entity, &blueprint.0))
+    }
+
+    pub fn get(&self, entity: Entity) -> Option<&B> {
+        self.query
+            .get(entity)
+            .ok()
+            .map(|(_, blueprint)| &blueprint.0)
+    }
+
+    pub fn get_mut(&mut self, entity: Entity) -> Option<Mut<'_, Blueprint<B>>> {
+        self.query
+            .get_mut(entity)
+            .ok()
+            .map(|(_, blueprint)| blueprint)
+    }
+
+    pub fn for_each_mut(&mut self, mut f: impl FnMut(Entity, &mut B)) {
+        for (entity, mut blueprint) in self.query.iter_mut() {
+            f(entity, &mut blueprint.0);
+        }
+    }
+
+    /// Marks every blueprint as changed so that all of them are rebuilt.
+    pub fn rebuild_all(&mut self) {
+        for (_, mut blueprint) in self.query.iter_mut() {
+            blueprint.set_changed();
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.query.is_empty()
+    }
+}
+
 pub struct AsSelf;
+/// Builds the prefab on a child of the blueprint entity. The blueprint entity gets a default
+/// `Transform` and `GlobalTransform` (and visibility components, with the `render` feature) if it
+/// lacks them, so that the child is placed relative to it.
 pub struct AsChild;
+/// Builds nothing, for blueprints whose `from_blueprint` only exists for the side effects of its
+/// `Params` (the prefab is usually `()`).
+pub struct AsNothing;
+
+// marks the children spawned by the `AsChild` prefab `P` of blueprint `B`
+#[derive(Component)]
+struct PrefabChild<B, P>(PhantomData<(B, P)>);
+
+impl<B, P> Default for PrefabChild<B, P> {
+    fn default() -> Self {
+        PrefabChild(PhantomData)
+    }
+}
+
+/// Where a `BlueprintTarget` attaches the built bundle, e.g. for tools previewing the hierarchy
+/// that a blueprint builds. Listed per prefab in the `BlueprintRegistry`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Reflect)]
+pub enum TargetKind {
+    AsSelf,
+    AsChild,
+    AsContainerChild,
+    /// `AsSibling`, and `AsSiblingUnder`.
+    AsSibling,
+    AsNothing,
+    /// A custom target, named by its implementation.
+    Other(&'static str),
+}
 
+/// Where a `BlueprintPlugin` attaches the bundles it builds.
+///
+/// Blueprint plugins call `remove_prefab`, `attach_prefab` and `cleanup_despawned_prefabs`, which
+/// fall back to `remove_target_bundle`, `attach_target_bundle` and `cleanup_despawned`, so custom
+/// targets can implement either set. Only the `_prefab` methods can report the entity spawned to
+/// hold the bundle, or how many entities were despawned.
 pub trait BlueprintTarget {
-    fn remove_target_bundle<T, P: Bundle + FromBlueprint<T>>(entity: &mut EntityCommands);
+    /// Where the built bundle is attached. Custom targets default to `TargetKind::Other("custom")`,
+    /// and should name themselves instead.
+    const KIND: TargetKind = TargetKind::Other("custom");
+
+    /// Whether the built bundle is dropped rather than attached, as with `AsNothing`, so that
+    /// `describe_blueprint` has no components to list for it.
+    const DISCARDS_BUNDLE: bool = false;
+
+    // remove the bundle from the entity by default
+    fn remove_target_bundle<T, P: Bundle + FromBlueprint<T>>(entity: &mut EntityCommands) {
+        entity.remove::<P>();
+    }
 
+    // insert the bundle on the entity by default
     fn attach_target_bundle<T, P: Bundle + FromBlueprint<T>>(
         entity: &mut EntityCommands,
         bundle: P,
-    );
+    ) {
+        entity.insert(bundle);
+    }
 
     // do nothing by default, but if AsChild, clean up orphaned children
     fn cleanup_despawned(
@@ -55,9 +601,58 @@ pub trait BlueprintTarget {
         _query: &Query<(Entity, &Parent)>,
     ) {
     }
+
+    /// Removes the bundle of any prefab, including those built with `TryFromBlueprint` or
+    /// `FromBlueprintWithArgs`. Calls `remove_target_bundle` by default.
+    fn remove_prefab<T: Send + Sync + 'static, P: Bundle + TryFromBlueprint<T>>(
+        entity: &mut EntityCommands,
+    ) {
+        Self::remove_target_bundle::<T, TargetBundle<P>>(entity);
+    }
+
+    /// Attaches the bundle of any prefab, returning the entity spawned to hold it (if any). Calls
+    /// `attach_target_bundle` by default, which spawns none that can be reported.
+    fn attach_prefab<T: Send + Sync + 'static, P: Bundle + TryFromBlueprint<T>>(
+        entity: &mut EntityCommands,
+        bundle: P,
+    ) -> Option<Entity> {
+        Self::attach_target_bundle::<T, TargetBundle<P>>(entity, TargetBundle(bundle));
+        None
+    }
+
+    /// Cleans up after a blueprint entity was despawned, returning how many entities were
+    /// despawned. Calls `cleanup_despawned` by default, which despawns none that can be reported.
+    fn cleanup_despawned_prefabs(
+        commands: &mut Commands,
+        entity: Entity,
+        query: &Query<(Entity, &Parent)>,
+    ) -> usize {
+        Self::cleanup_despawned(commands, entity, query);
+        0
+    }
+}
+
+// any prefab `P`, as a `FromBlueprint` bundle for the `BlueprintTarget` methods that require one
+#[derive(Bundle)]
+struct TargetBundle<P: Bundle>(P);
+
+impl<T, P: Bundle + TryFromBlueprint<T>> FromBlueprint<T> for TargetBundle<P> {
+    type Params<'w, 's> = P::Params<'w, 's>;
+
+    fn from_blueprint(blueprint: &T, params: &mut StaticSystemParam<Self::Params<'_, '_>>) -> Self {
+        match P::try_from_blueprint(blueprint, params) {
+            Ok(bundle) => TargetBundle(bundle),
+            Err(error) => panic!(
+                "failed to build {}: {error}",
+                get_short_name(std::any::type_name::<P>())
+            ),
+        }
+    }
 }
 
 impl BlueprintTarget for AsSelf {
+    const KIND: TargetKind = TargetKind::AsSelf;
+
     fn remove_target_bundle<T, P: Bundle + FromBlueprint<T>>(entity: &mut EntityCommands) {
         entity.remove::<IsBlueprint>();
         entity.remove::<P>();
@@ -73,6 +668,8 @@ impl BlueprintTarget for AsSelf {
 }
 
 impl BlueprintTarget for AsChild {
+    const KIND: TargetKind = TargetKind::AsChild;
+
     fn remove_target_bundle<T, P: Bundle + FromBlueprint<T>>(entity: &mut EntityCommands) {
         entity.remove::<IsBlueprint>();
         entity.despawn_descendants();
@@ -94,372 +691,3949 @@ impl BlueprintTarget for AsChild {
         parent_entity: Entity,
         query: &Query<(Entity, &Parent)>,
     ) {
+        Self::cleanup_despawned_prefabs(commands, parent_entity, query);
+    }
+
+    fn remove_prefab<T: Send + Sync + 'static, P: Bundle + TryFromBlueprint<T>>(
+        entity: &mut EntityCommands,
+    ) {
+        entity.remove::<IsBlueprint>();
+        // only this prefab's children, since other `AsChild` prefabs of the entity may have been
+        // built already this frame
+        entity.add(|entity: Entity, world: &mut World| {
+            let Some(children) = world.get::<Children>(entity) else {
+                return;
+            };
+            let prefab_children = children
+                .iter()
+                .copied()
+                .filter(|child| world.entity(*child).contains::<PrefabChild<T, P>>())
+                .collect::<Vec<_>>();
+            for child in prefab_children {
+                world.entity_mut(child).despawn_recursive();
+            }
+        });
+    }
+
+    // the child is spawned explicitly (rather than with `with_children`) to return its id
+    fn attach_prefab<T: Send + Sync + 'static, P: Bundle + TryFromBlueprint<T>>(
+        entity: &mut EntityCommands,
+        bundle: P,
+    ) -> Option<Entity> {
+        let parent = entity.id();
+        entity.insert(IsBlueprint).add(insert_spatial_parent);
+        let child = entity
+            .commands()
+            .spawn((
+                bundle,
+                BlueprintSource(parent),
+                PrefabChild::<T, P>::default(),
+            ))
+            .set_parent(parent)
+            .id();
+        Some(child)
+    }
+
+    fn cleanup_despawned_prefabs(
+        commands: &mut Commands,
+        parent_entity: Entity,
+        query: &Query<(Entity, &Parent)>,
+    ) -> usize {
+        let mut despawned = 0;
         for (child_entity, parent) in query.iter() {
             if parent.get() == parent_entity {
                 commands.entity(child_entity).despawn_recursive();
+                despawned += 1;
             }
         }
+        despawned
     }
 }
 
-pub struct BlueprintPlugin<B, P: Bundle + FromBlueprint<B>, T: BlueprintTarget = AsSelf> {
-    blueprint_marker: PhantomData<B>,
-    prefab_marker: PhantomData<P>,
-    target_marker: PhantomData<T>,
+impl BlueprintTarget for AsNothing {
+    const KIND: TargetKind = TargetKind::AsNothing;
+    const DISCARDS_BUNDLE: bool = true;
+
+    fn remove_target_bundle<T, P: Bundle + FromBlueprint<T>>(entity: &mut EntityCommands) {
+        entity.remove::<IsBlueprint>();
+    }
+
+    fn attach_target_bundle<T, P: Bundle + FromBlueprint<T>>(
+        entity: &mut EntityCommands,
+        _bundle: P,
+    ) {
+        entity.insert(IsBlueprint);
+    }
 }
 
-impl<B, P, T> Default for BlueprintPlugin<B, P, T>
-where
-    P: Bundle + FromBlueprint<B>,
-    T: BlueprintTarget,
-{
-    fn default() -> Self {
-        Self {
-            blueprint_marker: PhantomData::<B>,
-            prefab_marker: PhantomData::<P>,
-            target_marker: PhantomData::<T>,
-        }
+/// Builds this entity's blueprints as children, even for plugins whose target is not `AsChild`.
+///
+/// Useful when one entity has components that conflict with an `AsSelf` prefab. Only builds that
+/// happen while the marker is present are affected.
+#[derive(Clone, Copy, Debug, Default, Component, Reflect)]
+#[reflect(Component)]
+pub struct ForceAsChild;
+
+/// Records the child that a `P` prefab was built on because of `ForceAsChild`, or the `AsChild`
+/// child of a plugin with a `ChildParent` other than the blueprint entity.
+///
+/// Removing this component, or despawning the blueprint entity, despawns the child.
+#[derive(Debug)]
+pub struct ForcedChild<B, P> {
+    child: Entity,
+    marker: PhantomData<(B, P)>,
+}
+
+impl<B, P> ForcedChild<B, P> {
+    pub fn child(&self) -> Entity {
+        self.child
     }
 }
 
-impl<B, P, T> BlueprintPlugin<B, P, T>
-where
-    B: Default + Send + Sync + 'static,
-    P: Bundle + FromBlueprint<B>,
-    T: BlueprintTarget,
-{
-    fn should_sync_blueprint(blueprint_query: Query<(), Changed<Blueprint<B>>>) -> bool {
-        !blueprint_query.is_empty()
+impl<B: Send + Sync + 'static, P: Send + Sync + 'static> Component for ForcedChild<B, P> {
+    const STORAGE_TYPE: StorageType = StorageType::Table;
+
+    fn register_component_hooks(hooks: &mut ComponentHooks) {
+        hooks.on_remove(|mut world, entity, _| {
+            let child = world.get::<Self>(entity).unwrap().child;
+            world.commands().add(move |world: &mut World| {
+                if let Some(child) = world.get_entity_mut(child) {
+                    child.despawn_recursive();
+                }
+            });
+        });
     }
+}
 
-    fn sync_blueprint_prefab(
-        mut commands: Commands,
-        blueprint_query: Query<(Entity, &Blueprint<B>), Changed<Blueprint<B>>>,
-        mut system_params: StaticSystemParam<P::Params<'_, '_>>,
-    ) {
-        for (entity, blueprint) in blueprint_query.iter() {
-            let mut entity_commands = commands.entity(entity);
-            T::remove_target_bundle::<B, P>(&mut entity_commands);
-            T::attach_target_bundle::<B, P>(
-                &mut entity_commands,
-                P::from_blueprint(&blueprint.0, &mut system_params),
-            );
-        }
+/// Where a `BlueprintPlugin` parents the children holding its prefab (for `AsChild` and
+/// `ForceAsChild` builds), set with `BlueprintPlugin::child_parent`.
+///
+/// The children are linked to their blueprint by `BlueprintSource` wherever they are, and are
+/// despawned with it as usual. If the root does not exist, they stay under the blueprint entity.
+#[derive(Clone, Copy, Debug, Default)]
+pub enum ChildParent {
+    /// Under the blueprint entity.
+    #[default]
+    Blueprint,
+    /// Under a shared root entity, e.g. a scene or UI root.
+    Entity(Entity),
+    /// Under the root entity read from the world when the child is spawned, e.g. from a resource
+    /// holding a root spawned at startup. See `ChildParent::resource`.
+    Resource(fn(&World) -> Option<Entity>),
+}
+
+impl ChildParent {
+    /// Parents the children under the entity held by the resource `R`, once it is inserted.
+    pub fn resource<R: Resource + std::ops::Deref<Target = Entity>>() -> Self {
+        ChildParent::Resource(|world| world.get_resource::<R>().map(|root| **root))
     }
 
-    fn handle_removed_blueprints(
-        mut commands: Commands,
-        mut blueprint_query: RemovedComponents<Blueprint<B>>,
-        child_query: Query<(Entity, &Parent)>,
-    ) {
-        for entity in blueprint_query.read() {
-            if let Some(mut entity_commands) = commands.get_entity(entity) {
-                T::remove_target_bundle::<B, P>(&mut entity_commands);
-            } else {
-                T::cleanup_despawned(&mut commands, entity, &child_query);
+    // parents the child under the root, unless it is the blueprint entity
+    fn reparent(self) -> impl FnOnce(Entity, &mut World) {
+        move |child, world| {
+            let root = match self {
+                ChildParent::Blueprint => return,
+                ChildParent::Entity(root) => Some(root),
+                ChildParent::Resource(read_root) => read_root(world),
+            };
+            match root.filter(|root| world.get_entity(*root).is_some()) {
+                Some(root) => {
+                    world.entity_mut(root).add_child(child);
+                }
+                None => warn!(
+                    "the root to parent child {} under does not exist, so it stays under its \
+                     blueprint entity",
+                    child,
+                ),
             }
         }
     }
 }
 
-impl<B, P, T> Plugin for BlueprintPlugin<B, P, T>
-where
-    B: Default + GetTypeRegistration + FromReflect + TypePath + Send + Sync + 'static,
-    P: Bundle + FromBlueprint<B>,
-    T: BlueprintTarget + Send + Sync + 'static,
-{
-    fn build(&self, app: &mut App) {
-        app.add_systems(
-            Update,
-            (
-                Self::handle_removed_blueprints.in_set(BlueprintSet::Cleanup),
-                Self::sync_blueprint_prefab
-                    .in_set(BlueprintSet::Sync)
-                    .run_if(Self::should_sync_blueprint),
-            ),
-        );
+/// How a `BlueprintPlugin` reacts when its prefab fails to build.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OnBuildError {
+    /// Log the error and leave the blueprint pending.
+    #[default]
+    Log,
+    /// Panic in debug builds, but only log the error in release builds.
+    PanicInDebug,
+    /// Always panic.
+    Panic,
+}
 
-        app.register_type::<Blueprint<B>>().register_type::<B>();
-    }
+/// What a `BlueprintPlugin` with `retry_failed_builds` does once a blueprint has failed to build
+/// too many times in a row.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OnPermanentFailure {
+    /// Stop retrying, leaving the blueprint pending until it changes.
+    #[default]
+    Keep,
+    /// Despawn the blueprint entity (recursively), e.g. for a corrupt spawn.
+    Despawn,
 }
 
-pub struct BlueprintsPlugin;
+/// Sent when a `BlueprintPlugin` with `retry_failed_builds` gives up on building a blueprint,
+/// before the entity is despawned for `OnPermanentFailure::Despawn`.
+#[derive(Debug, Event)]
+pub struct BlueprintBuildError<B: Send + Sync + 'static> {
+    pub blueprint: Entity,
+    /// The last error.
+    pub error: String,
+    pub attempts: u32,
+    marker: PhantomData<B>,
+}
 
-impl Plugin for BlueprintsPlugin {
-    fn build(&self, app: &mut App) {
-        app.configure_sets(
-            Update,
-            (
-                BlueprintSet::Cleanup,
-                BlueprintSet::Sync,
-                BlueprintSet::Flush,
-            )
-                .chain()
-                .in_set(BlueprintsSet),
-        )
-        .add_systems(Update, apply_deferred.in_set(BlueprintSet::Flush));
-    }
+// asks the plugin of the prefab `P` (and target `T`) to build the entity's blueprint, whatever
+// the plugin's trigger (so also with `added_only`), and without marking `Blueprint<B>` as changed
+// for the other plugins: when a transaction is retried, a dependency wait ends, or a gate opens
+#[derive(Component)]
+struct RebuildPrefab<B, P, T>(PhantomData<(B, P, T)>);
+
+// the plugin's settings, and the blueprints it was asked to build with `RebuildPrefab`
+type PluginParams<'w, 's, B, P, T> = (
+    Res<'w, PluginSettings<B, P, T>>,
+    Query<'w, 's, (), With<RebuildPrefab<B, P, T>>>,
+);
+
+// how many times in a row the prefab `P` of blueprint `B` failed to build, for
+// `retry_failed_builds`
+#[derive(Component)]
+struct FailedBuilds<B, P> {
+    attempts: u32,
+    marker: PhantomData<(B, P)>,
 }
 
-#[cfg(test)]
-mod tests {
-    use bevy::{color::palettes, prelude::*};
+// a condition set with `build_if` or `in_state_blueprints`
+type BuildIf = Arc<dyn Fn(&World) -> bool + Send + Sync>;
 
-    use super::*;
+// the conditions of a plugin, and whether they all held when last checked
+#[derive(Resource)]
+struct BuildCondition<B, P, T> {
+    conditions: Vec<BuildIf>,
+    holds: bool,
+    marker: PhantomData<(B, P, T)>,
+}
 
-    #[derive(Clone, Default, Reflect)]
+impl<B, P, T> BuildCondition<B, P, T> {
+    fn check(&self, world: &World) -> bool {
+        self.conditions.iter().all(|condition| condition(world))
+    }
+}
+
+/// What a `BlueprintPlugin` does with the built target when `Blueprint<B>` is removed from an
+/// entity that still exists.
+#[derive(Clone, Copy, Debug, Default)]
+pub enum CleanupPolicy {
+    /// Remove the target: the prefab components for `AsSelf`, or the spawned children for
+    /// `AsChild`.
+    #[default]
+    Remove,
+    /// Keep the target, detaching it from the blueprint. Inserting the blueprint again replaces
+    /// the kept target with a new build.
+    Keep,
+    /// Call the function with the entity instead, while the target (and so the values it was
+    /// built with) is still in place, e.g. to keep only some of the components.
+    Custom(fn(&mut World, Entity)),
+}
+
+#[derive(Clone, Debug, Default)]
+struct BlueprintSettings {
+    on_build_error: OnBuildError,
+    cleanup: CleanupPolicy,
+    skip_unchanged: bool,
+    rebuild_on_parent_change: bool,
+    added_only: bool,
+    only_build_in_update: bool,
+    auto_name: bool,
+    // the attempts before a failed build is given up on, and what happens then
+    retry_failed: Option<(u32, OnPermanentFailure)>,
+}
+
+#[derive(Resource)]
+struct PluginSettings<B, P: TryFromBlueprint<B>, T> {
+    settings: BlueprintSettings,
+    partial_update: Option<PartialUpdate<B, P>>,
+    with_child: Option<fn(&mut EntityCommands)>,
+    post_build: Option<fn(&mut EntityCommands, Entity)>,
+    child_parent: ChildParent,
+    // clones each built bundle into a `LastBuilt<P>`
+    #[cfg(feature = "last-built")]
+    snapshot: Option<fn(&P) -> P>,
+    #[cfg(feature = "render")]
+    render_layers: Option<RenderLayers>,
+    // the marker set with `gated_by`
+    gate: Option<ComponentId>,
+    // builds every blueprint with the plugin's args, for `materialize_blueprint_entity`
+    build_all: fn(&mut World),
+    // where the time spent in the sync system is measured
+    sync_diagnostic: DiagnosticPath,
+    marker: PhantomData<(B, P, T)>,
+}
+
+// the sync system of the `order`th prefab registered for blueprint `B`
+// (the traits are implemented by hand, since deriving them would require them of `B`)
+#[derive(SystemSet)]
+struct PrefabSyncSet<B> {
+    order: usize,
+    marker: PhantomData<B>,
+}
+
+impl<B> PrefabSyncSet<B> {
+    fn new(order: usize) -> Self {
+        PrefabSyncSet {
+            order,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<B> Clone for PrefabSyncSet<B> {
+    fn clone(&self) -> Self {
+        Self::new(self.order)
+    }
+}
+
+impl<B> PartialEq for PrefabSyncSet<B> {
+    fn eq(&self, other: &Self) -> bool {
+        self.order == other.order
+    }
+}
+
+impl<B> Eq for PrefabSyncSet<B> {}
+
+impl<B> std::hash::Hash for PrefabSyncSet<B> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.order.hash(state);
+    }
+}
+
+impl<B> std::fmt::Debug for PrefabSyncSet<B> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "PrefabSyncSet<{}>({})",
+            get_short_name(std::any::type_name::<B>()),
+            self.order
+        )
+    }
+}
+
+// the sync systems of every prefab of blueprint `B`, for `flush_blueprints_between`
+#[derive(SystemSet)]
+struct BlueprintSyncSet<B>(PhantomData<B>);
+
+impl<B> Default for BlueprintSyncSet<B> {
+    fn default() -> Self {
+        BlueprintSyncSet(PhantomData)
+    }
+}
+
+impl<B> Clone for BlueprintSyncSet<B> {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl<B> PartialEq for BlueprintSyncSet<B> {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl<B> Eq for BlueprintSyncSet<B> {}
+
+impl<B> std::hash::Hash for BlueprintSyncSet<B> {
+    fn hash<H: std::hash::Hasher>(&self, _: &mut H) {}
+}
+
+impl<B> std::fmt::Debug for BlueprintSyncSet<B> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "BlueprintSyncSet<{}>",
+            get_short_name(std::any::type_name::<B>())
+        )
+    }
+}
+
+type SyncData<B> = (
+    Entity,
+    Ref<'static, Blueprint<B>>,
+    Has<InView>,
+    Has<ForceAsChild>,
+);
+
+// the args passed to the builds of plugins with `with_args`
+type ArgsQuery<'w, 's, A> = Query<'w, 's, Ref<'static, BlueprintArgs<A>>>;
+
+// blueprints that matched the trigger's filter `F`, or whose entity came into view for lazy plugins
+type SyncQuery<'w, 's, B, F> = Query<'w, 's, SyncData<B>, Or<(F, Added<InView>)>>;
+
+// blueprint entities that were reparented or moved, for `rebuild_on_parent_change`
+type MovedQuery<'w, 's, B> =
+    Query<'w, 's, SyncData<B>, Or<(Changed<Parent>, Changed<GlobalTransform>)>>;
+
+// the blueprints to sync: changed ones, and moved ones for `rebuild_on_parent_change`, except those
+// waiting for their dependencies
+#[derive(SystemParam)]
+struct SyncQueries<'w, 's, B, F, A = ()>
+where
+    B: Default + Send + Sync + 'static,
+    F: QueryFilter + 'static,
+    A: Send + Sync + 'static,
+{
+    changed: SyncQuery<'w, 's, B, F>,
+    // the blueprints that matched `F` itself
+    triggered: Query<'w, 's, (), F>,
+    moved: MovedQuery<'w, 's, B>,
+    // every blueprint, for builds queued by a `BlueprintBudget`
+    blueprints: Query<'w, 's, SyncData<B>>,
+    dependencies: Res<'w, BlueprintDependencies>,
+    // to tell which blueprints changed since `build_blueprints_now` built them
+    ticks: SystemChangeTick,
+    args: ArgsQuery<'w, 's, A>,
+    // to tell which entities have the marker set with `gated_by`
+    entities: &'w Entities,
+    archetypes: &'w Archetypes,
+}
+
+impl<B, F, A> SyncQueries<'_, '_, B, F, A>
+where
+    B: Default + Send + Sync + 'static,
+    F: QueryFilter + 'static,
+    A: Send + Sync + 'static,
+{
+    fn is_gate_open(&self, entity: Entity, gate: Option<ComponentId>) -> bool {
+        let Some(gate) = gate else {
+            return true;
+        };
+        self.entities
+            .get(entity)
+            .is_some_and(|location| self.archetypes[location.archetype_id].contains(gate))
+    }
+}
+
+// the blueprint values each entity was last built from, when skipping unchanged blueprints or
+// updating them partially, and the entities holding the prefabs for partial updates
+#[derive(Resource)]
+struct BuiltValues<B, P, T> {
+    values: EntityHashMap<Box<dyn Reflect>>,
+    targets: EntityHashMap<Entity>,
+    // blueprints that have not changed since this tick were built by `build_blueprints_now`
+    built_now: Option<Tick>,
+    // the entity that `materialize_blueprint_entity` is building, alone, and the entities it built
+    // with the tick they were built at, which are likewise skipped until they change
+    only: Option<Entity>,
+    materialized: EntityHashMap<Tick>,
+    marker: PhantomData<(B, P, T)>,
+}
+
+impl<B, P, T> Default for BuiltValues<B, P, T> {
+    fn default() -> Self {
+        Self {
+            values: EntityHashMap::default(),
+            targets: EntityHashMap::default(),
+            built_now: None,
+            only: None,
+            materialized: EntityHashMap::default(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<B, P, T> BuiltValues<B, P, T> {
+    fn forget(&mut self, entity: &Entity) {
+        self.values.remove(entity);
+        self.targets.remove(entity);
+    }
+}
+
+// the systems of a plugin with `with_args`, which read the args type
+#[derive(Clone, Copy)]
+struct ArgsSystems {
+    // given whether the plugin is `added_only`
+    sync_system: fn(bool) -> SystemConfigs,
+    build_all: fn(&mut World),
+    build_now: fn(&mut World),
+}
+
+pub struct BlueprintPlugin<B, P: Bundle + TryFromBlueprint<B>, T: BlueprintTarget = AsSelf> {
+    settings: BlueprintSettings,
+    partial_update: Option<PartialUpdate<B, P>>,
+    with_child: Option<fn(&mut EntityCommands)>,
+    post_build: Option<fn(&mut EntityCommands, Entity)>,
+    child_parent: ChildParent,
+    // the sync system for the `BlueprintTrigger` set with `trigger`
+    sync_system: Option<fn() -> SystemConfigs>,
+    args: Option<ArgsSystems>,
+    // adds the systems for the marker set with `gated_by`, returning its id
+    gate: Option<fn(&mut App) -> ComponentId>,
+    // warns about the components kept by `keep_on_cleanup` that the prefab does not have
+    check_kept: Option<fn(&mut World)>,
+    build_if: Vec<BuildIf>,
+    // the schedules that also apply the build conditions, such as `OnExit` for
+    // `in_state_blueprints`
+    build_if_schedules: Vec<InternedScheduleLabel>,
+    // the sets that the sync system runs after, set with `after_set`
+    after_sets: Vec<InternedSystemSet>,
+    #[cfg(feature = "last-built")]
+    snapshot: Option<fn(&P) -> P>,
+    #[cfg(feature = "render")]
+    render_layers: Option<RenderLayers>,
+    blueprint_marker: PhantomData<B>,
+    prefab_marker: PhantomData<P>,
+    target_marker: PhantomData<T>,
+}
+
+// every field is `Clone`, but deriving it would require `B`, `P` and `T` to be
+impl<B, P, T> Clone for BlueprintPlugin<B, P, T>
+where
+    P: Bundle + TryFromBlueprint<B>,
+    T: BlueprintTarget,
+{
+    fn clone(&self) -> Self {
+        Self {
+            settings: self.settings.clone(),
+            partial_update: self.partial_update,
+            with_child: self.with_child,
+            post_build: self.post_build,
+            child_parent: self.child_parent,
+            sync_system: self.sync_system,
+            args: self.args,
+            gate: self.gate,
+            check_kept: self.check_kept,
+            build_if: self.build_if.clone(),
+            build_if_schedules: self.build_if_schedules.clone(),
+            after_sets: self.after_sets.clone(),
+            #[cfg(feature = "last-built")]
+            snapshot: self.snapshot,
+            #[cfg(feature = "render")]
+            render_layers: self.render_layers.clone(),
+            blueprint_marker: PhantomData::<B>,
+            prefab_marker: PhantomData::<P>,
+            target_marker: PhantomData::<T>,
+        }
+    }
+}
+
+impl<B, P, T> Default for BlueprintPlugin<B, P, T>
+where
+    P: Bundle + TryFromBlueprint<B>,
+    T: BlueprintTarget,
+{
+    fn default() -> Self {
+        Self {
+            settings: BlueprintSettings::default(),
+            partial_update: None,
+            with_child: None,
+            post_build: None,
+            child_parent: ChildParent::Blueprint,
+            sync_system: None,
+            args: None,
+            gate: None,
+            check_kept: None,
+            build_if: Vec::new(),
+            build_if_schedules: Vec::new(),
+            after_sets: Vec::new(),
+            #[cfg(feature = "last-built")]
+            snapshot: None,
+            #[cfg(feature = "render")]
+            render_layers: None,
+            blueprint_marker: PhantomData::<B>,
+            prefab_marker: PhantomData::<P>,
+            target_marker: PhantomData::<T>,
+        }
+    }
+}
+
+impl<B, P, T> BlueprintPlugin<B, P, T>
+where
+    P: Bundle + TryFromBlueprint<B>,
+    T: BlueprintTarget,
+{
+    /// Starts configuring the plugin. `BlueprintPlugin` is its own builder: every option is a
+    /// method returning the updated plugin, which is then added to the app as is, e.g.
+    /// `BlueprintPlugin::<B, P>::builder().skip_unchanged().cleanup_policy(CleanupPolicy::Keep)`.
+    ///
+    /// This is the same as `default()`, which leaves every option off.
+    pub fn builder() -> Self {
+        Self::default()
+    }
+
+    pub fn on_build_error(mut self, on_build_error: OnBuildError) -> Self {
+        self.settings.on_build_error = on_build_error;
+        self
+    }
+
+    /// Retries failed builds in the next frame, until a blueprint has failed to build
+    /// `max_attempts` times in a row. It then sends a `BlueprintBuildError<B>` and applies
+    /// `on_permanent_failure`. A successful build resets the count, so that transient failures
+    /// (e.g. an asset that is still loading) recover.
+    ///
+    /// Members of a `BlueprintTransaction` are retried by their transaction instead.
+    pub fn retry_failed_builds(
+        mut self,
+        max_attempts: u32,
+        on_permanent_failure: OnPermanentFailure,
+    ) -> Self {
+        self.settings.retry_failed = Some((max_attempts, on_permanent_failure));
+        self
+    }
+
+    /// Sets what happens to the built target when the blueprint is removed, `Remove` by default.
+    /// Targets are always cleaned up when the blueprint entity is despawned.
+    pub fn cleanup_policy(mut self, cleanup: CleanupPolicy) -> Self {
+        self.settings.cleanup = cleanup;
+        self
+    }
+
+    /// Keeps the components of `K` (e.g. an `Enemy` marker) when the blueprint is removed, and
+    /// removes the rest of the target's components as `CleanupPolicy::Remove` would. This replaces
+    /// the cleanup policy. Children spawned to hold the target are kept, with only the `K`
+    /// components of the prefab.
+    ///
+    /// Components of `K` that are not in the prefab are ignored, with a warning.
+    pub fn keep_on_cleanup<K: Bundle>(mut self) -> Self
+    where
+        B: 'static,
+        T: 'static,
+    {
+        self.settings.cleanup = CleanupPolicy::Custom(remove_target_except::<B, P, T, K>);
+        self.check_kept = Some(warn_kept_outside_prefab::<P, K>);
+        self
+    }
+
+    /// Runs `with_child` on every child spawned to hold the prefab (for `AsChild`,
+    /// `AsContainerChild` and `ForceAsChild` builds), e.g. to add a `RenderLayers`.
+    ///
+    /// Every rebuild spawns a new child, so this runs again on each rebuild.
+    pub fn with_child(mut self, with_child: fn(&mut EntityCommands)) -> Self {
+        self.with_child = Some(with_child);
+        self
+    }
+
+    /// Runs `post_build` with the target (the blueprint entity for `AsSelf`, or the spawned
+    /// child) and the blueprint entity right after the prefab is attached, e.g. to insert a
+    /// `RenderLayers` computed from state that `from_blueprint` cannot see. It runs last, so what
+    /// it inserts overrides the prefab's components.
+    ///
+    /// It runs again on every rebuild, so it should only insert (or replace) components. Components
+    /// it inserts on the blueprint entity are not removed with the target.
+    pub fn post_build(mut self, post_build: fn(&mut EntityCommands, Entity)) -> Self {
+        self.post_build = Some(post_build);
+        self
+    }
+
+    /// Parents the children holding the prefab (for `AsChild` and `ForceAsChild` builds) under
+    /// `child_parent` rather than under the blueprint entity, e.g. to nest them all under a UI
+    /// root.
+    pub fn child_parent(mut self, child_parent: ChildParent) -> Self {
+        self.child_parent = child_parent;
+        self
+    }
+
+    /// Puts every target built by this plugin (the blueprint entity for `AsSelf`, or the spawned
+    /// child) on `render_layers`, e.g. for a preview viewport, replacing any `RenderLayers` of the
+    /// prefab. The layers are left in place when the target is cleaned up.
+    #[cfg(feature = "render")]
+    pub fn render_layers(mut self, render_layers: RenderLayers) -> Self {
+        self.render_layers = Some(render_layers);
+        self
+    }
+
+    /// Skips rebuilding blueprints that were marked as changed but whose value is still equal
+    /// (according to `Reflect::reflect_partial_eq`) to the value they were last built from.
+    /// Values that reflection cannot compare are always rebuilt.
+    ///
+    /// This keeps a reflected copy of every built blueprint.
+    pub fn skip_unchanged(mut self) -> Self {
+        self.settings.skip_unchanged = true;
+        self
+    }
+
+    /// Also rebuilds blueprints whose entity is reparented or whose `GlobalTransform` changes,
+    /// for prefabs (usually `AsChild`) that are built relative to the parent.
+    ///
+    /// Note that a moving entity changes its `GlobalTransform` every frame, and so is rebuilt
+    /// every frame.
+    pub fn rebuild_on_parent_change(mut self) -> Self {
+        self.settings.rebuild_on_parent_change = true;
+        self
+    }
+
+    /// Builds each blueprint once, when `Blueprint<B>` is added, and **never rebuilds it on
+    /// change**: later changes to the blueprint are ignored, as is `rebuild_on_parent_change`.
+    ///
+    /// Builds that the crate itself holds back still happen once they can: a retried
+    /// `BlueprintTransaction`, the end of a `BlueprintDependsOn` wait, a `gated_by` gate opening,
+    /// a `build_if` condition holding again, and the despawn of a `BlueprintContainer` all build
+    /// the blueprint (again).
+    ///
+    /// This is for blueprints that are effectively immutable after spawning, and saves the work
+    /// of checking for changes. Re-insert `Blueprint<B>` (or remove it and insert it again) to
+    /// force a rebuild.
+    pub fn added_only(mut self) -> Self {
+        self.settings.added_only = true;
+        self
+    }
+
+    /// Leaves these blueprints out of `build_blueprints_now`, e.g. because the prefab's params are
+    /// not available yet at startup. They are built in `Update` as usual.
+    pub fn only_build_in_update(mut self) -> Self {
+        self.settings.only_build_in_update = true;
+        self
+    }
+
+    /// Names the blueprint entities it builds after their blueprint type (e.g.
+    /// `Blueprint<Rect>`), for debugging in an editor's hierarchy. Entities that have a `Name`
+    /// (including one inserted by the prefab) keep it.
+    pub fn auto_name(mut self) -> Self {
+        self.settings.auto_name = true;
+        self
+    }
+}
+
+impl<B, P> BlueprintPlugin<B, P>
+where
+    P: Bundle + TryFromBlueprint<B>,
+{
+    /// Builds `P` both on the blueprint entity and on a child, as an `AsSelf` plugin and an
+    /// `AsChild` plugin which can be added together.
+    ///
+    /// The two targets are cleaned up independently, and rebuilt in that order (self, then
+    /// child) like any two prefabs of the same blueprint.
+    pub fn self_and_child() -> (Self, BlueprintPlugin<B, P, AsChild>) {
+        (Self::default(), BlueprintPlugin::default())
+    }
+}
+
+impl<B, P, T> BlueprintPlugin<B, P, T>
+where
+    B: Default + Reflect + Send + Sync + 'static,
+    P: Bundle + TryFromBlueprint<B>,
+    T: BlueprintTarget + Send + Sync + 'static,
+{
+    fn should_sync_blueprint<F: QueryFilter + 'static>(
+        queries: SyncQueries<B, F, ()>,
+        settings: Res<PluginSettings<B, P, T>>,
+        queued: Res<QueuedBuilds<B, P, T>>,
+        condition: Option<Res<BuildCondition<B, P, T>>>,
+    ) -> bool {
+        if condition.is_some_and(|condition| !condition.holds) {
+            return false;
+        }
+        !queries.changed.is_empty()
+            || !queued.is_empty()
+            || (settings.settings.rebuild_on_parent_change && !queries.moved.is_empty())
+    }
+
+    /// Builds the blueprints selected by the `BlueprintTrigger` `Tr` rather than every changed
+    /// blueprint (`ChangedTrigger`). `added_only` takes precedence over this.
+    pub fn trigger<Tr: BlueprintTrigger<B>>(mut self) -> Self {
+        self.sync_system = Some(Self::sync_system::<Tr, NoArgs>);
+        self
+    }
+
+    /// Builds the blueprints of entities with a `BlueprintArgs<A>` with
+    /// `FromBlueprintWithArgs::from_blueprint_with_args`, and rebuilds them when their args
+    /// change. Entities without args are built as usual.
+    ///
+    /// This replaces the `trigger`, if any.
+    pub fn with_args<A: Send + Sync + 'static>(mut self) -> Self
+    where
+        P: FromBlueprintWithArgs<B, A>,
+    {
+        self.args = Some(ArgsSystems {
+            sync_system: Self::args_sync_system::<A>,
+            build_all: Self::build_all::<WithArgs<A>>,
+            build_now: Self::build_now::<WithArgs<A>>,
+        });
+        self
+    }
+
+    /// Only builds blueprints whose entity has the marker component `M`, e.g. a `Spawned` marker
+    /// inserted once gameplay decides so. Removing the marker tears the target down, regardless
+    /// of the `cleanup_policy`, and inserting it again builds the blueprint anew.
+    ///
+    /// Removing the marker and inserting it again before the plugin runs keeps the target, but
+    /// rebuilds it.
+    pub fn gated_by<M: Component>(mut self) -> Self {
+        self.gate = Some(Self::add_gate::<M>);
+        self
+    }
+
+    /// Only builds blueprints while `condition` holds for the world, e.g. while a resource flag is
+    /// set. It is checked every frame before the blueprints are synced: once it stops holding,
+    /// every target built by this plugin is torn down (regardless of the `cleanup_policy`), and
+    /// once it holds again, every blueprint is built anew.
+    ///
+    /// Unlike `gated_by`, this applies to every blueprint of the plugin at once. With several
+    /// conditions, blueprints are only built while all of them hold.
+    pub fn build_if(mut self, condition: fn(&World) -> bool) -> Self {
+        self.build_if.push(Arc::new(condition));
+        self
+    }
+
+    /// Defers every build of the plugin until the asset collection `C` is loaded, i.e. until the
+    /// resource is inserted, as `bevy_asset_loader` does with an `AssetCollection` once all of
+    /// its assets are loaded. This keeps prefabs from being built with placeholder assets (such
+    /// as missing materials).
+    ///
+    /// Like `build_if(|world| world.contains_resource::<C>())`, so removing the collection tears
+    /// the targets down. A collection that fails to load is never inserted, leaving the
+    /// blueprints unbuilt: handle the failure where it is reported (e.g. in the failure state of
+    /// the loading state).
+    #[cfg(feature = "asset")]
+    pub fn requires_collection<C: Resource>(self) -> Self {
+        self.build_if(|world| world.contains_resource::<C>())
+    }
+
+    /// Only builds blueprints in the `state` of the `States` type `S`, like
+    /// `build_if(|world| in_state(state))`, except that the targets are torn down in
+    /// `OnExit(state)` (so they are gone before the next state's `OnEnter` systems run) and the
+    /// blueprints are built anew after `OnEnter(state)`.
+    ///
+    /// Only leaving `state` itself tears the targets down: transitions between the substates of
+    /// `state`, or from `state` to itself, keep them.
+    #[cfg(feature = "state")]
+    pub fn in_state_blueprints<S: States>(mut self, state: S) -> Self {
+        let condition_state = state.clone();
+        self.build_if.push(Arc::new(move |world: &World| {
+            world
+                .get_resource::<State<S>>()
+                .is_some_and(|current| *current.get() == condition_state)
+        }));
+        self.build_if_schedules
+            .extend([OnExit(state.clone()).intern(), OnEnter(state).intern()]);
+        self
+    }
+
+    /// Builds the blueprints after the system set `set` in `Update`, e.g. for a third-party plugin
+    /// that must see each `Blueprint<B>` before its target is built. Commands queued by `set` are
+    /// applied first. The rest of `BlueprintsSet` is not affected.
+    ///
+    /// If `set` runs after `BlueprintsSet` itself, the order is circular: the app panics with the
+    /// scheduler's error once its plugins are finished.
+    pub fn after_set(mut self, set: impl SystemSet) -> Self {
+        self.after_sets.push(set.intern());
+        self
+    }
+
+    // tears the targets down when the build conditions stop holding, and requests the build of
+    // every blueprint when they hold again
+    fn apply_build_condition(world: &mut World) {
+        let Some(condition) = world.get_resource::<BuildCondition<B, P, T>>() else {
+            return;
+        };
+        let holds = condition.check(world);
+        if holds == condition.holds {
+            return;
+        }
+        world.resource_mut::<BuildCondition<B, P, T>>().holds = holds;
+        let entities = world
+            .query_filtered::<Entity, With<Blueprint<B>>>()
+            .iter(world)
+            .collect::<Vec<_>>();
+        if holds {
+            for entity in entities {
+                Self::request_rebuild(world, entity);
+            }
+            return;
+        }
+        let mut built_values = world.resource_mut::<BuiltValues<B, P, T>>();
+        for entity in &entities {
+            built_values.forget(entity);
+        }
+        if let Some(mut lazy_targets) = world.get_resource_mut::<LazyTargets<B, P, T>>() {
+            lazy_targets.built.clear();
+        }
+        let mut commands = world.commands();
+        for entity in entities {
+            let mut entity_commands = commands.entity(entity);
+            Self::remove_target(&mut entity_commands);
+            entity_commands.remove::<BlueprintBuilt<B>>();
+        }
+        world.flush();
+    }
+
+    fn add_gate<M: Component>(app: &mut App) -> ComponentId {
+        Removals::<M, (B, P, T, M)>::buffer_while_paused(app);
+        app.add_systems(Update, Self::update_gate::<M>.in_set(BlueprintSet::Cleanup));
+        app.world_mut().init_component::<M>()
+    }
+
+    // tears down the targets of blueprints whose entity lost the marker `M`, and requests the
+    // build of those whose entity got it
+    fn update_gate<M: Component>(
+        mut commands: Commands,
+        mut removed: Removals<M, (B, P, T, M)>,
+        opened: Query<Entity, (With<Blueprint<B>>, Added<M>)>,
+        closed: Query<(), (With<Blueprint<B>>, Without<M>)>,
+        mut built_values: ResMut<BuiltValues<B, P, T>>,
+        mut lazy_targets: Option<ResMut<LazyTargets<B, P, T>>>,
+    ) {
+        for entity in removed.read() {
+            // despawned, or the marker was inserted again since
+            if !closed.contains(entity) {
+                continue;
+            }
+            built_values.forget(&entity);
+            if let Some(lazy_targets) = lazy_targets.as_deref_mut() {
+                lazy_targets.built.remove(&entity);
+            }
+            let mut entity_commands = commands.entity(entity);
+            Self::remove_target(&mut entity_commands);
+            entity_commands.remove::<BlueprintBuilt<B>>();
+        }
+        for entity in opened.iter() {
+            commands
+                .entity(entity)
+                .insert(RebuildPrefab::<B, P, T>(PhantomData));
+        }
+    }
+
+    // see `RebuildPrefab`
+    fn request_rebuild(world: &mut World, entity: Entity) {
+        if let Some(mut entity_mut) = world.get_entity_mut(entity) {
+            if entity_mut.contains::<Blueprint<B>>() {
+                entity_mut.insert(RebuildPrefab::<B, P, T>(PhantomData));
+            }
+        }
+    }
+
+    // system conditions run even when a set condition fails, so `should_sync_blueprint` is
+    // skipped explicitly while paused, or it would consume the changes made in the meantime
+    fn sync_system<Tr: BlueprintTrigger<B>, Ar: PrefabArgs<B, P>>() -> SystemConfigs {
+        type Filter<F, B, P, T> = Or<(F, With<RebuildPrefab<B, P, T>>)>;
+        Self::sync_blueprint_prefab::<Filter<Tr::Filter, B, P, T>, Ar>.run_if(
+            not(blueprints_paused)
+                .and_then(Self::should_sync_blueprint::<Filter<Tr::Filter, B, P, T>>),
+        )
+    }
+
+    fn args_sync_system<A: Send + Sync + 'static>(added_only: bool) -> SystemConfigs
+    where
+        P: FromBlueprintWithArgs<B, A>,
+    {
+        if added_only {
+            Self::sync_system::<AddedTrigger, WithArgs<A>>()
+        } else {
+            Self::sync_system::<ArgsTrigger<A>, WithArgs<A>>()
+        }
+    }
+
+    // a newly created system sees every blueprint as changed
+    fn build_all<Ar: PrefabArgs<B, P>>(world: &mut World) {
+        world.run_system_once(Self::sync_blueprint_prefab::<Changed<Blueprint<B>>, Ar>);
+    }
+
+    // like `build_all`, but the blueprints built here are then skipped by the sync system, unless
+    // they change again; nothing is built while the build conditions do not hold
+    fn build_now<Ar: PrefabArgs<B, P>>(world: &mut World) {
+        if world
+            .get_resource::<BuildCondition<B, P, T>>()
+            .is_some_and(|condition| !condition.check(world))
+        {
+            return;
+        }
+        let tick = world.change_tick();
+        Self::build_all::<Ar>(world);
+        world.resource_mut::<BuiltValues<B, P, T>>().built_now = Some(tick);
+    }
+
+    // builds the blueprint of `entity` alone, right away, see `materialize_blueprint_entity`
+    pub(crate) fn materialize(world: &mut World, entity: Entity) -> Result<(), MaterializeError> {
+        let Some(build_all) = world
+            .get_resource::<PluginSettings<B, P, T>>()
+            .map(|settings| settings.build_all)
+        else {
+            return Err(MaterializeError::NoPlugin);
+        };
+        if !world
+            .get_entity(entity)
+            .is_some_and(|entity_ref| entity_ref.contains::<Blueprint<B>>())
+        {
+            return Err(MaterializeError::NoBlueprint(entity));
+        }
+        let missing = missing_resources::<B, P>(world);
+        if !missing.is_empty() {
+            return Err(MaterializeError::MissingResources(missing));
+        }
+        if world
+            .get_resource::<BuildCondition<B, P, T>>()
+            .is_some_and(|condition| !condition.check(world))
+        {
+            return Ok(());
+        }
+        let tick = world.change_tick();
+        world.resource_mut::<BuiltValues<B, P, T>>().only = Some(entity);
+        build_all(world);
+        let mut built_values = world.resource_mut::<BuiltValues<B, P, T>>();
+        built_values.only = None;
+        built_values.materialized.insert(entity, tick);
+        Ok(())
+    }
+
+    // `F` selects the blueprints to build, see `BlueprintTrigger`, and `Ar` how to build them with
+    // their args
+    fn sync_blueprint_prefab<F: QueryFilter + 'static, Ar: PrefabArgs<B, P>>(
+        mut sync_commands: SyncCommands,
+        queries: SyncQueries<B, F, Ar::Args>,
+        mut system_params: StaticSystemParam<P::Params<'_, '_>>,
+        (settings, requested): PluginParams<B, P, T>,
+        mut built_values: ResMut<BuiltValues<B, P, T>>,
+        mut lazy_targets: Option<ResMut<LazyTargets<B, P, T>>>,
+        mut build_queue: BuildQueue<B, P, T>,
+    ) {
+        let start = Instant::now();
+        let only = built_values.only;
+        // a materialized entity is built on its own, even if it is a transaction member
+        let alone = only.is_some();
+        let built_now = if only.is_none() {
+            built_values.built_now.take()
+        } else {
+            None
+        };
+        let skip_unchanged = settings.settings.skip_unchanged;
+        let added_only = settings.settings.added_only;
+        let moved = if settings.settings.rebuild_on_parent_change && !added_only {
+            queries.moved.iter().map(|(entity, ..)| entity).collect()
+        } else {
+            EntityHashSet::default()
+        };
+        // moved entities whose blueprint did not change as well
+        let moved_items = queries
+            .moved
+            .iter()
+            .filter(|(entity, ..)| moved.contains(entity) && !queries.changed.contains(*entity));
+        let mut builds = Vec::new();
+        for (entity, blueprint, in_view, _) in queries.changed.iter().chain(moved_items) {
+            if only.is_some_and(|only| only != entity) {
+                continue;
+            }
+            // consumed even if the build waits below: the end of the wait requests it again
+            let requested = requested.contains(entity);
+            if requested {
+                sync_commands
+                    .commands()
+                    .entity(entity)
+                    .remove::<RebuildPrefab<B, P, T>>();
+            }
+            // built once its dependencies are, when `order_blueprint_dependencies` requests it, or
+            // once the gate opens, when `update_gate` does
+            if queries.dependencies.is_waiting(entity)
+                || !queries.is_gate_open(entity, settings.gate)
+            {
+                continue;
+            }
+            let moved = moved.contains(&entity);
+            let changed = queries.triggered.contains(entity) || moved;
+            // rebuilt even if the blueprint itself is unchanged
+            let args_changed = queries.args.get(entity).is_ok_and(|args| args.is_changed());
+            let rebuild = moved || args_changed || requested;
+            // built by `build_blueprints_now` (or `materialize_blueprint_entity`) and unchanged since
+            // (unless it is a lazy blueprint that was out of view then)
+            let built_at = built_values.materialized.remove(&entity).or(built_now);
+            if !rebuild
+                && built_at.is_some_and(|tick| {
+                    !blueprint
+                        .last_changed()
+                        .is_newer_than(tick, queries.ticks.this_run())
+                })
+                && lazy_targets
+                    .as_ref()
+                    .is_none_or(|lazy_targets| lazy_targets.is_built(entity))
+            {
+                continue;
+            }
+            let lazy_sync = lazy_targets
+                .as_deref_mut()
+                .map(|lazy_targets| lazy_targets.sync(entity, in_view, changed));
+            match lazy_sync {
+                Some(LazySync::TearDown) => {
+                    built_values.forget(&entity);
+                    let mut commands = sync_commands.commands();
+                    let mut entity_commands = commands.entity(entity);
+                    Self::remove_target(&mut entity_commands);
+                    entity_commands.remove::<BlueprintBuilt<B>>();
+                    continue;
+                }
+                Some(LazySync::Skip) => continue,
+                None if !changed => continue,
+                _ => {}
+            }
+            // transaction members may have been built by commands that were discarded
+            if skip_unchanged
+                && !rebuild
+                && !sync_commands.is_member(entity)
+                && built_values
+                    .values
+                    .get(&entity)
+                    .and_then(|value| value.reflect_partial_eq(&blueprint.0))
+                    .unwrap_or(false)
+            {
+                continue;
+            }
+            if let Some(update_field) = settings.partial_update {
+                if !rebuild
+                    && !sync_commands.is_member(entity)
+                    && Self::update_partially(
+                        entity,
+                        &blueprint.0,
+                        update_field,
+                        &mut sync_commands,
+                        &mut system_params,
+                        &mut built_values,
+                    )
+                {
+                    continue;
+                }
+            }
+            builds.push(entity);
+        }
+
+        // a queued build is dropped once its blueprint is gone, or once out of view (or gated), and
+        // queued again once back in view (or once the gate opens)
+        let lazy = lazy_targets.is_some();
+        let buildable = |entity| {
+            queries
+                .blueprints
+                .get(entity)
+                .is_ok_and(|(_, _, in_view, _)| !lazy || in_view)
+                && queries.is_gate_open(entity, settings.gate)
+        };
+        // transaction members are not budgeted, so that every member can build in the same frame,
+        // and neither are materialized entities
+        let builds = build_queue.take(
+            builds,
+            |entity| alone || sync_commands.is_member(entity),
+            buildable,
+        );
+        for entity in builds {
+            if !buildable(entity) {
+                continue;
+            }
+            let Ok((entity, blueprint, _, as_child)) = queries.blueprints.get(entity) else {
+                continue;
+            };
+            let args = queries.args.get(entity).ok();
+            match build_prefab::<B, P, Ar>(
+                entity,
+                &blueprint.0,
+                args.as_deref().map(|args| &args.0),
+                &mut system_params,
+            ) {
+                Ok(bundle) => {
+                    let build = Self::build_target(entity, bundle, as_child, &settings);
+                    sync_commands.build(entity, alone, Self::request_rebuild, build);
+                    if let Some(lazy_targets) = lazy_targets.as_deref_mut() {
+                        lazy_targets.set_built(entity, true);
+                    }
+                    if skip_unchanged || settings.partial_update.is_some() {
+                        built_values
+                            .values
+                            .insert(entity, blueprint.0.clone_value());
+                    }
+                }
+                Err(error) => {
+                    built_values.forget(&entity);
+                    if let Some(lazy_targets) = lazy_targets.as_deref_mut() {
+                        lazy_targets.set_built(entity, false);
+                    }
+                    let clear = move |commands: &mut Commands| {
+                        let mut entity_commands = commands.entity(entity);
+                        Self::remove_target(&mut entity_commands);
+                        entity_commands.remove::<BlueprintBuilt<B>>();
+                    };
+                    sync_commands.build(entity, alone, Self::request_rebuild, clear);
+                    sync_commands.build_failed(entity, alone);
+                    let message = error.to_string();
+                    Self::report_build_error(entity, error, settings.settings.on_build_error);
+                    if let Some((max_attempts, on_permanent_failure)) =
+                        settings.settings.retry_failed
+                    {
+                        if !sync_commands.is_held(entity, alone) {
+                            sync_commands
+                                .commands()
+                                .entity(entity)
+                                .add(Self::retry_failed_build(
+                                    max_attempts,
+                                    on_permanent_failure,
+                                    message,
+                                ));
+                        }
+                    }
+                }
+            }
+        }
+        sync_commands.record_sync_time(&settings.sync_diagnostic, start.elapsed());
+    }
+
+    // updates the changed fields of a built blueprint in place, returning `false` if it must be
+    // rebuilt instead
+    fn update_partially(
+        entity: Entity,
+        blueprint: &B,
+        update_field: PartialUpdate<B, P>,
+        sync_commands: &mut SyncCommands,
+        system_params: &mut StaticSystemParam<P::Params<'_, '_>>,
+        built_values: &mut BuiltValues<B, P, T>,
+    ) -> bool {
+        let (Some(built), Some(target)) = (
+            built_values.values.get(&entity),
+            built_values.targets.get(&entity).copied(),
+        ) else {
+            return false;
+        };
+        // the child may have been despawned since it was built
+        if !sync_commands.exists(target) {
+            return false;
+        }
+        let mut commands = sync_commands.commands();
+        let mut target_commands = commands.entity(target);
+        if !update_changed_fields::<B, P>(
+            update_field,
+            built.as_ref(),
+            blueprint,
+            &mut target_commands,
+            system_params,
+        ) {
+            return false;
+        }
+        built_values.values.insert(entity, blueprint.clone_value());
+        true
+    }
+
+    // also removes a child built because of `ForceAsChild`, and the `LastBuilt<P>` snapshot
+    pub(crate) fn remove_target(entity_commands: &mut EntityCommands) {
+        T::remove_prefab::<B, P>(entity_commands);
+        entity_commands
+            .remove::<ForcedChild<B, P>>()
+            .add(forget_components::<B, P, T>);
+        #[cfg(feature = "last-built")]
+        entity_commands.remove::<LastBuilt<P>>();
+    }
+
+    // replaces the target of the entity with the built bundle, and marks the entity as built
+    fn build_target(
+        entity: Entity,
+        bundle: P,
+        as_child: bool,
+        settings: &PluginSettings<B, P, T>,
+    ) -> impl FnOnce(&mut Commands) + Send + 'static {
+        #[cfg(feature = "last-built")]
+        let snapshot = settings
+            .snapshot
+            .map(|snapshot| LastBuilt(snapshot(&bundle)));
+        #[cfg(feature = "render")]
+        let render_layers = settings.render_layers.clone();
+        let child_parent = settings.child_parent;
+        let with_child = settings.with_child;
+        let post_build = settings.post_build;
+        let auto_name = settings.settings.auto_name;
+        let retry_failed = settings.settings.retry_failed.is_some();
+        let partial_update = settings.partial_update.is_some();
+        move |commands: &mut Commands| {
+            let mut entity_commands = commands.entity(entity);
+            Self::remove_target(&mut entity_commands);
+            #[cfg(feature = "last-built")]
+            if let Some(snapshot) = snapshot {
+                entity_commands.insert(snapshot);
+            }
+            let child = Self::attach_target(&mut entity_commands, bundle, as_child, child_parent);
+            if let (Some(child), Some(with_child)) = (child, with_child) {
+                with_child(&mut entity_commands.commands().entity(child));
+            }
+            // after the bundle, so that it overrides the bundle's own layers
+            #[cfg(feature = "render")]
+            if let Some(render_layers) = render_layers {
+                let target = child.unwrap_or(entity);
+                entity_commands
+                    .commands()
+                    .entity(target)
+                    .insert(render_layers);
+            }
+            if let Some(post_build) = post_build {
+                post_build(
+                    &mut entity_commands.commands().entity(child.unwrap_or(entity)),
+                    entity,
+                );
+            }
+            if auto_name {
+                entity_commands.add(insert_type_name::<B>);
+            }
+            entity_commands
+                .insert(BlueprintBuilt::<B>::default())
+                .add(record_build);
+            if retry_failed {
+                entity_commands.remove::<FailedBuilds<B, P>>();
+            }
+            if partial_update {
+                entity_commands.add(move |entity: Entity, world: &mut World| {
+                    world
+                        .resource_mut::<BuiltValues<B, P, T>>()
+                        .targets
+                        .insert(entity, child.unwrap_or(entity));
+                });
+            }
+            commands.add(move |world: &mut World| {
+                world.send_event(BlueprintSpawned::<B> {
+                    blueprint: entity,
+                    child,
+                    marker: PhantomData,
+                });
+            });
+        }
+    }
+
+    // also records the prefab's components on the target, for `describe_blueprint`, and moves
+    // children under the `ChildParent`
+    fn attach_target(
+        entity_commands: &mut EntityCommands,
+        bundle: P,
+        as_child: bool,
+        child_parent: ChildParent,
+    ) -> Option<Entity> {
+        let entity = entity_commands.id();
+        if !as_child {
+            let child = T::attach_prefab::<B, P>(entity_commands, bundle);
+            if !T::DISCARDS_BUNDLE {
+                entity_commands
+                    .commands()
+                    .entity(child.unwrap_or(entity))
+                    .add(record_components::<B, P, T>);
+            }
+            // away from the blueprint entity, the child is cleaned up through `ForcedChild`
+            let moved =
+                T::KIND == TargetKind::AsChild && !matches!(child_parent, ChildParent::Blueprint);
+            if let Some(child) = child.filter(|_| moved) {
+                entity_commands.insert(ForcedChild::<B, P> {
+                    child,
+                    marker: PhantomData,
+                });
+                entity_commands
+                    .commands()
+                    .entity(child)
+                    .add(child_parent.reparent());
+            }
+            return child;
+        }
+        let child = entity_commands
+            .commands()
+            .spawn((bundle, BlueprintSource(entity)))
+            .set_parent(entity)
+            .add(record_components::<B, P, T>)
+            .add(child_parent.reparent())
+            .id();
+        entity_commands
+            .insert((
+                IsBlueprint,
+                ForcedChild::<B, P> {
+                    child,
+                    marker: PhantomData,
+                },
+            ))
+            .add(insert_spatial_parent);
+        Some(child)
+    }
+
+    fn report_build_error(entity: Entity, error: P::Error, on_build_error: OnBuildError) {
+        let message = format!(
+            "blueprint {} on entity {} failed to build {}: {}",
+            get_short_name(std::any::type_name::<B>()),
+            entity,
+            get_short_name(std::any::type_name::<P>()),
+            error,
+        );
+        match on_build_error {
+            OnBuildError::Panic => panic!("{message}"),
+            OnBuildError::PanicInDebug if cfg!(debug_assertions) => panic!("{message}"),
+            _ => error!("{message}"),
+        }
+    }
+
+    // counts a failed build, and requests the build of this prefab again in the next frame (leaving
+    // the other plugins of the blueprint alone), until it has failed `max_attempts` times
+    fn retry_failed_build(
+        max_attempts: u32,
+        on_permanent_failure: OnPermanentFailure,
+        error: String,
+    ) -> impl FnOnce(Entity, &mut World) {
+        move |entity, world| {
+            let Some(mut entity_mut) = world.get_entity_mut(entity) else {
+                return;
+            };
+            let attempts = entity_mut
+                .get::<FailedBuilds<B, P>>()
+                .map_or(0, |failed| failed.attempts)
+                + 1;
+            if attempts < max_attempts {
+                entity_mut.insert(FailedBuilds::<B, P> {
+                    attempts,
+                    marker: PhantomData,
+                });
+                Self::request_rebuild(world, entity);
+                return;
+            }
+            entity_mut.remove::<FailedBuilds<B, P>>();
+            if on_permanent_failure == OnPermanentFailure::Despawn {
+                error!(
+                    "despawning entity {} after blueprint {} failed to build {} {} times: {}",
+                    entity,
+                    get_short_name(std::any::type_name::<B>()),
+                    get_short_name(std::any::type_name::<P>()),
+                    attempts,
+                    error,
+                );
+            }
+            world.send_event(BlueprintBuildError::<B> {
+                blueprint: entity,
+                error,
+                attempts,
+                marker: PhantomData,
+            });
+            if on_permanent_failure == OnPermanentFailure::Despawn {
+                world.entity_mut(entity).despawn_recursive();
+            }
+        }
+    }
+
+    fn handle_removed_blueprints(
+        mut commands: Commands,
+        mut removed: Removals<Blueprint<B>, (P, T)>,
+        settings: Res<PluginSettings<B, P, T>>,
+        mut built_values: ResMut<BuiltValues<B, P, T>>,
+        mut lazy_targets: Option<ResMut<LazyTargets<B, P, T>>>,
+    ) {
+        let entities = removed.read();
+        if entities.is_empty() {
+            return;
+        }
+        for entity in &entities {
+            built_values.forget(entity);
+            if let Some(lazy_targets) = lazy_targets.as_deref_mut() {
+                lazy_targets.built.remove(entity);
+            }
+        }
+        let cleanup = settings.settings.cleanup;
+        // whether the entity still exists is decided when this command is applied, since other
+        // commands (e.g. despawns) queued this frame may be applied before it
+        commands.add(move |world: &mut World| {
+            if let CleanupPolicy::Custom(cleanup) = cleanup {
+                for entity in &entities {
+                    if world.get_entity(*entity).is_some() {
+                        cleanup(world, *entity);
+                    }
+                }
+            }
+            let entities = entities
+                .into_iter()
+                .map(|entity| (entity, world.get_entity(entity).is_some()))
+                .collect::<Vec<_>>();
+            let mut state = SystemState::<(Commands, Query<(Entity, &Parent)>)>::new(world);
+            let (mut commands, child_query) = state.get_mut(world);
+            let mut despawned = 0;
+            for (entity, exists) in entities {
+                if exists {
+                    let mut entity_commands = commands.entity(entity);
+                    if let CleanupPolicy::Remove = cleanup {
+                        Self::remove_target(&mut entity_commands);
+                    }
+                    entity_commands.remove::<BlueprintBuilt<B>>();
+                } else {
+                    despawned += T::cleanup_despawned_prefabs(&mut commands, entity, &child_query);
+                }
+            }
+            state.apply(world);
+            if let Some(mut despawned_children) = world.get_resource_mut::<DespawnedChildren>() {
+                despawned_children.0 += despawned;
+            }
+        });
+    }
+
+    // cleans up the target of every blueprint as if it was removed, then marks the blueprint as
+    // changed so it is built again, for `reset_all_targets`
+    pub(crate) fn reset_targets(world: &mut World) {
+        let entities = world
+            .query_filtered::<Entity, With<Blueprint<B>>>()
+            .iter(world)
+            .collect::<Vec<_>>();
+        if let Some(mut built_values) = world.get_resource_mut::<BuiltValues<B, P, T>>() {
+            for entity in &entities {
+                built_values.forget(entity);
+            }
+        }
+        if let Some(mut lazy_targets) = world.get_resource_mut::<LazyTargets<B, P, T>>() {
+            for entity in &entities {
+                lazy_targets.built.remove(entity);
+            }
+        }
+        let Some(cleanup) = world
+            .get_resource::<PluginSettings<B, P, T>>()
+            .map(|settings| settings.settings.cleanup)
+        else {
+            return;
+        };
+        if let CleanupPolicy::Custom(cleanup) = cleanup {
+            for entity in &entities {
+                cleanup(world, *entity);
+            }
+        }
+        let mut state = SystemState::<Commands>::new(world);
+        let mut commands = state.get_mut(world);
+        for entity in &entities {
+            let mut entity_commands = commands.entity(*entity);
+            if let CleanupPolicy::Remove = cleanup {
+                Self::remove_target(&mut entity_commands);
+            }
+            entity_commands.remove::<BlueprintBuilt<B>>();
+        }
+        state.apply(world);
+        for entity in entities {
+            if let Some(mut blueprint) = world.get_mut::<Blueprint<B>>(entity) {
+                blueprint.set_changed();
+            }
+        }
+    }
+}
+
+impl<B, P, T> Plugin for BlueprintPlugin<B, P, T>
+where
+    B: Default + GetTypeRegistration + FromReflect + TypePath + Send + Sync + 'static,
+    P: Bundle + TryFromBlueprint<B>,
+    T: BlueprintTarget + Send + Sync + 'static,
+{
+    fn build(&self, app: &mut App) {
+        BlueprintsPlugin::add_once(app);
+        let gate = self.gate.map(|add_gate| add_gate(app));
+        if let Some(check_kept) = self.check_kept {
+            check_kept(app.world_mut());
+        }
+        if !self.build_if.is_empty() {
+            app.insert_resource(BuildCondition::<B, P, T> {
+                conditions: self.build_if.clone(),
+                holds: true,
+                marker: PhantomData,
+            })
+            .add_systems(
+                Update,
+                Self::apply_build_condition.in_set(BlueprintSet::Cleanup),
+            );
+            // outside of `BlueprintsSet`, so its conditions are checked here
+            for schedule in &self.build_if_schedules {
+                app.add_systems(
+                    *schedule,
+                    Self::apply_build_condition.run_if(
+                        not(blueprints_paused).and_then(not(resource_exists::<BlueprintsBaked>)),
+                    ),
+                );
+            }
+        }
+        let (build_all, build_now) = match self.args {
+            Some(args) => (args.build_all, args.build_now),
+            None => (
+                Self::build_all::<NoArgs> as fn(&mut World),
+                Self::build_now::<NoArgs> as fn(&mut World),
+            ),
+        };
+        let sync_diagnostic = app
+            .world_mut()
+            .resource_mut::<SyncTimes>()
+            .diagnostic_of::<B>();
+        if let Some(diagnostic) = sync_diagnostic.1 {
+            app.register_diagnostic(diagnostic);
+        }
+        app.insert_resource(PluginSettings::<B, P, T> {
+            settings: self.settings.clone(),
+            partial_update: self.partial_update,
+            with_child: self.with_child,
+            post_build: self.post_build,
+            child_parent: self.child_parent,
+            #[cfg(feature = "last-built")]
+            snapshot: self.snapshot,
+            #[cfg(feature = "render")]
+            render_layers: self.render_layers.clone(),
+            gate,
+            build_all,
+            sync_diagnostic: sync_diagnostic.0,
+            marker: PhantomData,
+        })
+        .init_resource::<BuiltValues<B, P, T>>()
+        .init_resource::<QueuedBuilds<B, P, T>>()
+        .init_resource::<BlueprintTransactions>()
+        .init_resource::<BlueprintDependencies>()
+        .add_event::<BlueprintSpawned<B>>()
+        .add_event::<BlueprintBuildError<B>>();
+        Removals::<Blueprint<B>, (P, T)>::buffer_while_paused(app);
+
+        let order = app
+            .world_mut()
+            .get_resource_or_insert_with(BlueprintRegistry::default)
+            .register::<B, P, T>(
+                build_all,
+                Self::reset_targets,
+                Self::request_rebuild,
+                (!self.settings.only_build_in_update).then_some(build_now),
+                std::iter::once(Update.intern())
+                    .chain(self.build_if_schedules.iter().copied())
+                    .collect(),
+            );
+        let sync_system = match (self.args, self.sync_system) {
+            (Some(args), _) => (args.sync_system)(self.settings.added_only),
+            _ if self.settings.added_only => Self::sync_system::<AddedTrigger, NoArgs>(),
+            (None, Some(sync_system)) => sync_system(),
+            (None, None) => Self::sync_system::<ChangedTrigger, NoArgs>(),
+        };
+        let mut sync_system = sync_system
+            .in_set(BlueprintSet::Sync)
+            .in_set(BlueprintSyncSet::<B>::default())
+            .in_set(PrefabSyncSet::<B>::new(order));
+        // prefabs of the same blueprint sync in registration order, so that overlapping prefabs
+        // have a deterministic result: their commands are applied in that (system) order at the
+        // shared sync point. There is no sync point between them, so a prefab does not see what
+        // the prefabs registered before it inserted
+        if let Some(previous) = order.checked_sub(1) {
+            sync_system = sync_system.after_ignore_deferred(PrefabSyncSet::<B>::new(previous));
+        }
+        for set in &self.after_sets {
+            sync_system = sync_system.after(*set);
+        }
+        app.add_systems(
+            Update,
+            (
+                Self::handle_removed_blueprints.in_set(BlueprintSet::Cleanup),
+                sync_system,
+            ),
+        );
+
+        app.register_type::<Blueprint<B>>().register_type::<B>();
+    }
+
+    // reports a circular `after_set` now, naming the plugin, rather than when `Update` first runs
+    fn finish(&self, app: &mut App) {
+        if self.after_sets.is_empty() {
+            return;
+        }
+        app.world_mut()
+            .resource_scope(|world, mut schedules: Mut<Schedules>| {
+                let Some(schedule) = schedules.get_mut(Update) else {
+                    return;
+                };
+                if let Err(error) = schedule.initialize(world) {
+                    panic!(
+                        "{} builds after {:?}, which cannot be scheduled: {}",
+                        get_short_name(std::any::type_name::<Self>()),
+                        self.after_sets,
+                        error,
+                    );
+                }
+            });
+    }
+}
+
+/// A `BlueprintPlugin` for prefabs that must always build, such as those depending on critical
+/// assets. A failed build panics with the blueprint type and entity in debug builds (so that
+/// content bugs fail CI loudly) and is logged as an error in release builds.
+pub struct StrictBlueprintPlugin<B, P: Bundle + TryFromBlueprint<B>, T: BlueprintTarget = AsSelf> {
+    panic_in_release: bool,
+    blueprint_marker: PhantomData<B>,
+    prefab_marker: PhantomData<P>,
+    target_marker: PhantomData<T>,
+}
+
+impl<B, P, T> Default for StrictBlueprintPlugin<B, P, T>
+where
+    P: Bundle + TryFromBlueprint<B>,
+    T: BlueprintTarget,
+{
+    fn default() -> Self {
+        Self {
+            panic_in_release: false,
+            blueprint_marker: PhantomData::<B>,
+            prefab_marker: PhantomData::<P>,
+            target_marker: PhantomData::<T>,
+        }
+    }
+}
+
+impl<B, P, T> StrictBlueprintPlugin<B, P, T>
+where
+    P: Bundle + TryFromBlueprint<B>,
+    T: BlueprintTarget,
+{
+    pub fn panic_in_release(mut self) -> Self {
+        self.panic_in_release = true;
+        self
+    }
+}
+
+impl<B, P, T> Plugin for StrictBlueprintPlugin<B, P, T>
+where
+    B: Default + GetTypeRegistration + FromReflect + TypePath + Send + Sync + 'static,
+    P: Bundle + TryFromBlueprint<B>,
+    T: BlueprintTarget + Send + Sync + 'static,
+{
+    fn build(&self, app: &mut App) {
+        let on_build_error = if self.panic_in_release {
+            OnBuildError::Panic
+        } else {
+            OnBuildError::PanicInDebug
+        };
+        app.add_plugins(BlueprintPlugin::<B, P, T>::default().on_build_error(on_build_error));
+    }
+}
+
+pub struct BlueprintsPlugin;
+
+impl BlueprintsPlugin {
+    // adds the plugin unless the app already has it, for the plugins that schedule systems in
+    // `BlueprintSet`, so that they can be added before (or without) it
+    pub(crate) fn add_once(app: &mut App) {
+        if !app.is_plugin_added::<Self>() {
+            app.add_plugins(Self);
+        }
+    }
+
+    /// How many orphaned children `BlueprintTarget::cleanup_despawned_prefabs` despawned each
+    /// frame, after their blueprint entity was despawned.
+    pub const DESPAWNED_CHILDREN: DiagnosticPath =
+        DiagnosticPath::const_new("blueprints/despawned_children");
+
+    /// How many blueprint types get their own sync time diagnostic, see `sync_diagnostic`. The
+    /// types registered after these share the `OTHER_SYNC_TIMES` diagnostic, so that a game with
+    /// many blueprint types does not flood the diagnostics.
+    pub const MAX_SYNC_DIAGNOSTICS: usize = 32;
+
+    /// The time (in milliseconds) spent each frame in the sync systems of the blueprint types that
+    /// did not get their own diagnostic, see `MAX_SYNC_DIAGNOSTICS`.
+    pub const OTHER_SYNC_TIMES: DiagnosticPath = DiagnosticPath::const_new("blueprints/sync/other");
+
+    /// The diagnostic of the time (in milliseconds) spent each frame in the sync systems of
+    /// blueprint `B`, summed over all of its plugins: `blueprints/sync/` followed by the short
+    /// name of `B`. Frames in which nothing of `B` changed, so that its systems did not run, are
+    /// measured as zero.
+    ///
+    /// Only the first `MAX_SYNC_DIAGNOSTICS` types are measured there, the others are measured as
+    /// `OTHER_SYNC_TIMES`. Types with the same short name share the diagnostic.
+    pub fn sync_diagnostic<B>() -> DiagnosticPath {
+        DiagnosticPath::from_components([
+            "blueprints",
+            "sync",
+            &get_short_name(std::any::type_name::<B>()),
+        ])
+    }
+}
+
+// the children despawned by `cleanup_despawned_prefabs` this frame, summed over every blueprint plugin
+#[derive(Default, Resource)]
+struct DespawnedChildren(usize);
+
+fn measure_despawned_children(
+    mut diagnostics: Diagnostics,
+    mut despawned_children: ResMut<DespawnedChildren>,
+) {
+    let despawned = std::mem::take(&mut despawned_children.0);
+    diagnostics.add_measurement(&BlueprintsPlugin::DESPAWNED_CHILDREN, || despawned as f64);
+}
+
+// the time spent in the sync systems this frame, in milliseconds, by diagnostic (including those
+// whose systems did not run)
+#[derive(Resource)]
+struct SyncTimes {
+    times: HashMap<DiagnosticPath, f64>,
+    // the diagnostics registered for single blueprint types
+    diagnostics: HashSet<DiagnosticPath>,
+}
+
+impl Default for SyncTimes {
+    fn default() -> Self {
+        SyncTimes {
+            times: HashMap::from([(BlueprintsPlugin::OTHER_SYNC_TIMES, 0.)]),
+            diagnostics: HashSet::default(),
+        }
+    }
+}
+
+impl SyncTimes {
+    // the diagnostic to measure the sync time of `B` as, and whether it must be registered first
+    fn diagnostic_of<B>(&mut self) -> (DiagnosticPath, Option<Diagnostic>) {
+        let path = BlueprintsPlugin::sync_diagnostic::<B>();
+        if self.diagnostics.contains(&path) {
+            return (path, None);
+        }
+        if self.diagnostics.len() >= BlueprintsPlugin::MAX_SYNC_DIAGNOSTICS {
+            return (BlueprintsPlugin::OTHER_SYNC_TIMES, None);
+        }
+        self.diagnostics.insert(path.clone());
+        self.times.insert(path.clone(), 0.);
+        (path.clone(), Some(Diagnostic::new(path).with_suffix("ms")))
+    }
+}
+
+// the time spent in one run of a sync system, added to `SyncTimes` with its commands, so that the
+// sync systems of different blueprints can still run in parallel
+#[derive(Default)]
+pub(crate) struct SyncTime(pub(crate) Option<(DiagnosticPath, std::time::Duration)>);
+
+impl SystemBuffer for SyncTime {
+    fn apply(&mut self, _: &SystemMeta, world: &mut World) {
+        let Some((path, elapsed)) = self.0.take() else {
+            return;
+        };
+        *world
+            .resource_mut::<SyncTimes>()
+            .times
+            .entry(path)
+            .or_default() += elapsed.as_secs_f64() * 1000.;
+    }
+}
+
+fn measure_sync_times(mut diagnostics: Diagnostics, mut sync_times: ResMut<SyncTimes>) {
+    for (path, time) in sync_times.times.iter_mut() {
+        let time = std::mem::take(time);
+        diagnostics.add_measurement(path, || time);
+    }
+}
+
+impl Plugin for BlueprintsPlugin {
+    fn build(&self, app: &mut App) {
+        // the sets and systems are only configured by the first instance, which may have been
+        // added by another blueprint plugin
+        if app.is_plugin_added::<Self>() {
+            return;
+        }
+        app.configure_sets(
+            Update,
+            (
+                BlueprintSet::Cleanup,
+                BlueprintSet::Sync,
+                BlueprintSet::Flush,
+            )
+                .chain()
+                .in_set(BlueprintsSet),
+        )
+        .configure_sets(
+            Update,
+            BlueprintsSet
+                .run_if(not(resource_exists::<BlueprintsBaked>))
+                .run_if(not(blueprints_paused)),
+        )
+        .init_resource::<BlueprintsPaused>()
+        .init_resource::<BlueprintRegistry>()
+        .init_resource::<BlueprintTransactions>()
+        .init_resource::<BlueprintDependencies>()
+        .init_resource::<DespawnedChildren>()
+        .init_resource::<SyncTimes>()
+        .register_diagnostic(Diagnostic::new(Self::DESPAWNED_CHILDREN))
+        .register_diagnostic(Diagnostic::new(Self::OTHER_SYNC_TIMES).with_suffix("ms"))
+        .register_type::<BlueprintSource>()
+        .register_type::<BlueprintBuildStats>()
+        .register_type::<BlueprintPriority>()
+        .register_type::<ForceAsChild>()
+        .register_type::<PersistSibling>()
+        .register_type::<BlueprintTransaction>()
+        .register_type::<BlueprintDependsOn>()
+        .add_systems(
+            Update,
+            (
+                order_blueprint_dependencies
+                    .after(BlueprintSet::Cleanup)
+                    .before(BlueprintSet::Sync)
+                    .in_set(BlueprintsSet)
+                    .run_if(not(blueprints_paused).and_then(dependencies_changed)),
+                apply_deferred
+                    .in_set(BlueprintSet::Flush)
+                    .before(apply_blueprint_transactions),
+                apply_blueprint_transactions.in_set(BlueprintSet::Flush),
+                measure_despawned_children
+                    .in_set(BlueprintSet::Flush)
+                    .after(apply_blueprint_transactions),
+                measure_sync_times
+                    .in_set(BlueprintSet::Flush)
+                    .after(apply_blueprint_transactions),
+            ),
+        );
+
+        #[cfg(feature = "scene")]
+        app.register_type::<BlueprintSceneId>();
+
+        #[cfg(debug_assertions)]
+        app.add_systems(Startup, warn_unreflected_prefab_components)
+            .add_systems(Last, warn_unregistered_blueprints);
+    }
+
+    fn is_unique(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{color::palettes, diagnostic::DiagnosticsStore, prelude::*};
+
+    use super::*;
+
+    #[derive(Clone, Default, Reflect)]
     struct Rect {
         size: Vec2,
     }
 
-    #[derive(Clone, Component, Default, Reflect)]
-    #[reflect(Component)]
-    struct RectSize(Vec2);
+    #[derive(Clone, Component, Default, Reflect)]
+    #[reflect(Component)]
+    struct RectSize(Vec2);
+
+    #[derive(Clone, Component, Default, Reflect)]
+    #[reflect(Component)]
+    struct RectColor(Color);
+
+    #[derive(Clone, Component, Default, Reflect)]
+    #[reflect(Component)]
+    struct RectArea(f32);
+
+    #[test]
+    fn single_blueprint() {
+        #[derive(Bundle)]
+        struct RectBundle {
+            size: RectSize,
+            color: RectColor,
+            area: RectArea,
+        }
+
+        impl FromBlueprint<Rect> for RectBundle {
+            type Params<'w, 's> = ();
+            fn from_blueprint(
+                blueprint: &Rect,
+                _: &mut StaticSystemParam<Self::Params<'_, '_>>,
+            ) -> Self {
+                RectBundle {
+                    size: RectSize(blueprint.size),
+                    color: RectColor(Color::Srgba(palettes::css::BLUE)),
+                    area: RectArea(blueprint.size.x * blueprint.size.y),
+                }
+            }
+        }
+
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            // whereas BlueprintsPlugin prepares the framework, individual BlueprintPlugins must
+            // be added to define which blueprints to reactively manage
+            .add_plugins(BlueprintPlugin::<Rect, RectBundle>::default());
+        // when spawning an entity of this kind, spawn a Blueprint::<MyType>
+        // (note: MyType does not have to be a component, but it can be)
+        let entity = app.world_mut().spawn(Blueprint::<Rect>::default()).id();
+        app.update();
+        assert_eq!(
+            app.world_mut()
+                .query::<(&RectSize, &RectColor, &RectArea)>()
+                .iter(app.world())
+                .collect::<Vec<_>>()
+                .len(),
+            1
+        );
+        assert_eq!(
+            app.world_mut()
+                .query_filtered::<(), Or<(With<RectSize>, With<RectColor>, With<RectArea>)>>()
+                .iter(app.world())
+                .collect::<Vec<_>>()
+                .len(),
+            1
+        );
+        // when the blueprint component is removed, the additional components are removed as well
+        app.world_mut()
+            .entity_mut(entity)
+            .remove::<Blueprint<Rect>>();
+        app.update();
+        assert_eq!(
+            app.world_mut()
+                .query_filtered::<(), Or<(With<RectSize>, With<RectColor>, With<RectArea>)>>()
+                .iter(app.world())
+                .collect::<Vec<_>>()
+                .len(),
+            0
+        );
+    }
+
+    #[test]
+    fn multiple_blueprint() {
+        #[derive(Default, Reflect)]
+        pub struct SpecificRect;
+
+        #[derive(Bundle)]
+        struct RectBundle {
+            size: RectSize,
+            color: RectColor,
+            area: RectArea,
+        }
+
+        impl FromBlueprint<Rect> for RectBundle {
+            type Params<'w, 's> = ();
+            fn from_blueprint(
+                blueprint: &Rect,
+                _: &mut StaticSystemParam<Self::Params<'_, '_>>,
+            ) -> Self {
+                RectBundle {
+                    size: RectSize(blueprint.size),
+                    color: RectColor(Color::Srgba(palettes::css::RED)),
+                    area: RectArea(blueprint.size.x * blueprint.size.y),
+                }
+            }
+        }
+
+        impl FromBlueprint<SpecificRect> for RectBundle {
+            type Params<'w, 's> = ();
+            fn from_blueprint(
+                _: &SpecificRect,
+                _: &mut StaticSystemParam<Self::Params<'_, '_>>,
+            ) -> Self {
+                let rect: Rect = Rect {
+                    size: Vec2::new(10., 10.),
+                };
+                RectBundle {
+                    size: RectSize(rect.size),
+                    color: RectColor(Color::Srgba(palettes::css::BLUE)),
+                    area: RectArea(rect.size.x * rect.size.y),
+                }
+            }
+        }
+
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            // can add as many blueprints as we want (including overlapping ones, be careful!)
+            .add_plugins(BlueprintPlugin::<Rect, RectBundle>::default())
+            .add_plugins(BlueprintPlugin::<SpecificRect, RectBundle>::default());
+        // spawn one entity for each blueprint
+        let entity1 = app.world_mut().spawn(Blueprint::<Rect>::default()).id();
+        let entity2 = app
+            .world_mut()
+            .spawn(Blueprint::<SpecificRect>::default())
+            .id();
+        app.update();
+        assert_eq!(
+            app.world_mut()
+                .query::<(&RectSize, &RectColor, &RectArea)>()
+                .iter(app.world())
+                .collect::<Vec<_>>()
+                .len(),
+            2
+        );
+        // clean up one at a time
+        app.world_mut()
+            .entity_mut(entity1)
+            .remove::<Blueprint<Rect>>();
+        app.update();
+        assert_eq!(
+            app.world_mut()
+                .query_filtered::<(), Or<(With<RectSize>, With<RectColor>, With<RectArea>)>>()
+                .iter(app.world())
+                .collect::<Vec<_>>()
+                .len(),
+            1
+        );
+        app.world_mut()
+            .entity_mut(entity2)
+            .remove::<Blueprint<SpecificRect>>();
+        app.update();
+        assert_eq!(
+            app.world_mut()
+                .query_filtered::<(), Or<(With<RectSize>, With<RectColor>, With<RectArea>)>>()
+                .iter(app.world())
+                .collect::<Vec<_>>()
+                .len(),
+            0
+        );
+    }
+
+    #[test]
+    fn multiple_prefab() {
+        #[derive(Bundle)]
+        struct RectBundle {
+            size: RectSize,
+            color: RectColor,
+        }
+
+        impl FromBlueprint<Rect> for RectBundle {
+            type Params<'w, 's> = ();
+            fn from_blueprint(
+                blueprint: &Rect,
+                _: &mut StaticSystemParam<Self::Params<'_, '_>>,
+            ) -> Self {
+                RectBundle {
+                    size: RectSize(blueprint.size),
+                    color: RectColor(Color::Srgba(palettes::css::RED)),
+                }
+            }
+        }
+
+        // for some reason I need this bundle spawned separately
+        // e.g. it is used in a separate crate or something
+        #[derive(Bundle)]
+        struct SecondRectBundle {
+            area: RectArea,
+        }
+
+        impl FromBlueprint<Rect> for SecondRectBundle {
+            type Params<'w, 's> = ();
+            fn from_blueprint(
+                blueprint: &Rect,
+                _: &mut StaticSystemParam<Self::Params<'_, '_>>,
+            ) -> Self {
+                SecondRectBundle {
+                    area: RectArea(blueprint.size.x * blueprint.size.y),
+                }
+            }
+        }
+
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            // can add multiple prefabs for the same blueprint component
+            .add_plugins(BlueprintPlugin::<Rect, RectBundle>::default())
+            .add_plugins(BlueprintPlugin::<Rect, SecondRectBundle>::default());
+        // spawn the blueprint component
+        let entity = app.world_mut().spawn(Blueprint::<Rect>::default()).id();
+        app.update();
+        assert_eq!(
+            app.world_mut()
+                .query::<(&RectSize, &RectColor, &RectArea)>()
+                .iter(app.world())
+                .collect::<Vec<_>>()
+                .len(),
+            1
+        );
+        // cleanup is handled automatically
+        app.world_mut()
+            .entity_mut(entity)
+            .remove::<Blueprint<Rect>>();
+        app.update();
+        assert_eq!(
+            app.world_mut()
+                .query_filtered::<(), Or<(With<RectSize>, With<RectColor>, With<RectArea>)>>()
+                .iter(app.world())
+                .collect::<Vec<_>>()
+                .len(),
+            0
+        );
+    }
+
+    #[test]
+    fn tuple_prefab() {
+        #[derive(Resource)]
+        struct Palette(Color);
+
+        #[derive(Bundle)]
+        struct RectBundle {
+            size: RectSize,
+            color: RectColor,
+        }
+
+        impl FromBlueprint<Rect> for RectBundle {
+            type Params<'w, 's> = Res<'w, Palette>;
+            fn from_blueprint(
+                blueprint: &Rect,
+                palette: &mut StaticSystemParam<Self::Params<'_, '_>>,
+            ) -> Self {
+                RectBundle {
+                    size: RectSize(blueprint.size),
+                    color: RectColor(palette.0),
+                }
+            }
+        }
+
+        #[derive(Bundle)]
+        struct SecondRectBundle {
+            area: RectArea,
+        }
+
+        impl FromBlueprint<Rect> for SecondRectBundle {
+            type Params<'w, 's> = ();
+            fn from_blueprint(
+                blueprint: &Rect,
+                _: &mut StaticSystemParam<Self::Params<'_, '_>>,
+            ) -> Self {
+                SecondRectBundle {
+                    area: RectArea(blueprint.size.x * blueprint.size.y),
+                }
+            }
+        }
+
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            // both prefabs are built by a single plugin
+            .add_plugins(BlueprintPlugin::<Rect, (RectBundle, SecondRectBundle)>::default())
+            .insert_resource(Palette(Color::Srgba(palettes::css::GREEN)));
+        let entity = app
+            .world_mut()
+            .spawn(Blueprint::new(Rect {
+                size: Vec2::new(2., 3.),
+            }))
+            .id();
+        app.update();
+        let (size, color, area) = app
+            .world_mut()
+            .query::<(&RectSize, &RectColor, &RectArea)>()
+            .single(app.world());
+        assert_eq!(size.0, Vec2::new(2., 3.));
+        assert_eq!(color.0, Color::Srgba(palettes::css::GREEN));
+        assert_eq!(area.0, 6.);
+        // both parts are cleaned up together
+        app.world_mut()
+            .entity_mut(entity)
+            .remove::<Blueprint<Rect>>();
+        app.update();
+        assert_eq!(
+            app.world_mut()
+                .query_filtered::<(), Or<(With<RectSize>, With<RectColor>, With<RectArea>)>>()
+                .iter(app.world())
+                .count(),
+            0
+        );
+    }
+
+    #[test]
+    fn self_and_child() {
+        #[derive(Bundle)]
+        struct RectBundle {
+            color: RectColor,
+        }
+
+        impl FromBlueprint<Rect> for RectBundle {
+            type Params<'w, 's> = ();
+            fn from_blueprint(_: &Rect, _: &mut StaticSystemParam<Self::Params<'_, '_>>) -> Self {
+                RectBundle {
+                    color: RectColor(Color::Srgba(palettes::css::RED)),
+                }
+            }
+        }
+
+        #[derive(Bundle)]
+        struct RectChildBundle {
+            area: RectArea,
+            size: RectSize,
+        }
+
+        impl FromBlueprint<Rect> for RectChildBundle {
+            type Params<'w, 's> = ();
+            fn from_blueprint(
+                blueprint: &Rect,
+                _: &mut StaticSystemParam<Self::Params<'_, '_>>,
+            ) -> Self {
+                RectChildBundle {
+                    size: RectSize(blueprint.size),
+                    area: RectArea(blueprint.size.x * blueprint.size.y),
+                }
+            }
+        }
+
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            // can add multiple prefabs for the same blueprint component
+            .add_plugins(BlueprintPlugin::<Rect, RectBundle>::default())
+            .add_plugins(BlueprintPlugin::<Rect, RectChildBundle, AsChild>::default());
+        // spawn the blueprint component
+        let entity = app.world_mut().spawn(Blueprint::<Rect>::default()).id();
+        app.update();
+        let parent_entities = app
+            .world_mut()
+            .query::<(Entity, &RectColor, &Children)>()
+            .iter(app.world())
+            .collect::<Vec<_>>();
+        assert_eq!(parent_entities.len(), 1);
+        let parent_entity = parent_entities.first().unwrap().0;
+        let child_entities = app
+            .world_mut()
+            .query::<(&RectSize, &RectArea, &Parent)>()
+            .iter(app.world())
+            .collect::<Vec<_>>();
+        assert_eq!(child_entities.len(), 1);
+        assert_eq!(child_entities.first().unwrap().2.get(), parent_entity);
+        // cleanup is handled automatically
+        app.world_mut()
+            .entity_mut(entity)
+            .remove::<Blueprint<Rect>>();
+        app.update();
+        assert_eq!(
+            app.world_mut()
+                .query_filtered::<(), Or<(With<RectSize>, With<RectColor>, With<RectArea>)>>()
+                .iter(app.world())
+                .collect::<Vec<_>>()
+                .len(),
+            0
+        );
+    }
+
+    #[test]
+    fn self_and_child_registration() {
+        #[derive(Bundle)]
+        struct RectBundle {
+            size: RectSize,
+        }
+
+        impl FromBlueprint<Rect> for RectBundle {
+            type Params<'w, 's> = ();
+            fn from_blueprint(
+                blueprint: &Rect,
+                _: &mut StaticSystemParam<Self::Params<'_, '_>>,
+            ) -> Self {
+                RectBundle {
+                    size: RectSize(blueprint.size),
+                }
+            }
+        }
+
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Rect, RectBundle>::self_and_child());
+        let entity = app
+            .world_mut()
+            .spawn(Blueprint::new(Rect { size: Vec2::ONE }))
+            .id();
+        app.update();
+        assert_eq!(app.world().get::<RectSize>(entity).unwrap().0, Vec2::ONE);
+        let children = app.world().get::<Children>(entity).unwrap();
+        assert_eq!(children.len(), 1);
+        assert_eq!(
+            app.world().get::<RectSize>(children[0]).unwrap().0,
+            Vec2::ONE
+        );
+
+        app.world_mut()
+            .entity_mut(entity)
+            .remove::<Blueprint<Rect>>();
+        app.update();
+        let sizes = app
+            .world_mut()
+            .query::<&RectSize>()
+            .iter(app.world())
+            .count();
+        assert_eq!(sizes, 0);
+    }
+
+    #[test]
+    fn pending_and_built_filters() {
+        #[derive(Bundle)]
+        struct RectChildBundle {
+            size: RectSize,
+        }
+
+        impl FromBlueprint<Rect> for RectChildBundle {
+            type Params<'w, 's> = ();
+            fn from_blueprint(
+                blueprint: &Rect,
+                _: &mut StaticSystemParam<Self::Params<'_, '_>>,
+            ) -> Self {
+                RectChildBundle {
+                    size: RectSize(blueprint.size),
+                }
+            }
+        }
+
+        #[derive(Resource, Default)]
+        struct LoadingScreen {
+            pending: usize,
+        }
+
+        // e.g. a loading screen that waits until every rect has been built
+        fn count_pending(query: Query<(), Pending<Rect>>, mut loading: ResMut<LoadingScreen>) {
+            loading.pending = query.iter().count();
+        }
+
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Rect, RectChildBundle, AsChild>::default())
+            .init_resource::<LoadingScreen>()
+            .add_systems(Update, count_pending.before(BlueprintsSet));
+        let entity = app.world_mut().spawn(Blueprint::<Rect>::default()).id();
+        app.world_mut().spawn(Blueprint::<Rect>::default());
+        app.update();
+        assert_eq!(app.world().resource::<LoadingScreen>().pending, 2);
+        assert_eq!(
+            app.world_mut()
+                .query_filtered::<(), Built<Rect>>()
+                .iter(app.world())
+                .count(),
+            2
+        );
+        app.update();
+        assert_eq!(app.world().resource::<LoadingScreen>().pending, 0);
+        // removing the blueprint also clears the built marker
+        app.world_mut()
+            .entity_mut(entity)
+            .remove::<Blueprint<Rect>>();
+        app.update();
+        assert!(!app
+            .world()
+            .entity(entity)
+            .contains::<BlueprintBuilt<Rect>>());
+    }
+
+    // inspectors (e.g. bevy_editor_pls) edit blueprints through reflection, often by mutating
+    // a nested field, which must still flag the outer component as changed
+    #[test]
+    fn reflect_mutation_rebuilds() {
+        use bevy::reflect::GetPath;
+
+        #[derive(Bundle)]
+        struct RectBundle {
+            size: RectSize,
+        }
+
+        impl FromBlueprint<Rect> for RectBundle {
+            type Params<'w, 's> = ();
+            fn from_blueprint(
+                blueprint: &Rect,
+                _: &mut StaticSystemParam<Self::Params<'_, '_>>,
+            ) -> Self {
+                RectBundle {
+                    size: RectSize(blueprint.size),
+                }
+            }
+        }
+
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Rect, RectBundle>::default());
+        let entity = app.world_mut().spawn(Blueprint::<Rect>::default()).id();
+        app.update();
+
+        let registry = app.world().resource::<AppTypeRegistry>().clone();
+        let registry = registry.read();
+        let reflect_component = registry
+            .get_type_data::<ReflectComponent>(std::any::TypeId::of::<Blueprint<Rect>>())
+            .unwrap();
+        let mut entity_mut = app.world_mut().entity_mut(entity);
+        let mut blueprint = reflect_component.reflect_mut(&mut entity_mut).unwrap();
+        *blueprint.path_mut::<f32>(".0.size.x").unwrap() = 3.;
+        app.update();
+        assert_eq!(
+            app.world().entity(entity).get::<RectSize>().unwrap().0,
+            Vec2::new(3., 0.)
+        );
+    }
+
+    #[test]
+    fn blueprint_data_from_entity_ref() {
+        use bevy::ecs::system::RunSystemOnce;
+
+        #[derive(Resource, Default)]
+        struct ReadSizes(Vec<Option<Vec2>>);
+
+        fn read_sizes(world: &mut World) {
+            let sizes = world
+                .iter_entities()
+                .map(|entity_ref| blueprint_data::<Rect>(entity_ref).map(|rect| rect.size))
+                .collect();
+            world.insert_resource(ReadSizes(sizes));
+        }
+
+        let mut world = World::new();
+        world.spawn(Blueprint::new(Rect { size: Vec2::ONE }));
+        world.spawn(RectSize(Vec2::ONE));
+        world.run_system_once(read_sizes);
+        let mut sizes = world.resource::<ReadSizes>().0.clone();
+        sizes.sort_by_key(Option::is_none);
+        assert_eq!(sizes, vec![Some(Vec2::ONE), None]);
+    }
+
+    #[test]
+    fn blueprint_bundle() {
+        let mut world = World::new();
+        let entity = world
+            .spawn(
+                Blueprint::bundle(Rect::default()).with_transform(Transform::from_xyz(1., 2., 3.)),
+            )
+            .id();
+        assert_eq!(world.get::<Name>(entity).unwrap().as_str(), "Rect");
+        assert_eq!(
+            world.get::<Transform>(entity).unwrap().translation,
+            Vec3::new(1., 2., 3.)
+        );
+        assert!(world.get::<Blueprint<Rect>>(entity).is_some());
+    }
+
+    #[test]
+    fn blueprints_param() {
+        #[derive(Bundle)]
+        struct RectBundle {
+            size: RectSize,
+        }
+
+        impl FromBlueprint<Rect> for RectBundle {
+            type Params<'w, 's> = ();
+            fn from_blueprint(
+                blueprint: &Rect,
+                _: &mut StaticSystemParam<Self::Params<'_, '_>>,
+            ) -> Self {
+                RectBundle {
+                    size: RectSize(blueprint.size),
+                }
+            }
+        }
+
+        #[derive(Resource)]
+        struct Reset;
+
+        // e.g. "reset all rects"
+        fn reset_rects(mut commands: Commands, mut rects: Blueprints<Rect, Built<Rect>>) {
+            rects.for_each_mut(|_, rect| rect.size = Vec2::ONE);
+            commands.remove_resource::<Reset>();
+        }
+
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Rect, RectBundle>::default())
+            .add_systems(
+                Update,
+                reset_rects
+                    .run_if(resource_exists::<Reset>)
+                    .before(BlueprintsSet),
+            );
+        for size in 2..5 {
+            app.world_mut().spawn(Blueprint::new(Rect {
+                size: Vec2::splat(size as f32),
+            }));
+        }
+        app.update();
+        app.insert_resource(Reset);
+        app.update();
+        assert!(app
+            .world_mut()
+            .query::<&RectSize>()
+            .iter(app.world())
+            .all(|size| size.0 == Vec2::ONE));
+    }
+
+    #[derive(Resource, Default)]
+    struct BuildCount(usize);
+
+    impl FromBlueprint<Rect> for () {
+        type Params<'w, 's> = ResMut<'w, BuildCount>;
+        fn from_blueprint(_: &Rect, count: &mut StaticSystemParam<Self::Params<'_, '_>>) {
+            count.0 += 1;
+        }
+    }
+
+    #[test]
+    fn rebuild_on_parent_change() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Rect, (), AsChild>::default().rebuild_on_parent_change())
+            .init_resource::<BuildCount>();
+        let parent = app.world_mut().spawn_empty().id();
+        let entity = app
+            .world_mut()
+            .spawn((Blueprint::<Rect>::default(), GlobalTransform::default()))
+            .id();
+        app.update();
+        assert_eq!(app.world().resource::<BuildCount>().0, 1);
+        let child = app.world().get::<Children>(entity).unwrap()[0];
+
+        app.update();
+        assert_eq!(app.world().resource::<BuildCount>().0, 1);
+
+        // reparenting rebuilds the child
+        app.world_mut().entity_mut(entity).set_parent(parent);
+        app.update();
+        assert_eq!(app.world().resource::<BuildCount>().0, 2);
+        let children = app.world().get::<Children>(entity).unwrap();
+        assert_eq!(children.len(), 1);
+        assert_ne!(children[0], child);
+
+        *app.world_mut().get_mut::<GlobalTransform>(entity).unwrap() =
+            GlobalTransform::from_xyz(1., 0., 0.);
+        app.update();
+        assert_eq!(app.world().resource::<BuildCount>().0, 3);
+        app.update();
+        assert_eq!(app.world().resource::<BuildCount>().0, 3);
+    }
+
+    #[test]
+    fn added_only() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Rect, (), AsNothing>::default().added_only())
+            .init_resource::<BuildCount>();
+        let entity = app.world_mut().spawn(Blueprint::<Rect>::default()).id();
+        app.update();
+        assert_eq!(app.world().resource::<BuildCount>().0, 1);
+
+        // mutating the blueprint does not rebuild it
+        app.world_mut()
+            .get_mut::<Blueprint<Rect>>(entity)
+            .unwrap()
+            .0
+            .size = Vec2::ONE;
+        app.update();
+        assert_eq!(app.world().resource::<BuildCount>().0, 1);
+
+        // re-adding it does
+        app.world_mut()
+            .entity_mut(entity)
+            .remove::<Blueprint<Rect>>()
+            .insert(Blueprint::<Rect>::default());
+        app.update();
+        assert_eq!(app.world().resource::<BuildCount>().0, 2);
+    }
+
+    #[test]
+    fn builder() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(
+                BlueprintPlugin::<Rect, (), AsChild>::builder()
+                    .skip_unchanged()
+                    .cleanup_policy(CleanupPolicy::Keep)
+                    .with_child(|child| {
+                        child.insert(Name::new("rect"));
+                    }),
+            )
+            .init_resource::<BuildCount>();
+        let entity = app.world_mut().spawn(Blueprint::<Rect>::default()).id();
+        app.update();
+        let child = app.world().get::<Children>(entity).unwrap()[0];
+        assert_eq!(app.world().get::<Name>(child).unwrap().as_str(), "rect");
+
+        app.world_mut()
+            .get_mut::<Blueprint<Rect>>(entity)
+            .unwrap()
+            .set_changed();
+        app.update();
+        assert_eq!(app.world().resource::<BuildCount>().0, 1);
+
+        app.world_mut()
+            .entity_mut(entity)
+            .remove::<Blueprint<Rect>>();
+        app.update();
+        assert!(app.world().get_entity(child).is_some());
+    }
+
+    #[test]
+    fn with_child() {
+        #[derive(Bundle)]
+        struct RectChildBundle {
+            size: RectSize,
+        }
+
+        impl FromBlueprint<Rect> for RectChildBundle {
+            type Params<'w, 's> = ();
+            fn from_blueprint(
+                blueprint: &Rect,
+                _: &mut StaticSystemParam<Self::Params<'_, '_>>,
+            ) -> Self {
+                RectChildBundle {
+                    size: RectSize(blueprint.size),
+                }
+            }
+        }
+
+        #[derive(Component)]
+        struct Pickable;
+
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(
+                BlueprintPlugin::<Rect, RectChildBundle, AsChild>::default().with_child(|child| {
+                    child.insert(Pickable);
+                }),
+            );
+        let entity = app.world_mut().spawn(Blueprint::<Rect>::default()).id();
+        app.update();
+        let child = app.world().get::<Children>(entity).unwrap()[0];
+        assert!(app.world().get::<Pickable>(child).is_some());
+        assert!(app.world().get::<Pickable>(entity).is_none());
+
+        // the rebuilt child is configured too
+        app.world_mut()
+            .get_mut::<Blueprint<Rect>>(entity)
+            .unwrap()
+            .0
+            .size = Vec2::ONE;
+        app.update();
+        let rebuilt = app.world().get::<Children>(entity).unwrap()[0];
+        assert_ne!(rebuilt, child);
+        assert!(app.world().get::<Pickable>(rebuilt).is_some());
+    }
+
+    #[test]
+    fn permanent_failure_despawns() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(
+                BlueprintPlugin::<Rect, RectMesh>::default()
+                    .retry_failed_builds(5, OnPermanentFailure::Despawn),
+            );
+        let corrupt = app.world_mut().spawn(Blueprint::<Rect>::default()).id();
+        let loading = app.world_mut().spawn(Blueprint::<Rect>::default()).id();
+        for _ in 0..4 {
+            app.update();
+        }
+        assert!(app.world().get_entity(corrupt).is_some());
+
+        // a transient failure recovers before the cap
+        app.world_mut()
+            .get_mut::<Blueprint<Rect>>(loading)
+            .unwrap()
+            .0
+            .size = Vec2::ONE;
+        app.update();
+        assert!(app.world().get_entity(corrupt).is_none());
+        assert!(app.world().get::<RectMesh>(loading).is_some());
+
+        let errors = app
+            .world_mut()
+            .resource_mut::<Events<BlueprintBuildError<Rect>>>()
+            .drain()
+            .collect::<Vec<_>>();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].blueprint, corrupt);
+        assert_eq!(errors[0].attempts, 5);
+        assert_eq!(errors[0].error, "missing mesh");
+    }
+
+    #[test]
+    fn retries_leave_other_prefabs_alone() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins((
+                BlueprintPlugin::<Rect, RectMesh>::default()
+                    .retry_failed_builds(5, OnPermanentFailure::Keep),
+                BlueprintPlugin::<Rect, (), AsChild>::default(),
+                BlueprintHistoryPlugin::<Rect>::default(),
+            ))
+            .init_resource::<BuildCount>();
+        let entity = app.world_mut().spawn(Blueprint::<Rect>::default()).id();
+        app.update();
+        let child = app.world().get::<Children>(entity).unwrap()[0];
+        for _ in 0..3 {
+            app.update();
+        }
+        assert_eq!(app.world().resource::<BuildCount>().0, 1);
+        assert_eq!(app.world().get::<Children>(entity).unwrap()[..], [child]);
+        assert!(app
+            .world()
+            .get::<BlueprintHistory<Rect>>(entity)
+            .unwrap()
+            .is_empty());
+
+        // the retried prefab still builds once it can
+        app.world_mut()
+            .get_mut::<Blueprint<Rect>>(entity)
+            .unwrap()
+            .0
+            .size = Vec2::ONE;
+        app.update();
+        assert!(app.world().get::<RectMesh>(entity).is_some());
+        assert_eq!(app.world().resource::<BuildCount>().0, 2);
+    }
+
+    #[test]
+    fn added_only_builds_once_held_back_builds_can_happen() {
+        #[derive(Component)]
+        struct Spawned;
+
+        #[derive(Resource)]
+        struct ShowRects;
+
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(
+                BlueprintPlugin::<Rect, (), AsNothing>::default()
+                    .added_only()
+                    .gated_by::<Spawned>()
+                    .build_if(|world| world.contains_resource::<ShowRects>()),
+            )
+            .init_resource::<BuildCount>();
+        let entity = app.world_mut().spawn(Blueprint::<Rect>::default()).id();
+        app.update();
+        assert_eq!(app.world().resource::<BuildCount>().0, 0);
+
+        // the gate opens while the condition does not hold
+        app.world_mut().entity_mut(entity).insert(Spawned);
+        app.update();
+        assert_eq!(app.world().resource::<BuildCount>().0, 0);
+
+        app.world_mut().insert_resource(ShowRects);
+        app.update();
+        assert_eq!(app.world().resource::<BuildCount>().0, 1);
+
+        // changes are still ignored, but reopening the gate builds it again
+        app.world_mut()
+            .get_mut::<Blueprint<Rect>>(entity)
+            .unwrap()
+            .0
+            .size = Vec2::ONE;
+        app.update();
+        assert_eq!(app.world().resource::<BuildCount>().0, 1);
+        app.world_mut().entity_mut(entity).remove::<Spawned>();
+        app.update();
+        app.world_mut().entity_mut(entity).insert(Spawned);
+        app.update();
+        assert_eq!(app.world().resource::<BuildCount>().0, 2);
+
+        // as does the condition holding again
+        app.world_mut().remove_resource::<ShowRects>();
+        app.update();
+        app.world_mut().insert_resource(ShowRects);
+        app.update();
+        assert_eq!(app.world().resource::<BuildCount>().0, 3);
+        app.update();
+        assert_eq!(app.world().resource::<BuildCount>().0, 3);
+    }
+
+    #[test]
+    fn gated_by() {
+        #[derive(Component)]
+        struct Spawned;
+
+        #[derive(Bundle)]
+        struct RectBundle {
+            size: RectSize,
+        }
+
+        impl FromBlueprint<Rect> for RectBundle {
+            type Params<'w, 's> = ();
+            fn from_blueprint(
+                blueprint: &Rect,
+                _: &mut StaticSystemParam<Self::Params<'_, '_>>,
+            ) -> Self {
+                RectBundle {
+                    size: RectSize(blueprint.size),
+                }
+            }
+        }
+
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Rect, RectBundle>::default().gated_by::<Spawned>());
+        let entity = app.world_mut().spawn(Blueprint::<Rect>::default()).id();
+        app.update();
+        assert!(app.world().get::<RectSize>(entity).is_none());
+
+        app.world_mut().entity_mut(entity).insert(Spawned);
+        app.update();
+        assert!(app.world().get::<RectSize>(entity).is_some());
+
+        // changes made while gated are built once the gate opens again
+        app.world_mut().entity_mut(entity).remove::<Spawned>();
+        app.world_mut()
+            .get_mut::<Blueprint<Rect>>(entity)
+            .unwrap()
+            .0
+            .size = Vec2::ONE;
+        app.update();
+        assert!(app.world().get::<RectSize>(entity).is_none());
+
+        app.world_mut().entity_mut(entity).insert(Spawned);
+        app.update();
+        assert_eq!(app.world().get::<RectSize>(entity).unwrap().0, Vec2::ONE);
+
+        // toggled within a frame
+        app.world_mut().entity_mut(entity).remove::<Spawned>();
+        app.world_mut().entity_mut(entity).insert(Spawned);
+        app.update();
+        assert!(app.world().get::<RectSize>(entity).is_some());
+    }
+
+    #[test]
+    fn build_if() {
+        #[derive(Default, Resource)]
+        struct ShowRects(bool);
+
+        #[derive(Bundle)]
+        struct RectBundle {
+            size: RectSize,
+        }
+
+        impl FromBlueprint<Rect> for RectBundle {
+            type Params<'w, 's> = ();
+            fn from_blueprint(
+                blueprint: &Rect,
+                _: &mut StaticSystemParam<Self::Params<'_, '_>>,
+            ) -> Self {
+                RectBundle {
+                    size: RectSize(blueprint.size),
+                }
+            }
+        }
+
+        fn built(app: &mut App) -> usize {
+            app.world_mut()
+                .query::<&RectSize>()
+                .iter(app.world())
+                .count()
+        }
+
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(
+                BlueprintPlugin::<Rect, RectBundle, AsChild>::default()
+                    .build_if(|world| world.resource::<ShowRects>().0),
+            )
+            .init_resource::<ShowRects>();
+        let first = app.world_mut().spawn(Blueprint::<Rect>::default()).id();
+        app.update();
+        assert_eq!(built(&mut app), 0);
+
+        app.world_mut().resource_mut::<ShowRects>().0 = true;
+        app.world_mut().spawn(Blueprint::<Rect>::default());
+        app.update();
+        assert_eq!(built(&mut app), 2);
+
+        // torn down, and changes made in the meantime are built once it holds again
+        app.world_mut().resource_mut::<ShowRects>().0 = false;
+        app.update();
+        assert_eq!(built(&mut app), 0);
+        app.world_mut()
+            .get_mut::<Blueprint<Rect>>(first)
+            .unwrap()
+            .0
+            .size = Vec2::ONE;
+        app.update();
+        assert_eq!(built(&mut app), 0);
+
+        app.world_mut().resource_mut::<ShowRects>().0 = true;
+        app.update();
+        assert_eq!(built(&mut app), 2);
+        let child = app.world().get::<Children>(first).unwrap()[0];
+        assert_eq!(app.world().get::<RectSize>(child).unwrap().0, Vec2::ONE);
+    }
+
+    #[test]
+    #[cfg(feature = "asset")]
+    fn requires_collection() {
+        // inserted by the asset loader once every texture is loaded
+        #[derive(Resource)]
+        struct RectTextures;
+
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(
+                BlueprintPlugin::<Rect, Outline>::default().requires_collection::<RectTextures>(),
+            );
+        let entity = app.world_mut().spawn(Blueprint::<Rect>::default()).id();
+        app.update();
+        app.update();
+        assert!(app.world().get::<Outline>(entity).is_none());
+
+        app.insert_resource(RectTextures);
+        app.update();
+        assert!(app.world().get::<Outline>(entity).is_some());
+    }
+
+    #[test]
+    #[cfg(feature = "state")]
+    fn in_state_blueprints() {
+        use bevy::state::app::StatesPlugin;
+
+        #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, States)]
+        enum GameState {
+            #[default]
+            Menu,
+            Playing,
+            Paused,
+        }
+
+        #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, SubStates)]
+        #[source(GameState = GameState::Playing)]
+        enum Phase {
+            #[default]
+            Combat,
+            Shop,
+        }
+
+        #[derive(Bundle)]
+        struct RectBundle {
+            size: RectSize,
+        }
+
+        impl FromBlueprint<Rect> for RectBundle {
+            type Params<'w, 's> = ();
+            fn from_blueprint(
+                blueprint: &Rect,
+                _: &mut StaticSystemParam<Self::Params<'_, '_>>,
+            ) -> Self {
+                RectBundle {
+                    size: RectSize(blueprint.size),
+                }
+            }
+        }
+
+        // the targets are already gone when the next state is entered
+        #[derive(Default, Resource)]
+        struct BuiltOnPause(Option<bool>);
+
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, StatesPlugin, BlueprintsPlugin))
+            .init_state::<GameState>()
+            .add_sub_state::<Phase>()
+            .add_plugins(
+                BlueprintPlugin::<Rect, RectBundle>::default()
+                    .in_state_blueprints(GameState::Playing),
+            )
+            .init_resource::<BuiltOnPause>()
+            .add_systems(
+                OnEnter(GameState::Paused),
+                |query: Query<(), With<RectSize>>, mut built: ResMut<BuiltOnPause>| {
+                    built.0 = Some(!query.is_empty());
+                },
+            );
+        let entity = app.world_mut().spawn(Blueprint::<Rect>::default()).id();
+        app.update();
+        assert!(app.world().get::<RectSize>(entity).is_none());
+
+        let set_state = |app: &mut App, state| {
+            app.world_mut()
+                .resource_mut::<NextState<GameState>>()
+                .set(state);
+            app.update();
+        };
+        set_state(&mut app, GameState::Playing);
+        assert!(app.world().get::<RectSize>(entity).is_some());
+
+        // substate transitions keep the targets
+        app.world_mut()
+            .resource_mut::<NextState<Phase>>()
+            .set(Phase::Shop);
+        app.update();
+        assert!(app.world().get::<RectSize>(entity).is_some());
+        assert_eq!(
+            app.world()
+                .get::<BlueprintBuildStats>(entity)
+                .unwrap()
+                .count,
+            1
+        );
+
+        set_state(&mut app, GameState::Paused);
+        assert!(app.world().get::<RectSize>(entity).is_none());
+        assert_eq!(app.world().resource::<BuiltOnPause>().0, Some(false));
+
+        set_state(&mut app, GameState::Playing);
+        assert!(app.world().get::<RectSize>(entity).is_some());
+    }
+
+    #[test]
+    fn plugin_order() {
+        #[derive(Bundle)]
+        struct RectBundle {
+            size: RectSize,
+        }
+
+        impl FromBlueprint<Rect> for RectBundle {
+            type Params<'w, 's> = ();
+            fn from_blueprint(
+                blueprint: &Rect,
+                _: &mut StaticSystemParam<Self::Params<'_, '_>>,
+            ) -> Self {
+                RectBundle {
+                    size: RectSize(blueprint.size),
+                }
+            }
+        }
+
+        // `BlueprintsPlugin` is added by the `BlueprintPlugin`, and adding it again is harmless
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugins(BlueprintPlugin::<Rect, RectBundle>::default())
+            .add_plugins(BlueprintsPlugin);
+        let entity = app
+            .world_mut()
+            .spawn(Blueprint::new(Rect { size: Vec2::ONE }))
+            .id();
+        app.update();
+        assert_eq!(app.world().get::<RectSize>(entity).unwrap().0, Vec2::ONE);
+
+        app.world_mut()
+            .entity_mut(entity)
+            .remove::<Blueprint<Rect>>();
+        app.update();
+        assert!(app.world().get::<RectSize>(entity).is_none());
+    }
+
+    #[test]
+    fn keep_on_cleanup() {
+        #[derive(Component)]
+        struct Enemy;
+
+        #[derive(Bundle)]
+        struct EnemyBundle {
+            enemy: Enemy,
+            size: RectSize,
+        }
+
+        impl FromBlueprint<Rect> for EnemyBundle {
+            type Params<'w, 's> = ();
+            fn from_blueprint(
+                blueprint: &Rect,
+                _: &mut StaticSystemParam<Self::Params<'_, '_>>,
+            ) -> Self {
+                EnemyBundle {
+                    enemy: Enemy,
+                    size: RectSize(blueprint.size),
+                }
+            }
+        }
+
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Rect, EnemyBundle>::default().keep_on_cleanup::<Enemy>())
+            .add_plugins(
+                BlueprintPlugin::<Rect, EnemyBundle, AsChild>::default().keep_on_cleanup::<Enemy>(),
+            );
+        let entity = app.world_mut().spawn(Blueprint::<Rect>::default()).id();
+        app.update();
+        let child = app.world().get::<Children>(entity).unwrap()[0];
+        app.world_mut()
+            .entity_mut(entity)
+            .remove::<Blueprint<Rect>>();
+        app.update();
+        for target in [entity, child] {
+            assert!(app.world().get::<Enemy>(target).is_some());
+            assert!(app.world().get::<RectSize>(target).is_none());
+        }
+        assert!(app.world().get::<BlueprintBuilt<Rect>>(entity).is_none());
+
+        // inserting the blueprint again builds it anew
+        app.world_mut()
+            .entity_mut(entity)
+            .insert(Blueprint::<Rect>::default());
+        app.update();
+        assert!(app.world().get::<RectSize>(entity).is_some());
+        assert!(app.world().get_entity(child).is_none());
+    }
+
+    #[test]
+    fn cleanup_policy() {
+        #[derive(Bundle)]
+        struct RectBundle {
+            size: RectSize,
+        }
+
+        #[derive(Bundle)]
+        struct RectChildBundle {
+            size: RectSize,
+        }
+
+        impl FromBlueprint<Rect> for RectBundle {
+            type Params<'w, 's> = ();
+            fn from_blueprint(
+                blueprint: &Rect,
+                _: &mut StaticSystemParam<Self::Params<'_, '_>>,
+            ) -> Self {
+                RectBundle {
+                    size: RectSize(blueprint.size),
+                }
+            }
+        }
+
+        impl FromBlueprint<Rect> for RectChildBundle {
+            type Params<'w, 's> = ();
+            fn from_blueprint(
+                blueprint: &Rect,
+                _: &mut StaticSystemParam<Self::Params<'_, '_>>,
+            ) -> Self {
+                RectChildBundle {
+                    size: RectSize(blueprint.size),
+                }
+            }
+        }
+
+        // replaces the size with the area it had
+        fn keep_area(world: &mut World, entity: Entity) {
+            let mut entity = world.entity_mut(entity);
+            let size = entity.take::<RectSize>().unwrap().0;
+            entity.insert(RectArea(size.x * size.y));
+        }
+
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(
+                BlueprintPlugin::<Rect, RectBundle>::default().cleanup_policy(CleanupPolicy::Keep),
+            )
+            .add_plugins(
+                BlueprintPlugin::<Rect, RectChildBundle, AsChild>::default()
+                    .cleanup_policy(CleanupPolicy::Keep),
+            );
+        let entity = app
+            .world_mut()
+            .spawn(Blueprint::new(Rect {
+                size: Vec2::new(2., 3.),
+            }))
+            .id();
+        app.update();
+        app.world_mut()
+            .entity_mut(entity)
+            .remove::<Blueprint<Rect>>();
+        app.update();
+        // both targets are detached rather than removed
+        assert!(app.world().get::<BlueprintBuilt<Rect>>(entity).is_none());
+        assert!(app.world().get::<RectSize>(entity).is_some());
+        let children = app.world().get::<Children>(entity).unwrap();
+        assert_eq!(children.len(), 1);
+        assert!(app.world().get::<RectSize>(children[0]).is_some());
+
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(
+                BlueprintPlugin::<Rect, RectBundle>::default()
+                    .cleanup_policy(CleanupPolicy::Custom(keep_area)),
+            );
+        let entity = app
+            .world_mut()
+            .spawn(Blueprint::new(Rect {
+                size: Vec2::new(2., 3.),
+            }))
+            .id();
+        app.update();
+        app.world_mut()
+            .entity_mut(entity)
+            .remove::<Blueprint<Rect>>();
+        app.update();
+        assert!(app.world().get::<RectSize>(entity).is_none());
+        assert_eq!(app.world().get::<RectArea>(entity).unwrap().0, 6.);
+    }
+
+    #[test]
+    fn build_timestamp() {
+        #[derive(Component)]
+        struct SpawnedAt(f32);
 
-    #[derive(Clone, Component, Default, Reflect)]
-    #[reflect(Component)]
-    struct RectColor(Color);
+        impl FromBlueprint<Rect> for SpawnedAt {
+            type Params<'w, 's> = Res<'w, Time>;
+            fn from_blueprint(
+                _: &Rect,
+                time: &mut StaticSystemParam<Self::Params<'_, '_>>,
+            ) -> Self {
+                SpawnedAt(time.elapsed_seconds())
+            }
+        }
 
-    #[derive(Clone, Component, Default, Reflect)]
-    #[reflect(Component)]
-    struct RectArea(f32);
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Rect, SpawnedAt>::default())
+            .insert_resource(bevy::time::TimeUpdateStrategy::ManualDuration(
+                std::time::Duration::from_secs(1),
+            ));
+        app.update();
+        app.update();
+        let entity = app.world_mut().spawn(Blueprint::<Rect>::default()).id();
+        app.update();
+        let built_at = app.world().resource::<Time>().elapsed_seconds();
+        assert!(built_at > 0.);
+        assert_eq!(app.world().get::<SpawnedAt>(entity).unwrap().0, built_at);
 
+        // the timestamp stays until a rebuild captures a new one
+        app.update();
+        assert_eq!(app.world().get::<SpawnedAt>(entity).unwrap().0, built_at);
+        app.world_mut()
+            .get_mut::<Blueprint<Rect>>(entity)
+            .unwrap()
+            .0
+            .size = Vec2::ONE;
+        app.update();
+        let rebuilt_at = app.world().resource::<Time>().elapsed_seconds();
+        assert!(rebuilt_at > built_at);
+        assert_eq!(app.world().get::<SpawnedAt>(entity).unwrap().0, rebuilt_at);
+    }
+
+    #[cfg(feature = "render")]
     #[test]
-    fn single_blueprint() {
+    fn render_layers() {
         #[derive(Bundle)]
-        struct RectBundle {
+        struct PreviewBundle {
             size: RectSize,
-            color: RectColor,
-            area: RectArea,
+            layers: RenderLayers,
         }
 
-        impl FromBlueprint<Rect> for RectBundle {
+        impl FromBlueprint<Rect> for PreviewBundle {
             type Params<'w, 's> = ();
             fn from_blueprint(
                 blueprint: &Rect,
                 _: &mut StaticSystemParam<Self::Params<'_, '_>>,
             ) -> Self {
-                RectBundle {
+                PreviewBundle {
                     size: RectSize(blueprint.size),
-                    color: RectColor(Color::Srgba(palettes::css::BLUE)),
-                    area: RectArea(blueprint.size.x * blueprint.size.y),
+                    layers: RenderLayers::layer(1),
                 }
             }
         }
 
         let mut app = App::new();
         app.add_plugins((MinimalPlugins, BlueprintsPlugin))
-            // whereas BlueprintsPlugin prepares the framework, individual BlueprintPlugins must
-            // be added to define which blueprints to reactively manage
-            .add_plugins(BlueprintPlugin::<Rect, RectBundle>::default());
-        // when spawning an entity of this kind, spawn a Blueprint::<MyType>
-        // (note: MyType does not have to be a component, but it can be)
+            .add_plugins(
+                BlueprintPlugin::<Rect, PreviewBundle, AsChild>::default()
+                    .render_layers(RenderLayers::layer(2)),
+            );
+        let entity = app.world_mut().spawn(Blueprint::<Rect>::default()).id();
+        app.update();
+        let child = app.world().get::<Children>(entity).unwrap()[0];
+        assert_eq!(
+            app.world().get::<RenderLayers>(child),
+            Some(&RenderLayers::layer(2))
+        );
+        assert!(app.world().get::<RenderLayers>(entity).is_none());
+    }
+
+    #[test]
+    fn build_stats() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Rect, ()>::default())
+            .init_resource::<BuildCount>();
+        let entity = app.world_mut().spawn(Blueprint::<Rect>::default()).id();
+        app.update();
+        let stats = *app.world().get::<BlueprintBuildStats>(entity).unwrap();
+        assert_eq!(stats.count, 1);
+
+        app.update();
+        assert_eq!(
+            app.world()
+                .get::<BlueprintBuildStats>(entity)
+                .unwrap()
+                .count,
+            1
+        );
+
+        app.world_mut()
+            .get_mut::<Blueprint<Rect>>(entity)
+            .unwrap()
+            .0
+            .size = Vec2::ONE;
+        app.update();
+        let entity_ref = app.world().entity(entity);
+        let rebuilt = entity_ref.get::<BlueprintBuildStats>().unwrap();
+        assert_eq!(rebuilt.count, 2);
+        assert!(rebuilt.last_tick.get() > stats.last_tick.get());
+        // never marked as changed after being inserted
+        let ticks = entity_ref
+            .get_change_ticks::<BlueprintBuildStats>()
+            .unwrap();
+        assert_eq!(ticks.last_changed_tick(), ticks.added_tick());
+    }
+
+    #[test]
+    fn despawned_children_diagnostic() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Rect, ()>::default())
+            .add_plugins(BlueprintPlugin::<Rect, (), AsChild>::default())
+            .init_resource::<BuildCount>();
+        let entities = (0..3)
+            .map(|_| app.world_mut().spawn(Blueprint::<Rect>::default()).id())
+            .collect::<Vec<_>>();
+        app.update();
+
+        let despawned_children = |app: &App| {
+            app.world()
+                .resource::<DiagnosticsStore>()
+                .get(&BlueprintsPlugin::DESPAWNED_CHILDREN)
+                .and_then(|diagnostic| diagnostic.value())
+        };
+        assert_eq!(despawned_children(&app), Some(0.));
+
+        // only the `AsChild` plugin despawns anything
+        app.world_mut().despawn(entities[0]);
+        app.world_mut().despawn(entities[1]);
+        app.update();
+        assert_eq!(despawned_children(&app), Some(2.));
+        app.update();
+        assert_eq!(despawned_children(&app), Some(0.));
+    }
+
+    #[test]
+    fn sync_time_diagnostics() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Rect, ()>::default())
+            .add_plugins(BlueprintPlugin::<Rect, (), AsChild>::default())
+            .init_resource::<BuildCount>();
+        // every other type shares a diagnostic once the limit is reached
+        let mut sync_times = app.world_mut().resource_mut::<SyncTimes>();
+        for i in 1..BlueprintsPlugin::MAX_SYNC_DIAGNOSTICS {
+            let path = DiagnosticPath::new(format!("blueprints/sync/Type{i}"));
+            sync_times.diagnostics.insert(path);
+        }
+        app.add_plugins(BlueprintPlugin::<Leaf, LeafMesh>::default());
+        app.world_mut().spawn(Blueprint::<Rect>::default());
+        app.update();
+        app.update();
+
+        let store = app.world().resource::<DiagnosticsStore>();
+        let rect = store
+            .get(&BlueprintsPlugin::sync_diagnostic::<Rect>())
+            .unwrap();
+        assert_eq!(rect.path().as_str(), "blueprints/sync/Rect");
+        // both plugins of `Rect` are measured together, once per frame, even when there is nothing
+        // to sync
+        assert_eq!(rect.measurements().count(), 2);
+        assert!(store
+            .get(&BlueprintsPlugin::sync_diagnostic::<Leaf>())
+            .is_none());
+        let other = store.get(&BlueprintsPlugin::OTHER_SYNC_TIMES).unwrap();
+        assert_eq!(other.measurements().count(), 2);
+    }
+
+    #[test]
+    fn side_effect_only() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Rect, (), AsNothing>::default())
+            .init_resource::<BuildCount>();
         let entity = app.world_mut().spawn(Blueprint::<Rect>::default()).id();
+        app.world_mut().spawn(Blueprint::<Rect>::default());
         app.update();
+        assert_eq!(app.world().resource::<BuildCount>().0, 2);
         assert_eq!(
             app.world_mut()
-                .query::<(&RectSize, &RectColor, &RectArea)>()
+                .query_filtered::<(), Built<Rect>>()
                 .iter(app.world())
-                .collect::<Vec<_>>()
-                .len(),
-            1
+                .count(),
+            2
         );
+        app.world_mut()
+            .entity_mut(entity)
+            .remove::<Blueprint<Rect>>();
+        app.update();
+        assert_eq!(app.world().resource::<BuildCount>().0, 2);
+        assert!(!app.world().entity(entity).contains::<IsBlueprint>());
+    }
+
+    #[test]
+    fn skip_unchanged() {
+        #[derive(Component)]
+        struct CountedRect;
+
+        impl FromBlueprint<Rect> for CountedRect {
+            type Params<'w, 's> = ResMut<'w, BuildCount>;
+            fn from_blueprint(
+                _: &Rect,
+                count: &mut StaticSystemParam<Self::Params<'_, '_>>,
+            ) -> Self {
+                count.0 += 1;
+                CountedRect
+            }
+        }
+
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            // Rect only implements Reflect, not PartialEq
+            .add_plugins(BlueprintPlugin::<Rect, CountedRect>::default().skip_unchanged())
+            .init_resource::<BuildCount>();
+        let entity = app.world_mut().spawn(Blueprint::<Rect>::default()).id();
+        app.update();
+        assert_eq!(app.world().resource::<BuildCount>().0, 1);
+        // flagged as changed without changing the value
+        app.world_mut()
+            .get_mut::<Blueprint<Rect>>(entity)
+            .unwrap()
+            .set_changed();
+        app.update();
+        assert_eq!(app.world().resource::<BuildCount>().0, 1);
+        app.world_mut()
+            .get_mut::<Blueprint<Rect>>(entity)
+            .unwrap()
+            .0
+            .size = Vec2::ONE;
+        app.update();
+        assert_eq!(app.world().resource::<BuildCount>().0, 2);
+        // re-adding a removed blueprint with the same value still builds it
+        let blueprint = app
+            .world_mut()
+            .entity_mut(entity)
+            .take::<Blueprint<Rect>>()
+            .unwrap();
+        app.update();
+        app.world_mut().entity_mut(entity).insert(blueprint);
+        app.update();
+        assert_eq!(app.world().resource::<BuildCount>().0, 3);
+        assert!(app.world().entity(entity).contains::<CountedRect>());
+    }
+
+    #[derive(Component)]
+    struct RectMesh;
+
+    impl TryFromBlueprint<Rect> for RectMesh {
+        type Params<'w, 's> = ();
+        type Error = &'static str;
+        fn try_from_blueprint(
+            blueprint: &Rect,
+            _: &mut StaticSystemParam<Self::Params<'_, '_>>,
+        ) -> Result<Self, Self::Error> {
+            if blueprint.size == Vec2::ZERO {
+                Err("missing mesh")
+            } else {
+                Ok(RectMesh)
+            }
+        }
+    }
+
+    #[test]
+    fn failed_build_stays_pending() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Rect, RectMesh>::default());
+        let entity = app.world_mut().spawn(Blueprint::<Rect>::default()).id();
+        app.update();
+        assert!(!app.world().entity(entity).contains::<RectMesh>());
         assert_eq!(
             app.world_mut()
-                .query_filtered::<(), Or<(With<RectSize>, With<RectColor>, With<RectArea>)>>()
+                .query_filtered::<(), Pending<Rect>>()
                 .iter(app.world())
-                .collect::<Vec<_>>()
-                .len(),
+                .count(),
             1
         );
-        // when the blueprint component is removed, the additional components are removed as well
+        // once the blueprint can be built, it is
         app.world_mut()
-            .entity_mut(entity)
+            .get_mut::<Blueprint<Rect>>(entity)
+            .unwrap()
+            .0
+            .size = Vec2::ONE;
+        app.update();
+        assert!(app.world().entity(entity).contains::<RectMesh>());
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "blueprint Rect on entity 0v1 failed to build RectMesh: missing mesh"
+    )]
+    fn strict_build_panics() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(StrictBlueprintPlugin::<Rect, RectMesh>::default().panic_in_release());
+        app.world_mut().spawn(Blueprint::<Rect>::default());
+        app.update();
+    }
+
+    // targets built this frame are visible to systems ordered after the flush, even without
+    // automatic sync points
+    #[test]
+    fn targets_exist_after_flush() {
+        #[derive(Bundle)]
+        struct RectChildBundle {
+            area: RectArea,
+        }
+
+        impl FromBlueprint<Rect> for RectChildBundle {
+            type Params<'w, 's> = ();
+            fn from_blueprint(
+                blueprint: &Rect,
+                _: &mut StaticSystemParam<Self::Params<'_, '_>>,
+            ) -> Self {
+                RectChildBundle {
+                    area: RectArea(blueprint.size.x * blueprint.size.y),
+                }
+            }
+        }
+
+        #[derive(Resource, Default)]
+        struct SeenAreas(usize);
+
+        fn count_areas(query: Query<&RectArea>, mut seen: ResMut<SeenAreas>) {
+            seen.0 = query.iter().count();
+        }
+
+        for auto_insert_apply_deferred in [true, false] {
+            let mut app = App::new();
+            app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+                .add_plugins(BlueprintPlugin::<Rect, RectChildBundle, AsChild>::default())
+                .init_resource::<SeenAreas>()
+                .add_systems(Update, count_areas.after(BlueprintSet::Flush))
+                .edit_schedule(Update, |schedule| {
+                    schedule.set_build_settings(bevy::ecs::schedule::ScheduleBuildSettings {
+                        auto_insert_apply_deferred,
+                        ..default()
+                    });
+                });
+            app.world_mut().spawn(Blueprint::<Rect>::default());
+            app.update();
+            assert_eq!(app.world().resource::<SeenAreas>().0, 1);
+        }
+    }
+
+    #[test]
+    fn force_as_child() {
+        #[derive(Bundle)]
+        struct RectBundle {
+            size: RectSize,
+        }
+
+        impl FromBlueprint<Rect> for RectBundle {
+            type Params<'w, 's> = ();
+            fn from_blueprint(
+                blueprint: &Rect,
+                _: &mut StaticSystemParam<Self::Params<'_, '_>>,
+            ) -> Self {
+                RectBundle {
+                    size: RectSize(blueprint.size),
+                }
+            }
+        }
+
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Rect, RectBundle>::default());
+        let regular = app.world_mut().spawn(Blueprint::<Rect>::default()).id();
+        let forced = app
+            .world_mut()
+            .spawn((Blueprint::<Rect>::default(), ForceAsChild))
+            .id();
+        app.update();
+
+        assert!(app.world().entity(regular).contains::<RectSize>());
+        assert!(app.world().get::<Children>(regular).is_none());
+        assert!(!app.world().entity(forced).contains::<RectSize>());
+        let child = app.world().get::<Children>(forced).unwrap()[0];
+        assert!(app.world().entity(child).contains::<RectSize>());
+        assert_eq!(
+            app.world()
+                .get::<ForcedChild<Rect, RectBundle>>(forced)
+                .unwrap()
+                .child(),
+            child
+        );
+
+        // rebuilding replaces the child
+        app.world_mut()
+            .get_mut::<Blueprint<Rect>>(forced)
+            .unwrap()
+            .0
+            .size = Vec2::ONE;
+        app.update();
+        let children = app.world().get::<Children>(forced).unwrap();
+        assert_eq!(children.len(), 1);
+        assert!(app.world().get_entity(child).is_none());
+        let child = children[0];
+        assert_eq!(app.world().get::<RectSize>(child).unwrap().0, Vec2::ONE);
+
+        // the child is cleaned up even if the blueprint entity is despawned on its own
+        app.world_mut().despawn(forced);
+        app.update();
+        assert!(app.world().get_entity(child).is_none());
+        let sizes = app
+            .world_mut()
+            .query::<&RectSize>()
+            .iter(app.world())
+            .count();
+        assert_eq!(sizes, 1);
+    }
+
+    #[test]
+    fn child_parent() {
+        #[derive(Bundle)]
+        struct RectChildBundle {
+            size: RectSize,
+        }
+
+        impl FromBlueprint<Rect> for RectChildBundle {
+            type Params<'w, 's> = ();
+            fn from_blueprint(
+                blueprint: &Rect,
+                _: &mut StaticSystemParam<Self::Params<'_, '_>>,
+            ) -> Self {
+                RectChildBundle {
+                    size: RectSize(blueprint.size),
+                }
+            }
+        }
+
+        #[derive(Resource, Deref)]
+        struct UiRoot(Entity);
+
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(
+                BlueprintPlugin::<Rect, RectChildBundle, AsChild>::default()
+                    .child_parent(ChildParent::resource::<UiRoot>()),
+            );
+        // no root yet
+        let early = app.world_mut().spawn(Blueprint::<Rect>::default()).id();
+        app.update();
+        assert_eq!(app.world().get::<Children>(early).unwrap().len(), 1);
+
+        let root = app.world_mut().spawn_empty().id();
+        app.world_mut().insert_resource(UiRoot(root));
+        let entities = [(); 2].map(|_| app.world_mut().spawn(Blueprint::<Rect>::default()).id());
+        app.update();
+        let children = app.world().get::<Children>(root).unwrap().to_vec();
+        assert_eq!(children.len(), 2);
+        for (entity, child) in entities.into_iter().zip(children) {
+            assert!(app.world().get::<Children>(entity).is_none());
+            assert_eq!(
+                app.world().get::<BlueprintSource>(child),
+                Some(&BlueprintSource(entity))
+            );
+        }
+
+        // rebuilding replaces the child under the root, and removing the blueprint despawns it
+        app.world_mut()
+            .get_mut::<Blueprint<Rect>>(entities[0])
+            .unwrap()
+            .0
+            .size = Vec2::ONE;
+        app.update();
+        assert_eq!(app.world().get::<Children>(root).unwrap().len(), 2);
+        app.world_mut()
+            .entity_mut(entities[0])
             .remove::<Blueprint<Rect>>();
+        app.world_mut().despawn(entities[1]);
+        app.update();
+        assert!(app
+            .world()
+            .get::<Children>(root)
+            .is_none_or(|children| children.is_empty()));
+    }
+
+    #[test]
+    fn child_targets_have_a_spatial_parent() {
+        #[derive(Bundle)]
+        struct RectChildBundle {
+            size: RectSize,
+            transform: TransformBundle,
+        }
+
+        impl FromBlueprint<Rect> for RectChildBundle {
+            type Params<'w, 's> = ();
+            fn from_blueprint(
+                blueprint: &Rect,
+                _: &mut StaticSystemParam<Self::Params<'_, '_>>,
+            ) -> Self {
+                RectChildBundle {
+                    size: RectSize(blueprint.size),
+                    transform: TransformBundle::from_transform(Transform::from_xyz(1., 0., 0.)),
+                }
+            }
+        }
+
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, TransformPlugin, HierarchyPlugin))
+            .add_plugins(BlueprintsPlugin)
+            .add_plugins(BlueprintPlugin::<Rect, RectChildBundle, AsChild>::default());
+        let bare = app.world_mut().spawn(Blueprint::<Rect>::default()).id();
+        let placed = app
+            .world_mut()
+            .spawn((
+                Blueprint::<Rect>::default(),
+                TransformBundle::from_transform(Transform::from_xyz(5., 0., 0.)),
+            ))
+            .id();
         app.update();
+
+        let child_x = |app: &App, entity: Entity| {
+            let child = app.world().get::<Children>(entity).unwrap()[0];
+            app.world()
+                .get::<GlobalTransform>(child)
+                .unwrap()
+                .translation()
+                .x
+        };
         assert_eq!(
-            app.world_mut()
-                .query_filtered::<(), Or<(With<RectSize>, With<RectColor>, With<RectArea>)>>()
-                .iter(app.world())
-                .collect::<Vec<_>>()
-                .len(),
-            0
+            app.world().get::<Transform>(bare),
+            Some(&Transform::IDENTITY)
+        );
+        assert_eq!(child_x(&app, bare), 1.);
+        // the existing transform is kept
+        assert_eq!(
+            app.world().get::<Transform>(placed),
+            Some(&Transform::from_xyz(5., 0., 0.))
         );
+        assert_eq!(child_x(&app, placed), 6.);
     }
 
     #[test]
-    fn multiple_blueprint() {
-        #[derive(Default, Reflect)]
-        pub struct SpecificRect;
-
+    fn spawned_events_report_children() {
         #[derive(Bundle)]
-        struct RectBundle {
-            size: RectSize,
-            color: RectColor,
+        struct RectAreaBundle {
             area: RectArea,
         }
 
-        impl FromBlueprint<Rect> for RectBundle {
+        impl FromBlueprint<Rect> for RectAreaBundle {
             type Params<'w, 's> = ();
             fn from_blueprint(
                 blueprint: &Rect,
                 _: &mut StaticSystemParam<Self::Params<'_, '_>>,
             ) -> Self {
-                RectBundle {
-                    size: RectSize(blueprint.size),
-                    color: RectColor(Color::Srgba(palettes::css::RED)),
+                RectAreaBundle {
                     area: RectArea(blueprint.size.x * blueprint.size.y),
                 }
             }
         }
 
-        impl FromBlueprint<SpecificRect> for RectBundle {
-            type Params<'w, 's> = ();
-            fn from_blueprint(
-                _: &SpecificRect,
-                _: &mut StaticSystemParam<Self::Params<'_, '_>>,
-            ) -> Self {
-                let rect: Rect = Rect {
-                    size: Vec2::new(10., 10.),
-                };
-                RectBundle {
-                    size: RectSize(rect.size),
-                    color: RectColor(Color::Srgba(palettes::css::BLUE)),
-                    area: RectArea(rect.size.x * rect.size.y),
-                }
+        #[derive(Component)]
+        struct Collider;
+
+        fn attach_colliders(
+            mut commands: Commands,
+            mut spawned_events: EventReader<BlueprintSpawned<Rect>>,
+        ) {
+            for event in spawned_events.read() {
+                commands.entity(event.child.unwrap()).insert(Collider);
             }
         }
 
         let mut app = App::new();
         app.add_plugins((MinimalPlugins, BlueprintsPlugin))
-            // can add as many blueprints as we want (including overlapping ones, be careful!)
-            .add_plugins(BlueprintPlugin::<Rect, RectBundle>::default())
-            .add_plugins(BlueprintPlugin::<SpecificRect, RectBundle>::default());
-        // spawn one entity for each blueprint
-        let entity1 = app.world_mut().spawn(Blueprint::<Rect>::default()).id();
-        let entity2 = app
-            .world_mut()
-            .spawn(Blueprint::<SpecificRect>::default())
-            .id();
-        app.update();
-        assert_eq!(
-            app.world_mut()
-                .query::<(&RectSize, &RectColor, &RectArea)>()
-                .iter(app.world())
-                .collect::<Vec<_>>()
-                .len(),
-            2
-        );
-        // clean up one at a time
-        app.world_mut()
-            .entity_mut(entity1)
-            .remove::<Blueprint<Rect>>();
-        app.update();
-        assert_eq!(
-            app.world_mut()
-                .query_filtered::<(), Or<(With<RectSize>, With<RectColor>, With<RectArea>)>>()
-                .iter(app.world())
-                .collect::<Vec<_>>()
-                .len(),
-            1
-        );
-        app.world_mut()
-            .entity_mut(entity2)
-            .remove::<Blueprint<SpecificRect>>();
+            .add_plugins(BlueprintPlugin::<Rect, RectAreaBundle, AsChild>::default())
+            .add_plugins(BlueprintPlugin::<Rect, Blueprint<Leaf>, AsChild>::default())
+            .add_systems(Update, attach_colliders.after(BlueprintSet::Flush));
+        let entity = app.world_mut().spawn(Blueprint::<Rect>::default()).id();
         app.update();
-        assert_eq!(
-            app.world_mut()
-                .query_filtered::<(), Or<(With<RectSize>, With<RectColor>, With<RectArea>)>>()
-                .iter(app.world())
-                .collect::<Vec<_>>()
-                .len(),
-            0
-        );
+
+        let children = app.world().get::<Children>(entity).unwrap();
+        assert_eq!(children.len(), 2);
+        assert!(children
+            .iter()
+            .all(|child| app.world().entity(*child).contains::<Collider>()));
+        let events = app.world().resource::<Events<BlueprintSpawned<Rect>>>();
+        let mut reported = events
+            .get_reader()
+            .read(events)
+            .map(|event| (event.blueprint, event.child.unwrap()))
+            .collect::<Vec<_>>();
+        reported.sort();
+        let mut expected = children
+            .iter()
+            .map(|child| (entity, *child))
+            .collect::<Vec<_>>();
+        expected.sort();
+        assert_eq!(reported, expected);
     }
 
     #[test]
-    fn multiple_prefab() {
+    fn overlapping_prefabs_apply_in_registration_order() {
         #[derive(Bundle)]
-        struct RectBundle {
+        struct FullSize {
             size: RectSize,
-            color: RectColor,
         }
 
-        impl FromBlueprint<Rect> for RectBundle {
+        impl FromBlueprint<Rect> for FullSize {
             type Params<'w, 's> = ();
             fn from_blueprint(
                 blueprint: &Rect,
                 _: &mut StaticSystemParam<Self::Params<'_, '_>>,
             ) -> Self {
-                RectBundle {
+                FullSize {
                     size: RectSize(blueprint.size),
-                    color: RectColor(Color::Srgba(palettes::css::RED)),
                 }
             }
         }
 
-        // for some reason I need this bundle spawned separately
-        // e.g. it is used in a separate crate or something
         #[derive(Bundle)]
-        struct SecondRectBundle {
-            area: RectArea,
+        struct HalfSize {
+            size: RectSize,
         }
 
-        impl FromBlueprint<Rect> for SecondRectBundle {
+        impl FromBlueprint<Rect> for HalfSize {
             type Params<'w, 's> = ();
             fn from_blueprint(
                 blueprint: &Rect,
                 _: &mut StaticSystemParam<Self::Params<'_, '_>>,
             ) -> Self {
-                SecondRectBundle {
-                    area: RectArea(blueprint.size.x * blueprint.size.y),
+                HalfSize {
+                    size: RectSize(blueprint.size / 2.),
                 }
             }
         }
 
+        // the prefabs are registered by separate `add_plugins` calls, with another blueprint's
+        // prefab in between; both insert `RectSize` at the same sync point, and the one registered
+        // last is applied last
+        for _ in 0..20 {
+            let mut app = App::new();
+            app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+                .add_plugins(BlueprintPlugin::<Rect, FullSize>::default())
+                .add_plugins(BlueprintPlugin::<Leaf, LeafMesh>::default())
+                .add_plugins(BlueprintPlugin::<Rect, HalfSize>::default());
+            let entity = app
+                .world_mut()
+                .spawn(Blueprint::new(Rect {
+                    size: Vec2::splat(4.),
+                }))
+                .id();
+            app.update();
+            assert_eq!(
+                app.world().get::<RectSize>(entity).unwrap().0,
+                Vec2::splat(2.)
+            );
+
+            app.world_mut()
+                .get_mut::<Blueprint<Rect>>(entity)
+                .unwrap()
+                .0
+                .size = Vec2::splat(8.);
+            app.update();
+            assert_eq!(
+                app.world().get::<RectSize>(entity).unwrap().0,
+                Vec2::splat(4.)
+            );
+        }
+    }
+
+    #[derive(Default, Reflect)]
+    struct Leaf;
+
+    #[derive(Component)]
+    struct LeafMesh;
+
+    impl FromBlueprint<Leaf> for LeafMesh {
+        type Params<'w, 's> = ();
+        fn from_blueprint(_: &Leaf, _: &mut StaticSystemParam<Self::Params<'_, '_>>) -> Self {
+            LeafMesh
+        }
+    }
+
+    impl FromBlueprint<Rect> for Blueprint<Leaf> {
+        type Params<'w, 's> = ();
+        fn from_blueprint(_: &Rect, _: &mut StaticSystemParam<Self::Params<'_, '_>>) -> Self {
+            Blueprint::default()
+        }
+    }
+
+    #[test]
+    fn nearest_blueprint_of_nested_children() {
         let mut app = App::new();
         app.add_plugins((MinimalPlugins, BlueprintsPlugin))
-            // can add multiple prefabs for the same blueprint component
-            .add_plugins(BlueprintPlugin::<Rect, RectBundle>::default())
-            .add_plugins(BlueprintPlugin::<Rect, SecondRectBundle>::default());
-        // spawn the blueprint component
-        let entity = app.world_mut().spawn(Blueprint::<Rect>::default()).id();
+            .add_plugins(BlueprintPlugin::<Rect, Blueprint<Leaf>, AsChild>::default())
+            .add_plugins(BlueprintPlugin::<Leaf, LeafMesh, AsChild>::default());
+        let outer = app.world_mut().spawn(Blueprint::<Rect>::default()).id();
+        // the inner blueprint is only spawned once the outer one is built
         app.update();
-        assert_eq!(
-            app.world_mut()
-                .query::<(&RectSize, &RectColor, &RectArea)>()
-                .iter(app.world())
-                .collect::<Vec<_>>()
-                .len(),
-            1
-        );
-        // cleanup is handled automatically
-        app.world_mut()
-            .entity_mut(entity)
-            .remove::<Blueprint<Rect>>();
         app.update();
+
+        let inner = app.world().get::<Children>(outer).unwrap()[0];
+        let mesh = app.world().get::<Children>(inner).unwrap()[0];
         assert_eq!(
-            app.world_mut()
-                .query_filtered::<(), Or<(With<RectSize>, With<RectColor>, With<RectArea>)>>()
-                .iter(app.world())
-                .collect::<Vec<_>>()
-                .len(),
-            0
+            app.world().get::<BlueprintSource>(mesh),
+            Some(&BlueprintSource(inner))
         );
+        let unrelated = app.world_mut().spawn_empty().set_parent(mesh).id();
+
+        let mut state = SystemState::<Query<BlueprintAncestry>>::new(app.world_mut());
+        let query = state.get(app.world());
+        assert_eq!(nearest_blueprint(outer, &query), Some(outer));
+        assert_eq!(nearest_blueprint(inner, &query), Some(inner));
+        assert_eq!(nearest_blueprint(mesh, &query), Some(inner));
+        assert_eq!(nearest_blueprint(unrelated, &query), Some(inner));
+        let loose = app.world_mut().spawn_empty().id();
+        let query = state.get(app.world());
+        assert_eq!(nearest_blueprint(loose, &query), None);
     }
 
+    // removing a blueprint and despawning its entity in the same frame, in any order relative to
+    // the cleanup systems, should never panic or leave prefabs behind
     #[test]
-    fn self_and_child() {
+    fn remove_and_despawn_same_frame() {
         #[derive(Bundle)]
         struct RectBundle {
-            color: RectColor,
+            size: RectSize,
         }
 
         impl FromBlueprint<Rect> for RectBundle {
             type Params<'w, 's> = ();
-            fn from_blueprint(_: &Rect, _: &mut StaticSystemParam<Self::Params<'_, '_>>) -> Self {
+            fn from_blueprint(
+                blueprint: &Rect,
+                _: &mut StaticSystemParam<Self::Params<'_, '_>>,
+            ) -> Self {
                 RectBundle {
-                    color: RectColor(Color::Srgba(palettes::css::RED)),
+                    size: RectSize(blueprint.size),
                 }
             }
         }
@@ -467,7 +4641,6 @@ mod tests {
         #[derive(Bundle)]
         struct RectChildBundle {
             area: RectArea,
-            size: RectSize,
         }
 
         impl FromBlueprint<Rect> for RectChildBundle {
@@ -477,7 +4650,6 @@ mod tests {
                 _: &mut StaticSystemParam<Self::Params<'_, '_>>,
             ) -> Self {
                 RectChildBundle {
-                    size: RectSize(blueprint.size),
                     area: RectArea(blueprint.size.x * blueprint.size.y),
                 }
             }
@@ -485,39 +4657,84 @@ mod tests {
 
         let mut app = App::new();
         app.add_plugins((MinimalPlugins, BlueprintsPlugin))
-            // can add multiple prefabs for the same blueprint component
             .add_plugins(BlueprintPlugin::<Rect, RectBundle>::default())
             .add_plugins(BlueprintPlugin::<Rect, RectChildBundle, AsChild>::default());
-        // spawn the blueprint component
-        let entity = app.world_mut().spawn(Blueprint::<Rect>::default()).id();
-        app.update();
-        let parent_entities = app
-            .world_mut()
-            .query::<(Entity, &RectColor, &Children)>()
-            .iter(app.world())
-            .collect::<Vec<_>>();
-        assert_eq!(parent_entities.len(), 1);
-        let parent_entity = parent_entities.first().unwrap().0;
-        let child_entities = app
-            .world_mut()
-            .query::<(&RectSize, &RectArea, &Parent)>()
-            .iter(app.world())
-            .collect::<Vec<_>>();
-        assert_eq!(child_entities.len(), 1);
-        assert_eq!(child_entities.first().unwrap().2.get(), parent_entity);
-        // cleanup is handled automatically
-        app.world_mut()
-            .entity_mut(entity)
-            .remove::<Blueprint<Rect>>();
-        app.update();
-        assert_eq!(
-            app.world_mut()
-                .query_filtered::<(), Or<(With<RectSize>, With<RectColor>, With<RectArea>)>>()
+
+        // runs alongside the app's own cleanup systems, so that some entities can be despawned
+        // after their removal was read but before the cleanup commands are applied
+        let mut cleanup = Schedule::default();
+        cleanup.set_apply_final_deferred(false).add_systems((
+            BlueprintPlugin::<Rect, RectBundle>::handle_removed_blueprints,
+            BlueprintPlugin::<Rect, RectChildBundle, AsChild>::handle_removed_blueprints,
+        ));
+
+        let mut seed: u64 = 0x2545_f491_4f6c_dd1d;
+        let mut next = move || {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            seed
+        };
+        let mut live = Vec::new();
+        for _ in 0..200 {
+            let mut despawn_during_cleanup = Vec::new();
+            for _ in 0..(next() % 4) {
+                live.push(app.world_mut().spawn(Blueprint::<Rect>::default()).id());
+            }
+            let mut index = 0;
+            while index < live.len() {
+                let entity = live[index];
+                match next() % 8 {
+                    0 => {
+                        app.world_mut()
+                            .entity_mut(entity)
+                            .remove::<Blueprint<Rect>>();
+                        despawn_during_cleanup.push(entity);
+                    }
+                    1 => {
+                        app.world_mut().despawn(entity);
+                    }
+                    2 => {
+                        app.world_mut()
+                            .entity_mut(entity)
+                            .remove::<Blueprint<Rect>>();
+                        app.world_mut().despawn(entity);
+                    }
+                    _ => {
+                        index += 1;
+                        continue;
+                    }
+                }
+                live.swap_remove(index);
+            }
+            cleanup.run(app.world_mut());
+            for entity in despawn_during_cleanup {
+                app.world_mut().despawn(entity);
+            }
+            cleanup.apply_deferred(app.world_mut());
+            app.update();
+
+            let blueprints = app
+                .world_mut()
+                .query_filtered::<Entity, With<Blueprint<Rect>>>()
                 .iter(app.world())
-                .collect::<Vec<_>>()
-                .len(),
-            0
-        );
+                .collect::<Vec<_>>();
+            assert_eq!(blueprints.len(), live.len());
+            let prefabs = app
+                .world_mut()
+                .query_filtered::<Entity, With<RectSize>>()
+                .iter(app.world())
+                .collect::<Vec<_>>();
+            assert_eq!(prefabs.len(), live.len());
+            let children = app
+                .world_mut()
+                .query_filtered::<&Parent, With<RectArea>>()
+                .iter(app.world())
+                .map(Parent::get)
+                .collect::<Vec<_>>();
+            assert_eq!(children.len(), live.len());
+            assert!(children.iter().all(|parent| live.contains(parent)));
+        }
     }
 
     // We don't want to panic if FromBlueprint::<MyType>::Params is not ready at startup time
@@ -611,4 +4828,157 @@ mod tests {
         app.world_mut().spawn(Blueprint::<Rect>::default());
         app.update();
     }
+
+    #[derive(Clone, Debug, PartialEq, Eq, Hash, SystemSet)]
+    struct ThirdPartySet;
+
+    #[derive(Component)]
+    struct Outline;
+
+    impl FromBlueprint<Rect> for Outline {
+        type Params<'w, 's> = ();
+        fn from_blueprint(_: &Rect, _: &mut StaticSystemParam<Self::Params<'_, '_>>) -> Self {
+            Outline
+        }
+    }
+
+    #[test]
+    fn after_set() {
+        // whether the third-party system saw each new blueprint already built
+        #[derive(Default, Resource)]
+        struct Seen(Vec<bool>);
+
+        fn observe(query: Query<Has<Outline>, Added<Blueprint<Rect>>>, mut seen: ResMut<Seen>) {
+            seen.0.extend(query.iter());
+        }
+
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Rect, Outline>::default().after_set(ThirdPartySet))
+            .init_resource::<Seen>()
+            .add_systems(Update, observe.in_set(ThirdPartySet));
+        for _ in 0..3 {
+            app.world_mut().spawn(Blueprint::<Rect>::default());
+        }
+        app.update();
+        assert_eq!(app.world().resource::<Seen>().0, vec![false; 3]);
+        assert_eq!(
+            app.world_mut()
+                .query::<&Outline>()
+                .iter(app.world())
+                .count(),
+            3
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot be scheduled")]
+    fn circular_after_set() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .configure_sets(Update, ThirdPartySet.after(BlueprintsSet))
+            .add_plugins(BlueprintPlugin::<Rect, Outline>::default().after_set(ThirdPartySet));
+        app.finish();
+    }
+
+    #[test]
+    fn post_build() {
+        // external state that `from_blueprint` does not read
+        #[derive(Resource)]
+        struct ActiveLayer(u8);
+
+        #[derive(Component, Debug, PartialEq)]
+        struct Layer(u8);
+
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(
+                BlueprintPlugin::<Rect, Outline>::default().post_build(|target, _| {
+                    target.add(|mut target: EntityWorldMut| {
+                        let layer = target.world().resource::<ActiveLayer>().0;
+                        target.insert(Layer(layer));
+                    });
+                }),
+            )
+            .insert_resource(ActiveLayer(1));
+        let entity = app.world_mut().spawn(Blueprint::<Rect>::default()).id();
+        app.update();
+        assert_eq!(app.world().get::<Layer>(entity), Some(&Layer(1)));
+
+        // it runs again on rebuilds
+        app.world_mut().resource_mut::<ActiveLayer>().0 = 2;
+        app.world_mut()
+            .get_mut::<Blueprint<Rect>>(entity)
+            .unwrap()
+            .0
+            .size = Vec2::ONE;
+        app.update();
+        assert_eq!(app.world().get::<Layer>(entity), Some(&Layer(2)));
+    }
+
+    #[test]
+    fn auto_name() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Rect, Outline>::default().auto_name());
+        let nameless = app.world_mut().spawn(Blueprint::<Rect>::default()).id();
+        let named = app
+            .world_mut()
+            .spawn((Blueprint::<Rect>::default(), Name::new("player")))
+            .id();
+        app.update();
+        let name = |entity| app.world().get::<Name>(entity).map(Name::as_str);
+        assert_eq!(name(nameless), Some("Blueprint<Rect>"));
+        assert_eq!(name(named), Some("player"));
+    }
+    #[test]
+    fn targets_with_only_bundle_methods_are_used() {
+        // e.g. a custom target written before `attach_prefab`, building under a child of a child
+        struct AsGrandchild;
+
+        impl BlueprintTarget for AsGrandchild {
+            fn remove_target_bundle<T, P: Bundle + FromBlueprint<T>>(entity: &mut EntityCommands) {
+                entity.despawn_descendants();
+            }
+
+            fn attach_target_bundle<T, P: Bundle + FromBlueprint<T>>(
+                entity: &mut EntityCommands,
+                bundle: P,
+            ) {
+                entity.with_children(|builder| {
+                    builder.spawn_empty().with_children(|builder| {
+                        builder.spawn(bundle);
+                    });
+                });
+            }
+        }
+
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Rect, Outline, AsGrandchild>::default());
+        let entity = app.world_mut().spawn(Blueprint::<Rect>::default()).id();
+        app.update();
+        let grandparents = |app: &mut App| {
+            app.world_mut()
+                .query_filtered::<&Parent, With<Outline>>()
+                .iter(app.world())
+                .map(|parent| app.world().get::<Parent>(parent.get()).unwrap().get())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(grandparents(&mut app), vec![entity]);
+
+        app.world_mut()
+            .get_mut::<Blueprint<Rect>>(entity)
+            .unwrap()
+            .0
+            .size = Vec2::ONE;
+        app.update();
+        assert_eq!(grandparents(&mut app), vec![entity]);
+
+        app.world_mut()
+            .entity_mut(entity)
+            .remove::<Blueprint<Rect>>();
+        app.update();
+        assert!(grandparents(&mut app).is_empty());
+    }
 }
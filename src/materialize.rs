@@ -0,0 +1,219 @@
+use bevy::{ecs::system::StaticSystemParam, prelude::*};
+
+use crate::{BlueprintPlugin, BlueprintTarget, TryFromBlueprint};
+
+/// Why `materialize_blueprint_entity` could not build a blueprint.
+#[derive(Debug, PartialEq, Eq)]
+pub enum MaterializeError {
+    /// The `BlueprintPlugin` of the blueprint, prefab and target types was not added.
+    NoPlugin,
+    /// The entity does not exist, or has no blueprint of the type.
+    NoBlueprint(Entity),
+    /// The resources that the prefab's params read but are missing, by type name. Resources
+    /// that are only read optionally (e.g. `Option<Res<R>>`) are included.
+    MissingResources(Vec<String>),
+}
+
+impl std::fmt::Display for MaterializeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoPlugin => write!(f, "the blueprint plugin was not added"),
+            Self::NoBlueprint(entity) => write!(f, "entity {entity} has no blueprint to build"),
+            Self::MissingResources(names) => {
+                write!(
+                    f,
+                    "the prefab's params need missing resources: {}",
+                    names.join(", ")
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for MaterializeError {}
+
+/// Builds blueprints directly on a `World`, without running the app's schedule, e.g. in tools.
+pub trait BlueprintWorldExt {
+    /// Builds the prefab `P` of the entity's `Blueprint<B>` right away, as the `BlueprintPlugin`
+    /// with these types would in the next `Update`, so that its components can be queried as soon
+    /// as this returns. The plugin is not run again for the blueprint unless it changes.
+    ///
+    /// Nothing is built while the plugin's `build_if` conditions do not hold, or for a lazy or gated
+    /// blueprint that would not be built in `Update` either. A prefab that fails to build is
+    /// reported like any other build. A member of a `BlueprintTransaction` is built on its own,
+    /// leaving the pending transactions alone.
+    fn materialize_blueprint_entity<B, P, T>(
+        &mut self,
+        entity: Entity,
+    ) -> Result<(), MaterializeError>
+    where
+        B: Default + Reflect + Send + Sync + 'static,
+        P: Bundle + TryFromBlueprint<B>,
+        T: BlueprintTarget + Send + Sync + 'static;
+}
+
+impl BlueprintWorldExt for World {
+    fn materialize_blueprint_entity<B, P, T>(
+        &mut self,
+        entity: Entity,
+    ) -> Result<(), MaterializeError>
+    where
+        B: Default + Reflect + Send + Sync + 'static,
+        P: Bundle + TryFromBlueprint<B>,
+        T: BlueprintTarget + Send + Sync + 'static,
+    {
+        BlueprintPlugin::<B, P, T>::materialize(self, entity)
+    }
+}
+
+fn read_params<B, P: TryFromBlueprint<B>>(_: StaticSystemParam<P::Params<'_, '_>>) {}
+
+// the type names of the resources read by the params of the prefab `P` that are not in the world,
+// found from the params' access without running them (optional params read their resource just
+// the same, so they cannot be told apart)
+pub(crate) fn missing_resources<B, P>(world: &mut World) -> Vec<String>
+where
+    B: 'static,
+    P: TryFromBlueprint<B> + 'static,
+{
+    let mut system = IntoSystem::into_system(read_params::<B, P>);
+    system.initialize(world);
+    let storages = world.storages();
+    system
+        .component_access()
+        .reads_and_writes()
+        .filter(|id| {
+            let resource = storages.resources.get(*id).map(|data| data.is_present());
+            let non_send = storages
+                .non_send_resources
+                .get(*id)
+                .map(|data| data.is_present());
+            resource.or(non_send) == Some(false)
+        })
+        .filter_map(|id| world.components().get_info(id))
+        .map(|info| info.name().to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use super::*;
+    use crate::{
+        AsSelf, Blueprint, BlueprintBuildStats, BlueprintTransaction, BlueprintsPlugin,
+        FromBlueprint,
+    };
+
+    #[derive(Default, Reflect)]
+    struct Rect {
+        size: f32,
+    }
+
+    #[derive(Component)]
+    struct RectSize(f32);
+
+    impl FromBlueprint<Rect> for RectSize {
+        type Params<'w, 's> = ();
+        fn from_blueprint(
+            blueprint: &Rect,
+            _: &mut StaticSystemParam<Self::Params<'_, '_>>,
+        ) -> Self {
+            RectSize(blueprint.size)
+        }
+    }
+
+    #[derive(Resource)]
+    struct Theme(Color);
+
+    #[derive(Component)]
+    struct RectColor(Color);
+
+    impl FromBlueprint<Rect> for RectColor {
+        type Params<'w, 's> = Res<'w, Theme>;
+        fn from_blueprint(_: &Rect, theme: &mut StaticSystemParam<Self::Params<'_, '_>>) -> Self {
+            RectColor(theme.0)
+        }
+    }
+
+    #[test]
+    fn materialize_single_entity() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Rect, RectSize>::default())
+            .add_plugins(BlueprintPlugin::<Rect, RectColor>::default());
+        let world = app.world_mut();
+        let entity = world.spawn(Blueprint::new(Rect { size: 2. })).id();
+        let other = world.spawn(Blueprint::new(Rect { size: 3. })).id();
+        world
+            .materialize_blueprint_entity::<Rect, RectSize, AsSelf>(entity)
+            .unwrap();
+        assert_eq!(world.get::<RectSize>(entity).unwrap().0, 2.);
+        assert!(world.get::<RectSize>(other).is_none());
+
+        assert_eq!(
+            world.materialize_blueprint_entity::<Rect, RectColor, AsSelf>(entity),
+            Err(MaterializeError::MissingResources(vec![
+                std::any::type_name::<Theme>().to_string()
+            ]))
+        );
+        assert_eq!(
+            world.materialize_blueprint_entity::<Rect, RectSize, AsSelf>(Entity::PLACEHOLDER),
+            Err(MaterializeError::NoBlueprint(Entity::PLACEHOLDER))
+        );
+
+        // `RectSize` is not built again in `Update`, unlike the other blueprint
+        world.insert_resource(Theme(Color::BLACK));
+        app.update();
+        assert_eq!(
+            app.world()
+                .get::<BlueprintBuildStats>(entity)
+                .unwrap()
+                .count,
+            2
+        );
+        assert_eq!(app.world().get::<RectSize>(other).unwrap().0, 3.);
+        assert_eq!(
+            app.world().get::<RectColor>(entity).unwrap().0,
+            Color::BLACK
+        );
+    }
+
+    #[test]
+    fn materialize_leaves_transactions_alone() {
+        #[derive(Component)]
+        struct Deployed;
+
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Rect, RectSize>::default().gated_by::<Deployed>());
+        // held until the member that is not deployed yet builds
+        let held = app
+            .world_mut()
+            .spawn((
+                Blueprint::<Rect>::default(),
+                BlueprintTransaction(1),
+                Deployed,
+            ))
+            .id();
+        app.world_mut()
+            .spawn((Blueprint::<Rect>::default(), BlueprintTransaction(1)));
+        app.update();
+        assert!(app.world().get::<RectSize>(held).is_none());
+
+        let world = app.world_mut();
+        let member = world
+            .spawn((
+                Blueprint::<Rect>::default(),
+                BlueprintTransaction(2),
+                Deployed,
+            ))
+            .id();
+        world.spawn((Blueprint::<Rect>::default(), BlueprintTransaction(2)));
+        world
+            .materialize_blueprint_entity::<Rect, RectSize, AsSelf>(member)
+            .unwrap();
+        assert!(world.get::<RectSize>(member).is_some());
+        assert!(world.get::<RectSize>(held).is_none());
+    }
+}
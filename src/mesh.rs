@@ -0,0 +1,89 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+/// Access to `Assets<Mesh>` and the `Assets<M>` of a material, which most rendered prefabs need
+/// in `from_blueprint`.
+///
+/// Use `StandardMaterial` for 3D prefabs and `ColorMaterial` for 2D ones.
+#[derive(SystemParam)]
+pub struct MeshMaterialParams<'w, M: Asset> {
+    pub meshes: ResMut<'w, Assets<Mesh>>,
+    pub materials: ResMut<'w, Assets<M>>,
+}
+
+impl<'w, M: Asset> MeshMaterialParams<'w, M> {
+    pub fn add_mesh(&mut self, mesh: impl Into<Mesh>) -> Handle<Mesh> {
+        self.meshes.add(mesh)
+    }
+
+    /// Adds a cuboid mesh centered on the origin.
+    pub fn add_box(&mut self, size: Vec3) -> Handle<Mesh> {
+        self.meshes.add(Cuboid::from_size(size))
+    }
+
+    /// Adds a rectangle mesh in the XY plane, centered on the origin.
+    pub fn add_rectangle(&mut self, size: Vec2) -> Handle<Mesh> {
+        self.meshes.add(Rectangle::from_size(size))
+    }
+
+    pub fn add_material(&mut self, material: impl Into<M>) -> Handle<M> {
+        self.materials.add(material)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{ecs::system::StaticSystemParam, prelude::*};
+
+    use super::*;
+    use crate::{Blueprint, BlueprintPlugin, BlueprintsPlugin, FromBlueprint};
+
+    #[derive(Asset, TypePath)]
+    struct Paint(Color);
+
+    impl From<Color> for Paint {
+        fn from(color: Color) -> Self {
+            Paint(color)
+        }
+    }
+
+    #[derive(Default, Reflect)]
+    struct Crate {
+        size: f32,
+    }
+
+    #[derive(Component)]
+    struct CrateMesh(Handle<Mesh>, Handle<Paint>);
+
+    impl FromBlueprint<Crate> for CrateMesh {
+        type Params<'w, 's> = MeshMaterialParams<'w, Paint>;
+        fn from_blueprint(
+            blueprint: &Crate,
+            params: &mut StaticSystemParam<Self::Params<'_, '_>>,
+        ) -> Self {
+            CrateMesh(
+                params.add_box(Vec3::splat(blueprint.size)),
+                params.add_material(Color::WHITE),
+            )
+        }
+    }
+
+    #[test]
+    fn mesh_material_params_add_assets() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default(), BlueprintsPlugin))
+            .init_asset::<Mesh>()
+            .init_asset::<Paint>()
+            .add_plugins(BlueprintPlugin::<Crate, CrateMesh>::default());
+        let entity = app
+            .world_mut()
+            .spawn(Blueprint::new(Crate { size: 2. }))
+            .id();
+        app.update();
+
+        let CrateMesh(mesh, paint) = app.world().get::<CrateMesh>(entity).unwrap();
+        let mesh = app.world().resource::<Assets<Mesh>>().get(mesh).unwrap();
+        assert_eq!(mesh.compute_aabb().unwrap().half_extents, Vec3::ONE.into());
+        let paint = app.world().resource::<Assets<Paint>>().get(paint).unwrap();
+        assert_eq!(paint.0, Color::WHITE);
+    }
+}
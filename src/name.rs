@@ -0,0 +1,355 @@
+use std::marker::PhantomData;
+
+use bevy::{ecs::system::SystemParam, prelude::*, utils::get_short_name};
+
+use crate::{Blueprint, BlueprintSet, BlueprintSource, BlueprintsPlugin, BlueprintsSet};
+
+/// Blueprints that can name the entities they are built on, e.g. from a `label` field.
+pub trait NamedBlueprint {
+    /// The `Name` to give the target, or `None` to leave its name alone.
+    fn blueprint_name(&self) -> Option<String>;
+}
+
+/// Which entities a `BlueprintNamePlugin` names.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NameTarget {
+    /// The entity with the `Blueprint<B>`.
+    #[default]
+    Blueprint,
+    /// The entities spawned for the blueprint, such as `AsChild` prefabs.
+    Spawned,
+}
+
+type NamedQuery<'w, 's, B> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        Ref<'static, Blueprint<B>>,
+        Option<&'static Children>,
+    ),
+>;
+
+#[derive(Resource)]
+struct NameSettings<B> {
+    target: NameTarget,
+    entity_index: bool,
+    marker: PhantomData<B>,
+}
+
+impl<B: NamedBlueprint> NameSettings<B> {
+    // `source` is the blueprint entity, so that every entity built from it has the same suffix
+    fn name(&self, blueprint: &B, source: Entity) -> Option<String> {
+        if !self.entity_index {
+            return blueprint.blueprint_name();
+        }
+        let name = blueprint
+            .blueprint_name()
+            .unwrap_or_else(|| get_short_name(std::any::type_name::<B>()));
+        Some(format!("{} #{}", name, source.index()))
+    }
+}
+
+/// Keeps a `Name` on each `Blueprint<B>` target in sync with `NamedBlueprint::blueprint_name`.
+pub struct BlueprintNamePlugin<B> {
+    target: NameTarget,
+    entity_index: bool,
+    marker: PhantomData<B>,
+}
+
+impl<B> Default for BlueprintNamePlugin<B> {
+    fn default() -> Self {
+        BlueprintNamePlugin {
+            target: NameTarget::default(),
+            entity_index: false,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<B> BlueprintNamePlugin<B> {
+    pub fn with_target(mut self, target: NameTarget) -> Self {
+        self.target = target;
+        self
+    }
+
+    /// Appends the index of the blueprint entity to the names (e.g. `"Rect #42"`), so that the
+    /// entities built from identical blueprints can be told apart in the editor. Blueprints
+    /// without a `blueprint_name` are then named after their type.
+    pub fn with_entity_index(mut self) -> Self {
+        self.entity_index = true;
+        self
+    }
+}
+
+impl<B> BlueprintNamePlugin<B>
+where
+    B: NamedBlueprint + Default + Send + Sync + 'static,
+{
+    fn update_names(
+        mut commands: Commands,
+        blueprint_query: NamedQuery<B>,
+        spawned_query: Query<(Entity, Ref<BlueprintSource>)>,
+        settings: Res<NameSettings<B>>,
+    ) {
+        match settings.target {
+            NameTarget::Blueprint => {
+                for (entity, blueprint, _) in blueprint_query.iter() {
+                    if !blueprint.is_changed() {
+                        continue;
+                    }
+                    if let Some(name) = settings.name(&blueprint.0, entity) {
+                        commands.entity(entity).insert(Name::new(name));
+                    }
+                }
+            }
+            NameTarget::Spawned => {
+                // spawned entities are named when they are (re)built, and whenever the blueprint
+                // changes without rebuilding them
+                for (entity, source) in spawned_query.iter() {
+                    if !source.is_added() {
+                        continue;
+                    }
+                    let Ok((_, blueprint, _)) = blueprint_query.get(source.0) else {
+                        continue;
+                    };
+                    if let Some(name) = settings.name(&blueprint.0, source.0) {
+                        commands.entity(entity).insert(Name::new(name));
+                    }
+                }
+                for (entity, blueprint, children) in blueprint_query.iter() {
+                    if !blueprint.is_changed() {
+                        continue;
+                    }
+                    let Some(name) = settings.name(&blueprint.0, entity) else {
+                        continue;
+                    };
+                    for child in children.into_iter().flatten() {
+                        if spawned_query.contains(*child) {
+                            commands.entity(*child).insert(Name::new(name.clone()));
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<B> Plugin for BlueprintNamePlugin<B>
+where
+    B: NamedBlueprint + Default + Send + Sync + 'static,
+{
+    fn build(&self, app: &mut App) {
+        BlueprintsPlugin::add_once(app);
+        app.insert_resource(NameSettings::<B> {
+            target: self.target,
+            entity_index: self.entity_index,
+            marker: PhantomData,
+        })
+        // spawned entities only exist once the flush has applied the sync commands
+        .add_systems(
+            Update,
+            Self::update_names
+                .in_set(BlueprintsSet)
+                .after(BlueprintSet::Flush),
+        );
+    }
+}
+
+/// Finds an entity by its `Name`, e.g. for a blueprint that refers to another entity by a
+/// logical name, such as the switch of a door. With several matches, any one of them is returned.
+///
+/// For builders with world access, such as those of a `BlueprintBuilderRegistry`. Prefabs can
+/// take `NamedEntities` as a param instead.
+pub fn resolve_by_name(world: &mut World, name: &str) -> Option<Entity> {
+    world
+        .query::<(Entity, &Name)>()
+        .iter(world)
+        .find(|(_, entity_name)| entity_name.as_str() == name)
+        .map(|(entity, _)| entity)
+}
+
+/// A system param finding entities by their `Name`, like `resolve_by_name`, for the params of a
+/// prefab.
+///
+/// The name is resolved whenever the prefab is built, so a rebuild picks up a renamed or
+/// respawned entity. If the entity may not exist yet, fail the build with `TryFromBlueprint` and
+/// add the plugin with `retry_failed_builds`, so it is resolved again in the next frame.
+#[derive(SystemParam)]
+pub struct NamedEntities<'w, 's> {
+    query: Query<'w, 's, (Entity, &'static Name)>,
+}
+
+impl NamedEntities<'_, '_> {
+    pub fn get(&self, name: &str) -> Option<Entity> {
+        self.query
+            .iter()
+            .find(|(_, entity_name)| entity_name.as_str() == name)
+            .map(|(entity, _)| entity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{ecs::system::StaticSystemParam, prelude::*};
+
+    use super::*;
+    use crate::{
+        AsChild, BlueprintPlugin, BlueprintsPlugin, FromBlueprint, OnPermanentFailure,
+        TryFromBlueprint,
+    };
+
+    #[derive(Default, Reflect)]
+    struct Rect {
+        label: String,
+    }
+
+    impl NamedBlueprint for Rect {
+        fn blueprint_name(&self) -> Option<String> {
+            (!self.label.is_empty()).then(|| self.label.clone())
+        }
+    }
+
+    #[derive(Component)]
+    struct RectMesh;
+
+    impl FromBlueprint<Rect> for RectMesh {
+        type Params<'w, 's> = ();
+        fn from_blueprint(_: &Rect, _: &mut StaticSystemParam<Self::Params<'_, '_>>) -> Self {
+            RectMesh
+        }
+    }
+
+    fn name(app: &App, entity: Entity) -> Option<&str> {
+        app.world().get::<Name>(entity).map(Name::as_str)
+    }
+
+    fn set_label(app: &mut App, entity: Entity, label: &str) {
+        app.world_mut()
+            .get_mut::<Blueprint<Rect>>(entity)
+            .unwrap()
+            .0
+            .label = label.to_string();
+        app.update();
+    }
+
+    #[test]
+    fn label_names_blueprint_entity() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Rect, RectMesh, AsChild>::default())
+            .add_plugins(BlueprintNamePlugin::<Rect>::default());
+        let entity = app
+            .world_mut()
+            .spawn(Blueprint::bundle(Rect::default()))
+            .id();
+        app.update();
+        // without a label, the name from the bundle is kept
+        assert_eq!(name(&app, entity), Some("Rect"));
+
+        set_label(&mut app, entity, "floor");
+        assert_eq!(name(&app, entity), Some("floor"));
+        let child = app.world().get::<Children>(entity).unwrap()[0];
+        assert_eq!(name(&app, child), None);
+    }
+
+    #[test]
+    fn label_names_spawned_entities() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Rect, RectMesh, AsChild>::default())
+            .add_plugins(BlueprintNamePlugin::<Rect>::default().with_target(NameTarget::Spawned));
+        let entity = app
+            .world_mut()
+            .spawn(Blueprint::bundle(Rect {
+                label: "wall".to_string(),
+            }))
+            .id();
+        app.update();
+        let child = app.world().get::<Children>(entity).unwrap()[0];
+        assert_eq!(name(&app, child), Some("wall"));
+        assert_eq!(name(&app, entity), Some("Rect"));
+
+        set_label(&mut app, entity, "door");
+        let child = app.world().get::<Children>(entity).unwrap()[0];
+        assert_eq!(name(&app, child), Some("door"));
+    }
+
+    #[test]
+    fn entity_index_names() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Rect, RectMesh, AsChild>::default())
+            .add_plugins(BlueprintNamePlugin::<Rect>::default().with_entity_index());
+        let rects = (0..3)
+            .map(|_| app.world_mut().spawn(Blueprint::new(Rect::default())).id())
+            .collect::<Vec<_>>();
+        app.update();
+        for rect in &rects {
+            let expected = format!("Rect #{}", rect.index());
+            assert_eq!(name(&app, *rect), Some(expected.as_str()));
+        }
+
+        // the suffix is kept by rebuilds
+        set_label(&mut app, rects[0], "floor");
+        let expected = format!("floor #{}", rects[0].index());
+        assert_eq!(name(&app, rects[0]), Some(expected.as_str()));
+    }
+
+    #[derive(Default, Reflect)]
+    struct Door {
+        switch: String,
+    }
+
+    #[derive(Component, Debug, PartialEq)]
+    struct LinkedTo(Entity);
+
+    impl TryFromBlueprint<Door> for LinkedTo {
+        type Params<'w, 's> = NamedEntities<'w, 's>;
+        type Error = String;
+
+        fn try_from_blueprint(
+            blueprint: &Door,
+            entities: &mut StaticSystemParam<Self::Params<'_, '_>>,
+        ) -> Result<Self, Self::Error> {
+            entities
+                .get(&blueprint.switch)
+                .map(LinkedTo)
+                .ok_or_else(|| format!("no switch named {}", blueprint.switch))
+        }
+    }
+
+    #[test]
+    fn blueprints_resolve_entities_by_name() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(
+                BlueprintPlugin::<Door, LinkedTo>::default()
+                    .retry_failed_builds(10, OnPermanentFailure::Keep),
+            );
+        let door = app
+            .world_mut()
+            .spawn(Blueprint::new(Door {
+                switch: "Lever".to_string(),
+            }))
+            .id();
+        app.update();
+        assert!(app.world().get::<LinkedTo>(door).is_none());
+
+        // resolved once the switch exists
+        let lever = app.world_mut().spawn(Name::new("Lever")).id();
+        app.update();
+        assert_eq!(app.world().get::<LinkedTo>(door), Some(&LinkedTo(lever)));
+        assert_eq!(resolve_by_name(app.world_mut(), "Lever"), Some(lever));
+
+        // and again when the blueprint changes
+        let button = app.world_mut().spawn(Name::new("Button")).id();
+        app.world_mut()
+            .get_mut::<Blueprint<Door>>(door)
+            .unwrap()
+            .0
+            .switch = "Button".to_string();
+        app.update();
+        assert_eq!(app.world().get::<LinkedTo>(door), Some(&LinkedTo(button)));
+    }
+}
@@ -0,0 +1,145 @@
+use bevy::{
+    ecs::{
+        component::{ComponentHooks, StorageType},
+        world::DeferredWorld,
+    },
+    prelude::*,
+};
+
+use crate::BlueprintSource;
+
+type SpawnChild = Box<dyn FnOnce(&mut World, Entity) -> Entity + Send + Sync>;
+
+/// The children of a `BlueprintNode`, which are spawned under the node's entity as soon as it is
+/// inserted.
+pub struct NodeChildren(Vec<SpawnChild>);
+
+impl Component for NodeChildren {
+    const STORAGE_TYPE: StorageType = StorageType::SparseSet;
+
+    fn register_component_hooks(hooks: &mut ComponentHooks) {
+        hooks.on_add(|mut world: DeferredWorld, entity, _| {
+            world
+                .commands()
+                .add(move |world: &mut World| spawn_node_children(world, entity));
+        });
+    }
+}
+
+// children are linked to the same blueprint as their parent node
+fn spawn_node_children(world: &mut World, entity: Entity) {
+    let Some(mut entity_mut) = world.get_entity_mut(entity) else {
+        return;
+    };
+    let Some(NodeChildren(children)) = entity_mut.take::<NodeChildren>() else {
+        return;
+    };
+    let source = entity_mut.get::<BlueprintSource>().copied();
+    for spawn_child in children {
+        let child = spawn_child(world, entity);
+        if let Some(source) = source {
+            world.entity_mut(child).insert(source);
+        }
+    }
+}
+
+/// A prefab that spawns a whole hierarchy: `bundle` on the target, and each child node as a child
+/// entity (with its own children, and so on).
+///
+/// The tree is materialized in a single build. This is meant for `AsChild` targets, whose cleanup
+/// despawns the entire generated tree. With `AsSelf`, the child entities are not removed.
+#[derive(Bundle)]
+pub struct BlueprintNode<P: Bundle> {
+    bundle: P,
+    children: NodeChildren,
+}
+
+impl<P: Bundle> BlueprintNode<P> {
+    pub fn new(bundle: P) -> Self {
+        BlueprintNode {
+            bundle,
+            children: NodeChildren(Vec::new()),
+        }
+    }
+
+    pub fn with_child<C: Bundle>(mut self, child: BlueprintNode<C>) -> Self {
+        self.children
+            .0
+            .push(Box::new(move |world: &mut World, parent: Entity| {
+                world.spawn(child).set_parent(parent).id()
+            }));
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{ecs::system::StaticSystemParam, prelude::*};
+
+    use super::*;
+    use crate::{AsChild, Blueprint, BlueprintPlugin, BlueprintsPlugin, FromBlueprint};
+
+    #[derive(Default, Reflect)]
+    struct Tree {
+        height: u32,
+    }
+
+    #[derive(Component)]
+    struct Trunk(u32);
+
+    #[derive(Component)]
+    struct Branch;
+
+    #[derive(Component)]
+    struct Leaf;
+
+    impl FromBlueprint<Tree> for BlueprintNode<Trunk> {
+        type Params<'w, 's> = ();
+        fn from_blueprint(
+            blueprint: &Tree,
+            _: &mut StaticSystemParam<Self::Params<'_, '_>>,
+        ) -> Self {
+            BlueprintNode::new(Trunk(blueprint.height))
+                .with_child(BlueprintNode::new(Branch).with_child(BlueprintNode::new(Leaf)))
+                .with_child(BlueprintNode::new(Branch))
+        }
+    }
+
+    #[test]
+    fn node_spawns_hierarchy() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Tree, BlueprintNode<Trunk>, AsChild>::default());
+        let entity = app
+            .world_mut()
+            .spawn(Blueprint::new(Tree { height: 3 }))
+            .id();
+        app.update();
+
+        let world = app.world();
+        let trunk = world.get::<Children>(entity).unwrap()[0];
+        assert_eq!(world.get::<Trunk>(trunk).unwrap().0, 3);
+        let branches = world.get::<Children>(trunk).unwrap();
+        assert_eq!(branches.len(), 2);
+        assert!(branches
+            .iter()
+            .all(|branch| world.entity(*branch).contains::<Branch>()));
+        let leaf = world.get::<Children>(branches[0]).unwrap()[0];
+        assert!(world.entity(leaf).contains::<Leaf>());
+        assert_eq!(
+            world.get::<BlueprintSource>(leaf),
+            Some(&BlueprintSource(entity))
+        );
+
+        app.world_mut()
+            .entity_mut(entity)
+            .remove::<Blueprint<Tree>>();
+        app.update();
+        let remaining = app
+            .world_mut()
+            .query_filtered::<(), Or<(With<Trunk>, With<Branch>, With<Leaf>)>>()
+            .iter(app.world())
+            .count();
+        assert_eq!(remaining, 0);
+    }
+}
@@ -0,0 +1,114 @@
+use std::time::Duration;
+
+use bevy::{ecs::entity::Entities, prelude::*, time::common_conditions::on_timer};
+
+use crate::{blueprints_paused, BlueprintSource};
+
+/// A safety net for cleanup bugs: periodically looks for entities spawned by blueprints (i.e.
+/// with a `BlueprintSource`) whose blueprint entity no longer exists, and warns about them.
+///
+/// Cleanup should never leave such entities behind, so any warning points at a cleanup
+/// regression. With `despawn`, the orphans are also despawned. Entities that are meant to outlive
+/// their blueprint entity should have their `BlueprintSource` removed.
+#[derive(Clone, Copy, Debug)]
+pub struct BlueprintOrphanPlugin {
+    /// How often to scan.
+    pub interval: Duration,
+    /// Whether to despawn the orphans found, rather than only warning about them.
+    pub despawn: bool,
+}
+
+impl Default for BlueprintOrphanPlugin {
+    fn default() -> Self {
+        BlueprintOrphanPlugin {
+            interval: Duration::from_secs(1),
+            despawn: false,
+        }
+    }
+}
+
+#[derive(Resource)]
+struct DespawnOrphans(bool);
+
+fn find_orphaned_children(
+    mut commands: Commands,
+    despawn: Res<DespawnOrphans>,
+    children: Query<(Entity, &BlueprintSource)>,
+    entities: &Entities,
+) {
+    for (entity, source) in children.iter() {
+        if entities.contains(source.0) {
+            continue;
+        }
+        warn!(
+            "entity {} outlived the blueprint entity {} that spawned it",
+            entity, source.0
+        );
+        if despawn.0 {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+impl Plugin for BlueprintOrphanPlugin {
+    fn build(&self, app: &mut App) {
+        // after the despawns of this frame's cleanup have been applied, and not while cleanup is
+        // paused
+        app.insert_resource(DespawnOrphans(self.despawn))
+            .add_systems(
+                Last,
+                find_orphaned_children
+                    .run_if(on_timer(self.interval))
+                    .run_if(not(blueprints_paused)),
+            );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{ecs::system::StaticSystemParam, prelude::*};
+
+    use super::*;
+    use crate::{AsChild, Blueprint, BlueprintPlugin, BlueprintsPlugin, FromBlueprint};
+
+    #[derive(Default, Reflect)]
+    struct Door;
+
+    #[derive(Component)]
+    struct DoorMesh;
+
+    impl FromBlueprint<Door> for DoorMesh {
+        type Params<'w, 's> = ();
+        fn from_blueprint(_: &Door, _: &mut StaticSystemParam<Self::Params<'_, '_>>) -> Self {
+            DoorMesh
+        }
+    }
+
+    // builds a door, then despawns it in a way that cleanup misses its child
+    fn orphan(despawn: bool) -> (App, Entity) {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Door, DoorMesh, AsChild>::default())
+            .add_plugins(BlueprintOrphanPlugin {
+                interval: Duration::ZERO,
+                despawn,
+            });
+        let entity = app.world_mut().spawn(Blueprint::new(Door)).id();
+        app.update();
+        let child = app.world().get::<Children>(entity).unwrap()[0];
+        app.world_mut().entity_mut(child).remove_parent();
+        app.world_mut().despawn(entity);
+        app.update();
+        (app, child)
+    }
+
+    #[test]
+    fn orphans_are_found() {
+        // only warned about by default
+        let (app, child) = orphan(false);
+        assert!(app.world().get::<DoorMesh>(child).is_some());
+
+        let (app, child) = orphan(true);
+        assert!(app.world().get_entity(child).is_none());
+    }
+}
@@ -0,0 +1,195 @@
+use bevy::{
+    ecs::system::{EntityCommands, StaticSystemParam},
+    prelude::*,
+    reflect::ReflectRef,
+};
+
+use crate::{BlueprintPlugin, BlueprintTarget, TryFromBlueprint};
+
+/// A prefab that can update only the components affected by a changed blueprint field, instead
+/// of being rebuilt, e.g. replacing just the material when the blueprint's `color` is edited in an
+/// inspector. Enable it with `BlueprintPlugin::partial_updates`.
+pub trait PartialFromBlueprint<T>: TryFromBlueprint<T> {
+    /// Updates the components built from the top-level blueprint field `field` on `target` (the
+    /// entity holding the prefab, i.e. the child for `AsChild`).
+    ///
+    /// Returns `false` if the field cannot be updated on its own (e.g. because it affects several
+    /// components), in which case the whole prefab is rebuilt.
+    fn update_field(
+        field: &str,
+        blueprint: &T,
+        target: &mut EntityCommands,
+        params: &mut StaticSystemParam<Self::Params<'_, '_>>,
+    ) -> bool;
+}
+
+pub(crate) type PartialUpdate<B, P> = fn(
+    &str,
+    &B,
+    &mut EntityCommands,
+    &mut StaticSystemParam<<P as TryFromBlueprint<B>>::Params<'_, '_>>,
+) -> bool;
+
+impl<B, P, T> BlueprintPlugin<B, P, T>
+where
+    P: Bundle + PartialFromBlueprint<B>,
+    T: BlueprintTarget,
+{
+    /// Updates prefabs through `PartialFromBlueprint::update_field` when only some top-level
+    /// fields of a struct blueprint changed, and rebuilds them when any of those fields cannot be
+    /// updated on its own.
+    ///
+    /// Changed fields are found with `Reflect::reflect_partial_eq`, so this keeps a reflected copy
+    /// of every built blueprint. Blueprints that are not structs are always rebuilt.
+    pub fn partial_updates(mut self) -> Self {
+        self.partial_update = Some(P::update_field);
+        self
+    }
+}
+
+// the top-level fields of a struct that differ from the value it was built from, or `None` if
+// reflection cannot compare them
+pub(crate) fn changed_fields<'a>(
+    built: &dyn Reflect,
+    blueprint: &'a dyn Reflect,
+) -> Option<Vec<&'a str>> {
+    let (ReflectRef::Struct(built), ReflectRef::Struct(blueprint)) =
+        (built.reflect_ref(), blueprint.reflect_ref())
+    else {
+        return None;
+    };
+    let mut changed = Vec::new();
+    for (index, value) in blueprint.iter_fields().enumerate() {
+        let name = blueprint.name_at(index)?;
+        if !built.field(name)?.reflect_partial_eq(value)? {
+            changed.push(name);
+        }
+    }
+    Some(changed)
+}
+
+// updates the fields of `blueprint` that changed since it was `built`, returning `false` if the
+// prefab must be rebuilt instead
+pub(crate) fn update_changed_fields<B: Reflect, P: TryFromBlueprint<B>>(
+    update_field: PartialUpdate<B, P>,
+    built: &dyn Reflect,
+    blueprint: &B,
+    target: &mut EntityCommands,
+    params: &mut StaticSystemParam<P::Params<'_, '_>>,
+) -> bool {
+    match changed_fields(built, blueprint.as_reflect()) {
+        Some(fields) if !fields.is_empty() => fields
+            .into_iter()
+            .all(|field| update_field(field, blueprint, target, params)),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use super::*;
+    use crate::{AsChild, Blueprint, BlueprintsPlugin, FromBlueprint};
+
+    #[derive(Default, Reflect)]
+    struct Rect {
+        size: Vec2,
+        color: Color,
+    }
+
+    #[derive(Component, Debug, PartialEq)]
+    struct RectMesh(Vec2);
+
+    #[derive(Component, Debug, PartialEq)]
+    struct RectMaterial(Color);
+
+    #[derive(Bundle)]
+    struct RectBundle {
+        mesh: RectMesh,
+        material: RectMaterial,
+    }
+
+    impl FromBlueprint<Rect> for RectBundle {
+        type Params<'w, 's> = ();
+        fn from_blueprint(
+            blueprint: &Rect,
+            _: &mut StaticSystemParam<Self::Params<'_, '_>>,
+        ) -> Self {
+            RectBundle {
+                mesh: RectMesh(blueprint.size),
+                material: RectMaterial(blueprint.color),
+            }
+        }
+    }
+
+    impl PartialFromBlueprint<Rect> for RectBundle {
+        fn update_field(
+            field: &str,
+            blueprint: &Rect,
+            target: &mut EntityCommands,
+            _: &mut StaticSystemParam<Self::Params<'_, '_>>,
+        ) -> bool {
+            match field {
+                "color" => {
+                    target.insert(RectMaterial(blueprint.color));
+                    true
+                }
+                _ => false,
+            }
+        }
+    }
+
+    fn child(app: &App, entity: Entity) -> Entity {
+        app.world().get::<Children>(entity).unwrap()[0]
+    }
+
+    #[test]
+    fn changed_field_updates_only_its_component() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Rect, RectBundle, AsChild>::default().partial_updates());
+        let entity = app.world_mut().spawn(Blueprint::<Rect>::default()).id();
+        app.update();
+        let built = child(&app, entity);
+        let mesh_tick = app
+            .world()
+            .entity(built)
+            .get_ref::<RectMesh>()
+            .unwrap()
+            .last_changed();
+
+        app.world_mut()
+            .get_mut::<Blueprint<Rect>>(entity)
+            .unwrap()
+            .0
+            .color = Color::WHITE;
+        app.update();
+        // the same child, with only its material replaced
+        assert_eq!(child(&app, entity), built);
+        assert_eq!(
+            app.world().get::<RectMaterial>(built),
+            Some(&RectMaterial(Color::WHITE))
+        );
+        let mesh = app.world().entity(built).get_ref::<RectMesh>().unwrap();
+        assert_eq!(mesh.last_changed(), mesh_tick);
+
+        // the size cannot be updated on its own
+        app.world_mut()
+            .get_mut::<Blueprint<Rect>>(entity)
+            .unwrap()
+            .0
+            .size = Vec2::ONE;
+        app.update();
+        let rebuilt = child(&app, entity);
+        assert_ne!(rebuilt, built);
+        assert_eq!(
+            app.world().get::<RectMesh>(rebuilt),
+            Some(&RectMesh(Vec2::ONE))
+        );
+        assert_eq!(
+            app.world().get::<RectMaterial>(rebuilt),
+            Some(&RectMaterial(Color::WHITE))
+        );
+    }
+}
@@ -0,0 +1,150 @@
+use std::marker::PhantomData;
+
+use bevy::{
+    ecs::{entity::EntityHashSet, system::SystemParam},
+    prelude::*,
+};
+
+use crate::BlueprintsSet;
+
+/// While `true`, no blueprint is built, rebuilt or cleaned up, e.g. during a bulk world edit.
+///
+/// Everything that happened in the meantime (including removed blueprints) is processed in one
+/// pass once it is `false` again. See `pause_blueprints` and `resume_blueprints`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Resource)]
+pub struct BlueprintsPaused(pub bool);
+
+pub fn pause_blueprints(world: &mut World) {
+    world.insert_resource(BlueprintsPaused(true));
+}
+
+pub fn resume_blueprints(world: &mut World) {
+    world.insert_resource(BlueprintsPaused(false));
+}
+
+pub(crate) fn blueprints_paused(paused: Option<Res<BlueprintsPaused>>) -> bool {
+    paused.is_some_and(|paused| paused.0)
+}
+
+// the removals of `C` seen while paused, kept for the system of `M` until it runs again, since
+// `RemovedComponents` only keeps them for a couple of frames
+#[derive(Resource)]
+struct PausedRemovals<C, M> {
+    entities: Vec<Entity>,
+    marker: PhantomData<(C, M)>,
+}
+
+impl<C, M> Default for PausedRemovals<C, M> {
+    fn default() -> Self {
+        Self {
+            entities: Vec::new(),
+            marker: PhantomData,
+        }
+    }
+}
+
+fn buffer_paused_removals<C: Component, M: Send + Sync + 'static>(
+    mut removed: RemovedComponents<C>,
+    mut buffer: ResMut<PausedRemovals<C, M>>,
+) {
+    buffer.entities.extend(removed.read());
+}
+
+/// `RemovedComponents<C>` for a system in `BlueprintsSet` (identified by `M`), which also returns
+/// the removals that happened while blueprints were paused.
+#[derive(SystemParam)]
+pub(crate) struct Removals<'w, 's, C: Component, M: Send + Sync + 'static> {
+    removed: RemovedComponents<'w, 's, C>,
+    paused: ResMut<'w, PausedRemovals<C, M>>,
+}
+
+impl<'w, 's, C: Component, M: Send + Sync + 'static> Removals<'w, 's, C, M> {
+    // the buffered removals may also still be in `removed`
+    pub(crate) fn read(&mut self) -> Vec<Entity> {
+        let mut seen = EntityHashSet::default();
+        self.paused
+            .entities
+            .drain(..)
+            .chain(self.removed.read())
+            .filter(|entity| seen.insert(*entity))
+            .collect()
+    }
+
+    /// Sets up the buffering of removals for the system using `Removals<C, M>`.
+    pub(crate) fn buffer_while_paused(app: &mut App) {
+        app.init_resource::<PausedRemovals<C, M>>().add_systems(
+            Update,
+            buffer_paused_removals::<C, M>
+                .run_if(blueprints_paused)
+                .before(BlueprintsSet),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{ecs::system::StaticSystemParam, prelude::*};
+
+    use super::*;
+    use crate::{Blueprint, BlueprintPlugin, BlueprintsPlugin, FromBlueprint};
+
+    #[derive(Default, Reflect)]
+    struct Crate {
+        weight: u32,
+    }
+
+    #[derive(Component)]
+    struct Weight(u32);
+
+    impl FromBlueprint<Crate> for Weight {
+        type Params<'w, 's> = ResMut<'w, BuildCount>;
+        fn from_blueprint(
+            blueprint: &Crate,
+            count: &mut StaticSystemParam<Self::Params<'_, '_>>,
+        ) -> Self {
+            count.0 += 1;
+            Weight(blueprint.weight)
+        }
+    }
+
+    #[derive(Default, Resource)]
+    struct BuildCount(usize);
+
+    #[test]
+    fn paused_changes_apply_on_resume() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Crate, Weight>::default())
+            .init_resource::<BuildCount>();
+        let crates = (0..100)
+            .map(|_| app.world_mut().spawn(Blueprint::<Crate>::default()).id())
+            .collect::<Vec<_>>();
+        app.update();
+        assert_eq!(app.world().resource::<BuildCount>().0, 100);
+
+        pause_blueprints(app.world_mut());
+        for (weight, entity) in crates.iter().enumerate() {
+            app.world_mut()
+                .get_mut::<Blueprint<Crate>>(*entity)
+                .unwrap()
+                .0
+                .weight = weight as u32;
+            app.update();
+        }
+        app.world_mut()
+            .entity_mut(crates[0])
+            .remove::<Blueprint<Crate>>();
+        // long enough for the removal to leave `RemovedComponents`
+        for _ in 0..3 {
+            app.update();
+        }
+        assert_eq!(app.world().resource::<BuildCount>().0, 100);
+        assert_eq!(app.world().get::<Weight>(crates[1]).unwrap().0, 0);
+
+        resume_blueprints(app.world_mut());
+        app.update();
+        assert_eq!(app.world().resource::<BuildCount>().0, 199);
+        assert!(app.world().get::<Weight>(crates[0]).is_none());
+        assert_eq!(app.world().get::<Weight>(crates[99]).unwrap().0, 99);
+    }
+}
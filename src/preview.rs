@@ -0,0 +1,157 @@
+use bevy::prelude::*;
+
+use crate::{BlueprintSet, BlueprintsPlugin, BlueprintsSet};
+
+/// Marks a throwaway blueprint entity that previews what its blueprint builds, e.g. the ghost of a
+/// placement tool following the cursor: spawn it with `Blueprint::bundle(data)`, move it around,
+/// and `despawn_recursive` it once the preview ends (spawning the real blueprint to commit it).
+///
+/// Previews are built by the usual `BlueprintPlugin`s, so their prefabs' params run for real:
+/// a prefab that creates assets (e.g. with `MeshMaterialParams`) creates them for every preview
+/// build too. With a `BlueprintPreviewPlugin<M>`, the materials of the preview and its descendants
+/// are made translucent.
+#[derive(Clone, Copy, Debug, Component, Reflect)]
+#[reflect(Component)]
+pub struct BlueprintPreview {
+    pub opacity: f32,
+}
+
+impl Default for BlueprintPreview {
+    fn default() -> Self {
+        BlueprintPreview { opacity: 0.5 }
+    }
+}
+
+// the translucent copy of the material that replaced the built one
+#[derive(Component)]
+struct PreviewMaterial<M: Asset>(AssetId<M>);
+
+/// Makes the materials `M` built for `BlueprintPreview` entities (and their descendants)
+/// translucent, by replacing them with copies faded by `fade(material, opacity)`, so that the
+/// materials shared with other entities are left as they are.
+///
+/// A rebuilt preview is faded again, but changing the `opacity` of a built preview does not fade
+/// it until it is rebuilt.
+pub struct BlueprintPreviewPlugin<M> {
+    fade: fn(&mut M, f32),
+}
+
+impl<M> BlueprintPreviewPlugin<M> {
+    /// `fade` sets the material's opacity, e.g. for a `StandardMaterial`, by scaling the alpha of
+    /// its `base_color` and switching its `alpha_mode` to `AlphaMode::Blend`.
+    pub fn new(fade: fn(&mut M, f32)) -> Self {
+        BlueprintPreviewPlugin { fade }
+    }
+}
+
+#[derive(Resource)]
+struct PreviewFade<M>(fn(&mut M, f32));
+
+type PreviewMaterials<'w, 's, M> =
+    Query<'w, 's, (&'static mut Handle<M>, Option<&'static PreviewMaterial<M>>)>;
+
+fn fade_previews<M: Asset + Clone>(
+    mut commands: Commands,
+    previews: Query<(Entity, &BlueprintPreview)>,
+    children: Query<&Children>,
+    mut handles: PreviewMaterials<M>,
+    mut materials: ResMut<Assets<M>>,
+    fade: Res<PreviewFade<M>>,
+) {
+    for (preview, BlueprintPreview { opacity }) in previews.iter() {
+        for entity in std::iter::once(preview).chain(children.iter_descendants(preview)) {
+            let Ok((mut handle, faded)) = handles.get_mut(entity) else {
+                continue;
+            };
+            if faded.is_some_and(|faded| faded.0 == handle.id()) {
+                continue;
+            }
+            let Some(mut material) = materials.get(handle.id()).cloned() else {
+                continue;
+            };
+            (fade.0)(&mut material, *opacity);
+            *handle = materials.add(material);
+            commands
+                .entity(entity)
+                .insert(PreviewMaterial::<M>(handle.id()));
+        }
+    }
+}
+
+impl<M: Asset + Clone> Plugin for BlueprintPreviewPlugin<M> {
+    fn build(&self, app: &mut App) {
+        BlueprintsPlugin::add_once(app);
+        app.register_type::<BlueprintPreview>()
+            .insert_resource(PreviewFade(self.fade))
+            .add_systems(
+                Update,
+                fade_previews::<M>
+                    .after(BlueprintSet::Flush)
+                    .in_set(BlueprintsSet),
+            );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{ecs::system::StaticSystemParam, prelude::*};
+
+    use super::*;
+    use crate::{Blueprint, BlueprintPlugin, FromBlueprint};
+
+    #[derive(Asset, Clone, TypePath)]
+    struct Paint(Color);
+
+    #[derive(Resource)]
+    struct SharedPaint(Handle<Paint>);
+
+    #[derive(Default, Reflect)]
+    struct Rect;
+
+    impl FromBlueprint<Rect> for Handle<Paint> {
+        type Params<'w, 's> = Res<'w, SharedPaint>;
+        fn from_blueprint(_: &Rect, paint: &mut StaticSystemParam<Self::Params<'_, '_>>) -> Self {
+            paint.0.clone()
+        }
+    }
+
+    fn alpha(app: &App, entity: Entity) -> f32 {
+        let handle = app.world().get::<Handle<Paint>>(entity).unwrap();
+        let paint = app.world().resource::<Assets<Paint>>().get(handle).unwrap();
+        paint.0.alpha()
+    }
+
+    #[test]
+    fn previews_are_translucent() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default(), BlueprintsPlugin))
+            .init_asset::<Paint>()
+            .add_plugins(BlueprintPlugin::<Rect, Handle<Paint>>::default())
+            .add_plugins(BlueprintPreviewPlugin::<Paint>::new(|paint, opacity| {
+                paint.0.set_alpha(paint.0.alpha() * opacity);
+            }));
+        let paint = app
+            .world_mut()
+            .resource_mut::<Assets<Paint>>()
+            .add(Paint(Color::WHITE));
+        app.insert_resource(SharedPaint(paint));
+        let placed = app.world_mut().spawn(Blueprint::<Rect>::default()).id();
+        let ghost = app
+            .world_mut()
+            .spawn((Blueprint::<Rect>::default(), BlueprintPreview::default()))
+            .id();
+        app.update();
+        assert_eq!(alpha(&app, ghost), 0.5);
+        assert_eq!(alpha(&app, placed), 1.);
+
+        // faded once, and again after a rebuild
+        app.update();
+        assert_eq!(alpha(&app, ghost), 0.5);
+        app.world_mut()
+            .get_mut::<Blueprint<Rect>>(ghost)
+            .unwrap()
+            .set_changed();
+        app.update();
+        assert_eq!(alpha(&app, ghost), 0.5);
+    }
+}
@@ -0,0 +1,156 @@
+use bevy::{
+    asset::ron,
+    prelude::*,
+    reflect::{serde::TypedReflectDeserializer, DynamicTupleStruct, TypeRegistry},
+};
+use serde::{de::DeserializeSeed, Deserialize, Serialize};
+
+use crate::{blueprint_component_type_id, BlueprintsPlugin, BlueprintsSet};
+
+/// Blueprint data received as a RON string before its type is known, e.g. from a content server.
+/// `type_path` is the type path of the blueprint data, e.g. `my_game::Rect`.
+///
+/// With `RawBlueprintPlugin`, it is replaced by the typed `Blueprint<B>` once a `BlueprintPlugin`
+/// of that type is registered.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Component, Reflect, Serialize, Deserialize)]
+#[reflect(Component)]
+pub struct RawBlueprint {
+    pub type_path: String,
+    pub ron: String,
+}
+
+impl RawBlueprint {
+    pub fn new(type_path: impl Into<String>, ron: impl Into<String>) -> Self {
+        RawBlueprint {
+            type_path: type_path.into(),
+            ron: ron.into(),
+        }
+    }
+}
+
+/// Deserializes every `RawBlueprint` whose type has a `BlueprintPlugin` into the typed
+/// `Blueprint<B>`, which is built in the same frame, and removes the raw component.
+///
+/// Raw blueprints of types without a plugin are kept, and tried again every frame. Those whose
+/// RON does not parse are removed, with an error.
+pub struct RawBlueprintPlugin;
+
+impl Plugin for RawBlueprintPlugin {
+    fn build(&self, app: &mut App) {
+        BlueprintsPlugin::add_once(app);
+        app.register_type::<RawBlueprint>()
+            .add_systems(Update, type_raw_blueprints.before(BlueprintsSet));
+    }
+}
+
+fn type_raw_blueprints(world: &mut World) {
+    let mut query = world.query::<(Entity, Ref<RawBlueprint>)>();
+    let raw_blueprints = query
+        .iter(world)
+        .map(|(entity, raw)| (entity, raw.clone(), raw.is_added()))
+        .collect::<Vec<_>>();
+    if raw_blueprints.is_empty() {
+        return;
+    }
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+    for (entity, raw, added) in raw_blueprints {
+        let Some(component_type_id) = blueprint_component_type_id(world, &raw.type_path) else {
+            if added {
+                debug!(
+                    "raw blueprint {} of entity {} waits for its BlueprintPlugin",
+                    raw.type_path, entity,
+                );
+            }
+            continue;
+        };
+        let Some(reflect_component) =
+            type_registry.get_type_data::<ReflectComponent>(component_type_id)
+        else {
+            continue;
+        };
+        let mut entity_mut = world.entity_mut(entity);
+        match parse_raw_blueprint(&raw, &type_registry) {
+            Ok(blueprint) => {
+                reflect_component.insert(&mut entity_mut, &blueprint, &type_registry);
+            }
+            Err(error) => {
+                error!(
+                    "could not parse the raw blueprint {} of entity {}: {}",
+                    raw.type_path, entity, error,
+                );
+            }
+        }
+        entity_mut.remove::<RawBlueprint>();
+    }
+}
+
+// the `Blueprint<B>` of the raw data, as a dynamic tuple struct
+pub(crate) fn parse_raw_blueprint(
+    raw: &RawBlueprint,
+    type_registry: &TypeRegistry,
+) -> Result<DynamicTupleStruct, String> {
+    let registration = type_registry
+        .get_with_type_path(&raw.type_path)
+        .ok_or_else(|| format!("{} is not registered", raw.type_path))?;
+    let mut deserializer =
+        ron::Deserializer::from_str(&raw.ron).map_err(|error| error.to_string())?;
+    let data = TypedReflectDeserializer::new(registration, type_registry)
+        .deserialize(&mut deserializer)
+        .map_err(|error| error.to_string())?;
+    let mut blueprint = DynamicTupleStruct::default();
+    blueprint.insert_boxed(data);
+    Ok(blueprint)
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{ecs::system::StaticSystemParam, prelude::*, reflect::TypePath};
+
+    use super::*;
+    use crate::{Blueprint, BlueprintPlugin, FromBlueprint};
+
+    #[derive(Default, Reflect)]
+    struct Rect {
+        size: f32,
+    }
+
+    #[derive(Component)]
+    struct RectSize(f32);
+
+    impl FromBlueprint<Rect> for RectSize {
+        type Params<'w, 's> = ();
+        fn from_blueprint(
+            blueprint: &Rect,
+            _: &mut StaticSystemParam<Self::Params<'_, '_>>,
+        ) -> Self {
+            RectSize(blueprint.size)
+        }
+    }
+
+    #[test]
+    fn raw_blueprints_become_typed() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, RawBlueprintPlugin))
+            .add_plugins(BlueprintPlugin::<Rect, RectSize>::default());
+        let typed = app
+            .world_mut()
+            .spawn(RawBlueprint::new(Rect::type_path(), "(size: 2.0)"))
+            .id();
+        let unknown = app
+            .world_mut()
+            .spawn(RawBlueprint::new("my_mod::Tower", "(height: 3.0)"))
+            .id();
+        app.update();
+        assert_eq!(
+            app.world().get::<Blueprint<Rect>>(typed).unwrap().0.size,
+            2.
+        );
+        assert_eq!(app.world().get::<RectSize>(typed).unwrap().0, 2.);
+        assert!(app.world().get::<RawBlueprint>(typed).is_none());
+
+        // kept, to try again
+        app.update();
+        assert!(app.world().get::<RawBlueprint>(unknown).is_some());
+    }
+}
@@ -0,0 +1,755 @@
+use std::any::TypeId;
+
+use bevy::{
+    ecs::{
+        component::{ComponentId, Components},
+        schedule::InternedScheduleLabel,
+        world::DeferredWorld,
+    },
+    prelude::*,
+    reflect::TypePath,
+    utils::{get_short_name, HashMap, HashSet},
+};
+
+use crate::{strip_blueprints, Blueprint, BlueprintBuilt, BlueprintTarget, TargetKind};
+
+/// A prefab registered for some blueprint type by a `BlueprintPlugin`.
+#[derive(Clone, Debug)]
+pub struct PrefabRegistration {
+    pub prefab_name: &'static str,
+    /// Where the prefab is attached, from the plugin's `BlueprintTarget`.
+    pub target: TargetKind,
+    /// The schedules the plugin added its systems to: `Update`, where the prefab is built, then
+    /// any others, such as the `OnExit` and `OnEnter` schedules of `in_state_blueprints`.
+    pub schedules: Vec<InternedScheduleLabel>,
+    // builds the prefab for every blueprint entity, used by `bake_blueprints`
+    pub(crate) build_all: fn(&mut World),
+    // builds the prefab for the blueprints that changed since it last ran, used by
+    // `build_blueprints_now` (unless the plugin is `only_build_in_update`)
+    pub(crate) build_now: Option<fn(&mut World)>,
+    // cleans up the prefab's targets and marks their blueprints as changed, used by
+    // `reset_all_targets`
+    pub(crate) reset: fn(&mut World),
+    // asks the plugin to build the entity's blueprint, whatever its trigger
+    pub(crate) rebuild: fn(&mut World, Entity),
+    // names the prefab's components that are not registered for reflection
+    pub(crate) unreflected_components: fn(&mut World) -> Vec<String>,
+}
+
+/// Everything registered for a single blueprint type `B`.
+#[derive(Clone, Debug)]
+pub struct BlueprintRegistration {
+    pub type_path: &'static str,
+    pub component_type_id: TypeId,
+    pub prefabs: Vec<PrefabRegistration>,
+    pub(crate) strip: fn(&mut World),
+    // whether the entity has a blueprint that has not been built
+    pub(crate) pending: fn(&World, Entity) -> bool,
+    // every entity with a blueprint that has not been built
+    pub(crate) pending_entities: fn(&World) -> Vec<Entity>,
+    pub(crate) spawn_default: fn(&mut Commands) -> Entity,
+}
+
+impl BlueprintRegistration {
+    /// The type name of the blueprint without its module path, e.g. for a debug menu button.
+    pub fn short_name(&self) -> String {
+        get_short_name(self.type_path)
+    }
+
+    /// Spawns an entity with the default blueprint of this type, as `Blueprint::bundle` (so it is
+    /// named and positioned). Every registered type has a default, since `BlueprintPlugin`
+    /// requires one.
+    pub fn spawn_default(&self, commands: &mut Commands) -> Entity {
+        (self.spawn_default)(commands)
+    }
+}
+
+/// Tracks which blueprint types have a `BlueprintPlugin`, keyed by the blueprint's `TypeId`.
+#[derive(Debug, Default, Resource)]
+pub struct BlueprintRegistry {
+    registrations: HashMap<TypeId, BlueprintRegistration>,
+    ignored: HashSet<TypeId>,
+    unreflected_allowed: HashSet<TypeId>,
+    // names of the data-driven blueprints loaded at runtime, in the order they were loaded
+    dynamic: Vec<String>,
+    // the component ids of the `Blueprint<_>` types added to entities, registered or not, in the
+    // order they were first added
+    spawned: Vec<ComponentId>,
+}
+
+impl BlueprintRegistry {
+    // returns how many prefabs were registered for `B` before this one
+    pub(crate) fn register<B, P, T>(
+        &mut self,
+        build_all: fn(&mut World),
+        reset: fn(&mut World),
+        rebuild: fn(&mut World, Entity),
+        build_now: Option<fn(&mut World)>,
+        schedules: Vec<InternedScheduleLabel>,
+    ) -> usize
+    where
+        B: Default + TypePath + Send + Sync + 'static,
+        P: Bundle,
+        T: BlueprintTarget,
+    {
+        let prefabs = &mut self
+            .registrations
+            .entry(TypeId::of::<B>())
+            .or_insert_with(|| BlueprintRegistration {
+                type_path: B::type_path(),
+                component_type_id: TypeId::of::<Blueprint<B>>(),
+                prefabs: Vec::new(),
+                strip: strip_blueprints::<B>,
+                pending: blueprint_pending::<B>,
+                pending_entities: pending_blueprint_entities::<B>,
+                spawn_default: spawn_default_blueprint::<B>,
+            })
+            .prefabs;
+        prefabs.push(PrefabRegistration {
+            prefab_name: std::any::type_name::<P>(),
+            target: T::KIND,
+            schedules,
+            build_all,
+            build_now,
+            reset,
+            rebuild,
+            unreflected_components: unreflected_components::<P>,
+        });
+        prefabs.len() - 1
+    }
+
+    pub fn get<B: 'static>(&self) -> Option<&BlueprintRegistration> {
+        self.registrations.get(&TypeId::of::<B>())
+    }
+
+    /// Whether `B` has a `BlueprintPlugin` (for any prefab). Blueprints built by other plugins,
+    /// such as `ComponentBlueprintPlugin`, are not registered.
+    pub fn contains<B: 'static>(&self) -> bool {
+        self.registrations.contains_key(&TypeId::of::<B>())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &BlueprintRegistration> {
+        self.registrations.values()
+    }
+
+    /// The registrations sorted by type path, for menus that should list them in a stable order.
+    pub fn iter_sorted(&self) -> impl Iterator<Item = &BlueprintRegistration> {
+        let mut registrations = self.registrations.values().collect::<Vec<_>>();
+        registrations.sort_by_key(|registration| registration.type_path);
+        registrations.into_iter()
+    }
+
+    #[cfg_attr(not(feature = "asset"), allow(dead_code))]
+    pub(crate) fn register_dynamic(&mut self, name: String) {
+        if !self.dynamic.contains(&name) {
+            self.dynamic.push(name);
+        }
+    }
+
+    /// The names of the `DynamicBlueprintDefinition`s loaded so far.
+    pub fn dynamic_blueprints(&self) -> impl Iterator<Item = &str> {
+        self.dynamic.iter().map(String::as_str)
+    }
+
+    /// Silences the unregistered blueprint warning for blueprints which intentionally have no
+    /// `BlueprintPlugin`.
+    pub fn ignore<B: Default + Send + Sync + 'static>(&mut self) {
+        self.ignored.insert(TypeId::of::<Blueprint<B>>());
+    }
+
+    /// Silences the unreflected prefab component warning for components that are intentionally
+    /// excluded from scenes.
+    pub fn allow_unreflected<C: Component>(&mut self) {
+        self.unreflected_allowed.insert(TypeId::of::<C>());
+    }
+
+    /// Whether the component type is a `Blueprint<_>` that has been added to an entity, but that no
+    /// `BlueprintPlugin` builds (and that is not ignored). Blueprint types are recorded the first
+    /// time they are added, so this does not depend on their type names.
+    pub fn is_unregistered(&self, components: &Components, component_type_id: TypeId) -> bool {
+        !self.covers_component(component_type_id)
+            && components
+                .get_id(component_type_id)
+                .is_some_and(|id| self.spawned.contains(&id))
+    }
+
+    pub(crate) fn covers_component(&self, component_type_id: TypeId) -> bool {
+        self.ignored.contains(&component_type_id)
+            || self
+                .registrations
+                .values()
+                .any(|registration| registration.component_type_id == component_type_id)
+    }
+}
+
+/// Lists each registered blueprint type with its prefabs, their targets and their schedules, for a
+/// debug dump.
+impl std::fmt::Display for BlueprintRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for registration in self.iter_sorted() {
+            writeln!(f, "{}", registration.short_name())?;
+            for prefab in &registration.prefabs {
+                let schedules = prefab
+                    .schedules
+                    .iter()
+                    .map(|schedule| format!("{schedule:?}"))
+                    .collect::<Vec<_>>();
+                writeln!(
+                    f,
+                    "  {} as {:?} in {}",
+                    get_short_name(prefab.prefab_name),
+                    prefab.target,
+                    schedules.join(", "),
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Checks the `BlueprintRegistry` of an `App` or `World` for a blueprint type, e.g. to handle a
+/// plugin missing from a modular build before spawning its blueprints.
+pub trait BlueprintRegistryExt {
+    /// Whether `B` has a `BlueprintPlugin`, as `BlueprintRegistry::contains`. Always `false`
+    /// before any blueprint plugin is added.
+    fn is_blueprint_registered<B: 'static>(&self) -> bool;
+}
+
+impl BlueprintRegistryExt for World {
+    fn is_blueprint_registered<B: 'static>(&self) -> bool {
+        self.get_resource::<BlueprintRegistry>()
+            .is_some_and(BlueprintRegistry::contains::<B>)
+    }
+}
+
+impl BlueprintRegistryExt for App {
+    fn is_blueprint_registered<B: 'static>(&self) -> bool {
+        self.world().is_blueprint_registered::<B>()
+    }
+}
+
+// the `TypeId` of `Blueprint<B>`, for the blueprint type `B` with this type path, if it has a
+// `BlueprintPlugin`
+pub(crate) fn blueprint_component_type_id(world: &World, type_path: &str) -> Option<TypeId> {
+    world
+        .get_resource::<BlueprintRegistry>()
+        .and_then(|registry| {
+            registry
+                .iter()
+                .find(|registration| registration.type_path == type_path)
+                .map(|registration| registration.component_type_id)
+        })
+}
+
+// the `on_add` hook of every `Blueprint<B>`, recording its component id the first time it is added
+pub(crate) fn record_spawned_blueprint(mut world: DeferredWorld, _: Entity, id: ComponentId) {
+    let spawned = world
+        .get_resource::<BlueprintRegistry>()
+        .is_some_and(|registry| registry.spawned.contains(&id));
+    if !spawned {
+        if let Some(mut registry) = world.get_resource_mut::<BlueprintRegistry>() {
+            registry.spawned.push(id);
+        }
+    }
+}
+
+#[cfg(feature = "scene")]
+pub(crate) fn blueprint_name_prefix() -> &'static str {
+    let name = std::any::type_name::<Blueprint<()>>();
+    &name[..=name.find('<').unwrap()]
+}
+
+/// Returns the names of `Blueprint<_>` components present in the world that have no registered
+/// `BlueprintPlugin`.
+pub fn unregistered_blueprints(world: &World) -> Vec<&str> {
+    let Some(registry) = world.get_resource::<BlueprintRegistry>() else {
+        return Vec::new();
+    };
+    registry
+        .spawned
+        .iter()
+        .filter_map(|id| world.components().get_info(*id))
+        .filter(|info| {
+            !info
+                .type_id()
+                .is_some_and(|type_id| registry.covers_component(type_id))
+        })
+        .filter(|info| {
+            world
+                .archetypes()
+                .iter()
+                .any(|archetype| !archetype.is_empty() && archetype.contains(info.id()))
+        })
+        .map(|info| info.name())
+        .collect()
+}
+
+// asks every prefab plugin to build the entity's blueprints, e.g. once it stops waiting for its
+// dependencies, including `added_only` plugins (which ignore changes to the blueprints)
+pub(crate) fn rebuild_prefabs(world: &mut World, entity: Entity) {
+    let rebuilds = world
+        .get_resource::<BlueprintRegistry>()
+        .map(|registry| {
+            registry
+                .iter()
+                .flat_map(|registration| registration.prefabs.iter())
+                .map(|prefab| prefab.rebuild)
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    for rebuild in rebuilds {
+        rebuild(world, entity);
+    }
+}
+
+fn spawn_default_blueprint<B: Default + Send + Sync + 'static>(commands: &mut Commands) -> Entity {
+    commands.spawn(Blueprint::bundle(B::default())).id()
+}
+
+fn blueprint_pending<B: Default + Send + Sync + 'static>(world: &World, entity: Entity) -> bool {
+    world.get_entity(entity).is_some_and(|entity| {
+        entity.contains::<Blueprint<B>>() && !entity.contains::<BlueprintBuilt<B>>()
+    })
+}
+
+// scans the archetypes rather than the entities, so that built blueprints cost nothing
+fn pending_blueprint_entities<B: Default + Send + Sync + 'static>(world: &World) -> Vec<Entity> {
+    let Some(blueprint) = world.component_id::<Blueprint<B>>() else {
+        return Vec::new();
+    };
+    let built = world.component_id::<BlueprintBuilt<B>>();
+    world
+        .archetypes()
+        .iter()
+        .filter(|archetype| {
+            archetype.contains(blueprint) && !built.is_some_and(|built| archetype.contains(built))
+        })
+        .flat_map(|archetype| archetype.entities().iter().map(|entity| entity.id()))
+        .collect()
+}
+
+/// Returns every entity with a blueprint (of any type with a `BlueprintPlugin`) that has not been
+/// built yet: its build is waiting for a `build_if` condition or its dependencies, or failed and
+/// will be retried. Useful to debug why something does not show up.
+pub fn pending_blueprints(world: &World) -> Vec<Entity> {
+    let mut entities = pending_blueprint_types(world)
+        .into_iter()
+        .map(|(entity, _)| entity)
+        .collect::<Vec<_>>();
+    entities.dedup();
+    entities
+}
+
+/// Returns the pending blueprints as `(entity, blueprint type path)` pairs, sorted by entity, e.g.
+/// for a diagnostics overlay. An entity with several pending blueprints has a pair for each.
+pub fn pending_blueprint_types(world: &World) -> Vec<(Entity, &'static str)> {
+    let Some(registry) = world.get_resource::<BlueprintRegistry>() else {
+        return Vec::new();
+    };
+    let mut pending = registry
+        .iter()
+        .flat_map(|registration| {
+            (registration.pending_entities)(world)
+                .into_iter()
+                .map(|entity| (entity, registration.type_path))
+        })
+        .collect::<Vec<_>>();
+    pending.sort();
+    pending
+}
+
+fn unreflected_components<P: Bundle>(world: &mut World) -> Vec<String> {
+    let component_ids = world.init_bundle::<P>().components().to_vec();
+    let Some(type_registry) = world.get_resource::<AppTypeRegistry>() else {
+        return Vec::new();
+    };
+    let type_registry = type_registry.read();
+    let allowed = world
+        .get_resource::<BlueprintRegistry>()
+        .map(|registry| &registry.unreflected_allowed);
+    component_ids
+        .into_iter()
+        .filter_map(|component_id| world.components().get_info(component_id))
+        .filter(|info| {
+            info.type_id().is_some_and(|type_id| {
+                type_registry.get(type_id).is_none()
+                    && !allowed.is_some_and(|allowed| allowed.contains(&type_id))
+            })
+        })
+        .map(|info| info.name().to_string())
+        .collect()
+}
+
+/// Returns the components of registered prefabs that are not registered for reflection (and so
+/// would be left out of saved scenes), as `(prefab name, component name)` pairs.
+pub fn unreflected_prefab_components(world: &mut World) -> Vec<(&'static str, String)> {
+    let Some(registry) = world.get_resource::<BlueprintRegistry>() else {
+        return Vec::new();
+    };
+    let prefabs = registry
+        .iter()
+        .flat_map(|registration| registration.prefabs.iter())
+        .map(|prefab| (prefab.prefab_name, prefab.unreflected_components))
+        .collect::<Vec<_>>();
+    let mut unreflected = prefabs
+        .into_iter()
+        .flat_map(|(prefab_name, unreflected_components)| {
+            unreflected_components(world)
+                .into_iter()
+                .map(move |component| (prefab_name, component))
+        })
+        .collect::<Vec<_>>();
+    unreflected.sort();
+    unreflected.dedup();
+    unreflected
+}
+
+// runs at startup, so that missing registrations are found before any scene is saved
+pub(crate) fn warn_unreflected_prefab_components(world: &mut World) {
+    for (prefab_name, component) in unreflected_prefab_components(world) {
+        warn!(
+            "{component} in prefab {prefab_name} is not registered for reflection, so it will not be saved in scenes"
+        );
+    }
+}
+
+// only checks the blueprint types first added since the last run, so it warns once per type
+pub(crate) fn warn_unregistered_blueprints(
+    registry: Option<Res<BlueprintRegistry>>,
+    components: &Components,
+    mut checked: Local<usize>,
+) {
+    let Some(registry) = registry else {
+        return;
+    };
+    for id in &registry.spawned[*checked..] {
+        let Some(info) = components.get_info(*id) else {
+            continue;
+        };
+        let covered = info
+            .type_id()
+            .is_some_and(|type_id| registry.covers_component(type_id));
+        if !covered {
+            warn!(
+                "{} was spawned but no BlueprintPlugin is registered for it",
+                info.name()
+            );
+        }
+    }
+    *checked = registry.spawned.len();
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use bevy::{
+        ecs::{
+            schedule::ScheduleLabel,
+            system::{EntityCommands, RunSystemOnce, StaticSystemParam},
+        },
+        log::tracing_subscriber::{self, layer::Context, prelude::*, Layer},
+        prelude::*,
+        utils::tracing::{
+            self,
+            field::{Field, Visit},
+            Event, Level, Subscriber,
+        },
+    };
+
+    use super::*;
+    use crate::{AsChild, BlueprintPlugin, BlueprintsPlugin, FromBlueprint};
+
+    #[derive(Default, Reflect)]
+    struct Rect;
+
+    #[derive(Default, Reflect)]
+    struct Circle;
+
+    #[derive(Default, Reflect)]
+    struct Marker;
+
+    #[derive(Component)]
+    struct Shape;
+
+    impl FromBlueprint<Rect> for Shape {
+        type Params<'w, 's> = ();
+        fn from_blueprint(_: &Rect, _: &mut StaticSystemParam<Self::Params<'_, '_>>) -> Self {
+            Shape
+        }
+    }
+
+    #[derive(Component, Reflect)]
+    struct Reflected;
+
+    #[derive(Component)]
+    struct Unreflected;
+
+    #[derive(Component)]
+    struct RuntimeOnly;
+
+    #[derive(Bundle)]
+    struct CircleBundle {
+        reflected: Reflected,
+        unreflected: Unreflected,
+        runtime_only: RuntimeOnly,
+    }
+
+    impl FromBlueprint<Circle> for CircleBundle {
+        type Params<'w, 's> = ();
+        fn from_blueprint(_: &Circle, _: &mut StaticSystemParam<Self::Params<'_, '_>>) -> Self {
+            CircleBundle {
+                reflected: Reflected,
+                unreflected: Unreflected,
+                runtime_only: RuntimeOnly,
+            }
+        }
+    }
+
+    #[test]
+    fn detects_unreflected_prefab_components() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Circle, CircleBundle>::default())
+            .register_type::<Reflected>();
+        app.world_mut()
+            .resource_mut::<BlueprintRegistry>()
+            .allow_unreflected::<RuntimeOnly>();
+
+        assert_eq!(
+            unreflected_prefab_components(app.world_mut()),
+            vec![(
+                std::any::type_name::<CircleBundle>(),
+                std::any::type_name::<Unreflected>().to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn detects_unregistered_blueprints() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Rect, Shape>::default());
+        app.world_mut()
+            .resource_mut::<BlueprintRegistry>()
+            .ignore::<Marker>();
+        app.world_mut().spawn(Blueprint::<Rect>::default());
+        app.world_mut().spawn(Blueprint::<Circle>::default());
+        app.world_mut().spawn(Blueprint::<Marker>::default());
+        app.update();
+
+        assert_eq!(
+            unregistered_blueprints(app.world()),
+            vec![std::any::type_name::<Blueprint<Circle>>()]
+        );
+        let registry = app.world().resource::<BlueprintRegistry>();
+        let components = app.world().components();
+        assert!(registry.is_unregistered(components, TypeId::of::<Blueprint<Circle>>()));
+        assert!(!registry.is_unregistered(components, TypeId::of::<Blueprint<Rect>>()));
+        assert!(!registry.is_unregistered(components, TypeId::of::<Blueprint<Marker>>()));
+        assert!(!registry.is_unregistered(components, TypeId::of::<Circle>()));
+        let registration = app
+            .world()
+            .resource::<BlueprintRegistry>()
+            .get::<Rect>()
+            .unwrap();
+        assert_eq!(registration.type_path, Rect::type_path());
+        assert_eq!(registration.prefabs.len(), 1);
+    }
+
+    // collects the messages of the warnings logged while it is the subscriber
+    #[derive(Clone, Default)]
+    struct Warnings(Arc<Mutex<Vec<String>>>);
+
+    impl Visit for Warnings {
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            if field.name() == "message" {
+                self.0.lock().unwrap().push(format!("{value:?}"));
+            }
+        }
+    }
+
+    impl<S: Subscriber> Layer<S> for Warnings {
+        fn on_event(&self, event: &Event<'_>, _: Context<'_, S>) {
+            if *event.metadata().level() == Level::WARN {
+                event.record(&mut self.clone());
+            }
+        }
+    }
+
+    #[test]
+    fn unregistered_blueprints_warn_once() {
+        let warnings = Warnings::default();
+        let subscriber = tracing_subscriber::registry().with(warnings.clone());
+        tracing::subscriber::with_default(subscriber, || {
+            let mut app = App::new();
+            app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+                .add_plugins(BlueprintPlugin::<Rect, Shape>::default());
+            app.world_mut().spawn(Blueprint::<Rect>::default());
+            app.world_mut().spawn(Blueprint::<Circle>::default());
+            app.update();
+            // more entities, and new archetypes, of the same types
+            app.world_mut().spawn(Blueprint::<Circle>::default());
+            app.world_mut()
+                .spawn((Blueprint::<Circle>::default(), Blueprint::<Rect>::default()));
+            app.update();
+        });
+        let circle = std::any::type_name::<Blueprint<Circle>>();
+        let unregistered = warnings
+            .0
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|warning| warning.contains("no BlueprintPlugin"))
+            .cloned()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            unregistered,
+            vec![format!(
+                "{circle} was spawned but no BlueprintPlugin is registered for it"
+            )]
+        );
+    }
+
+    #[test]
+    fn registered_blueprints_spawn_defaults() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Rect, Shape>::default())
+            .add_plugins(BlueprintPlugin::<Circle, CircleBundle>::default());
+        // e.g. the buttons of a debug spawn menu
+        let spawn_all = |mut commands: Commands, registry: Res<BlueprintRegistry>| {
+            registry
+                .iter_sorted()
+                .map(|registration| {
+                    (
+                        registration.short_name(),
+                        registration.spawn_default(&mut commands),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        let spawned = app.world_mut().run_system_once(spawn_all);
+        let names = spawned
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["Circle", "Rect"]);
+        app.update();
+        assert!(app.world().get::<Reflected>(spawned[0].1).is_some());
+        assert!(app.world().get::<Shape>(spawned[1].1).is_some());
+    }
+
+    // e.g. an overlay drawn by a custom render pass
+    struct AsOverlay;
+
+    impl BlueprintTarget for AsOverlay {
+        const KIND: TargetKind = TargetKind::Other("overlay");
+
+        fn remove_target_bundle<T, P: Bundle + FromBlueprint<T>>(entity: &mut EntityCommands) {
+            AsChild::remove_target_bundle::<T, P>(entity);
+        }
+
+        fn attach_target_bundle<T, P: Bundle + FromBlueprint<T>>(
+            entity: &mut EntityCommands,
+            bundle: P,
+        ) {
+            AsChild::attach_target_bundle::<T, P>(entity, bundle);
+        }
+    }
+
+    #[test]
+    fn registered_prefabs_record_their_target() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Rect, Shape>::default())
+            .add_plugins(BlueprintPlugin::<Rect, Shape, AsChild>::default())
+            .add_plugins(BlueprintPlugin::<Circle, CircleBundle, AsOverlay>::default());
+        let registry = app.world().resource::<BlueprintRegistry>();
+        let targets = |registration: &BlueprintRegistration| {
+            registration
+                .prefabs
+                .iter()
+                .map(|prefab| prefab.target)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            targets(registry.get::<Rect>().unwrap()),
+            vec![TargetKind::AsSelf, TargetKind::AsChild]
+        );
+        assert_eq!(
+            targets(registry.get::<Circle>().unwrap()),
+            vec![TargetKind::Other("overlay")]
+        );
+    }
+
+    #[test]
+    fn registry_dump_lists_schedules() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Rect, Shape>::default())
+            .add_plugins(BlueprintPlugin::<Rect, Shape, AsChild>::default())
+            .add_plugins(BlueprintPlugin::<Circle, CircleBundle, AsOverlay>::default());
+        let registry = app.world().resource::<BlueprintRegistry>();
+        assert_eq!(
+            registry.get::<Rect>().unwrap().prefabs[0].schedules,
+            vec![Update.intern()]
+        );
+        assert_eq!(
+            registry.to_string(),
+            "Circle\n  CircleBundle as Other(\"overlay\") in Update\n\
+             Rect\n  Shape as AsSelf in Update\n  Shape as AsChild in Update\n"
+        );
+    }
+
+    #[test]
+    fn registration_is_checked_before_spawning() {
+        let mut app = App::new();
+        assert!(!app.is_blueprint_registered::<Rect>());
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Rect, Shape>::default())
+            .add_plugins(BlueprintPlugin::<Rect, Shape, AsChild>::default());
+        assert!(app.is_blueprint_registered::<Rect>());
+        assert!(!app.is_blueprint_registered::<Circle>());
+
+        // e.g. circles are only available with an optional module
+        let spawn = |world: &mut World| {
+            world
+                .is_blueprint_registered::<Circle>()
+                .then(|| world.spawn(Blueprint::<Circle>::default()).id())
+        };
+        assert_eq!(app.world_mut().run_system_once(spawn), None);
+    }
+
+    #[test]
+    fn pending_blueprints_are_listed_with_their_types() {
+        #[derive(Resource)]
+        struct CirclesReady;
+
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Rect, Shape>::default())
+            .add_plugins(
+                BlueprintPlugin::<Circle, CircleBundle>::default()
+                    .build_if(|world| world.contains_resource::<CirclesReady>()),
+            );
+        let rect = app.world_mut().spawn(Blueprint::<Rect>::default()).id();
+        let circle = app.world_mut().spawn(Blueprint::<Circle>::default()).id();
+        let both = app
+            .world_mut()
+            .spawn((Blueprint::<Rect>::default(), Blueprint::<Circle>::default()))
+            .id();
+        assert_eq!(pending_blueprints(app.world()), vec![rect, circle, both]);
+
+        app.update();
+        assert_eq!(pending_blueprints(app.world()), vec![circle, both]);
+        assert_eq!(
+            pending_blueprint_types(app.world()),
+            vec![(circle, Circle::type_path()), (both, Circle::type_path())]
+        );
+
+        app.insert_resource(CirclesReady);
+        app.update();
+        assert!(pending_blueprints(app.world()).is_empty());
+    }
+}
@@ -0,0 +1,114 @@
+use bevy::prelude::*;
+
+use crate::Blueprint;
+
+/// Removes every blueprint of a type at once, e.g. to clear a level's enemies on a transition.
+pub trait RemoveBlueprintsExt {
+    /// Removes `Blueprint<B>` from every entity that has one. Their targets are cleaned up in the
+    /// next `Update`, as for any removed blueprint, while the entities themselves are kept.
+    fn remove_all_blueprints<B: Default + Send + Sync + 'static>(&mut self);
+
+    /// Despawns every entity with a `Blueprint<B>`, along with its descendants.
+    fn despawn_all_blueprints<B: Default + Send + Sync + 'static>(&mut self);
+}
+
+fn blueprint_entities<B: Default + Send + Sync + 'static>(world: &mut World) -> Vec<Entity> {
+    world
+        .query_filtered::<Entity, With<Blueprint<B>>>()
+        .iter(world)
+        .collect()
+}
+
+impl RemoveBlueprintsExt for World {
+    fn remove_all_blueprints<B: Default + Send + Sync + 'static>(&mut self) {
+        for entity in blueprint_entities::<B>(self) {
+            self.entity_mut(entity).remove::<Blueprint<B>>();
+        }
+    }
+
+    fn despawn_all_blueprints<B: Default + Send + Sync + 'static>(&mut self) {
+        for entity in blueprint_entities::<B>(self) {
+            // may have been despawned as the descendant of another one
+            if let Some(entity) = self.get_entity_mut(entity) {
+                entity.despawn_recursive();
+            }
+        }
+    }
+}
+
+impl RemoveBlueprintsExt for Commands<'_, '_> {
+    fn remove_all_blueprints<B: Default + Send + Sync + 'static>(&mut self) {
+        self.add(|world: &mut World| world.remove_all_blueprints::<B>());
+    }
+
+    fn despawn_all_blueprints<B: Default + Send + Sync + 'static>(&mut self) {
+        self.add(|world: &mut World| world.despawn_all_blueprints::<B>());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        ecs::system::{RunSystemOnce, StaticSystemParam},
+        prelude::*,
+    };
+
+    use super::*;
+    use crate::{AsChild, BlueprintPlugin, BlueprintsPlugin, FromBlueprint};
+
+    #[derive(Default, Reflect)]
+    struct Enemy;
+
+    #[derive(Component)]
+    struct EnemyMesh;
+
+    impl FromBlueprint<Enemy> for EnemyMesh {
+        type Params<'w, 's> = ();
+        fn from_blueprint(_: &Enemy, _: &mut StaticSystemParam<Self::Params<'_, '_>>) -> Self {
+            EnemyMesh
+        }
+    }
+
+    fn app() -> (App, Vec<Entity>) {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Enemy, EnemyMesh, AsChild>::default());
+        let enemies = (0..3)
+            .map(|_| app.world_mut().spawn(Blueprint::<Enemy>::default()).id())
+            .collect();
+        app.update();
+        (app, enemies)
+    }
+
+    fn meshes(app: &mut App) -> usize {
+        let mut query = app.world_mut().query_filtered::<(), With<EnemyMesh>>();
+        query.iter(app.world()).count()
+    }
+
+    #[test]
+    fn all_blueprints_are_removed() {
+        let (mut app, enemies) = app();
+        assert_eq!(meshes(&mut app), 3);
+
+        app.world_mut()
+            .run_system_once(|mut commands: Commands| commands.remove_all_blueprints::<Enemy>());
+        app.update();
+        assert_eq!(meshes(&mut app), 0);
+        assert!(enemies
+            .iter()
+            .all(|enemy| app.world().get_entity(*enemy).is_some()));
+    }
+
+    #[test]
+    fn all_blueprint_entities_are_despawned() {
+        let (mut app, enemies) = app();
+        app.world_mut().despawn_all_blueprints::<Enemy>();
+        assert_eq!(meshes(&mut app), 0);
+        assert!(enemies
+            .iter()
+            .all(|enemy| app.world().get_entity(*enemy).is_none()));
+        // the cleanup of the despawned blueprints finds nothing left
+        app.update();
+        assert_eq!(meshes(&mut app), 0);
+    }
+}
@@ -0,0 +1,96 @@
+use bevy::prelude::*;
+
+use crate::BlueprintRegistry;
+
+/// Tears down the targets of every blueprint, keeping the `Blueprint<B>` components, so that they
+/// are built from scratch in the next `Update`, e.g. to return a level to its initial state.
+///
+/// Each plugin cleans up as if the blueprint was removed, following its `CleanupPolicy`, and then
+/// marks the blueprint as changed. `AsChild` children are despawned and spawned anew, so state
+/// that other systems attached to them is lost. Plugins that only build added blueprints (e.g.
+/// with `added_only`, or a custom `BlueprintTrigger`) are not built again until their trigger
+/// matches.
+pub fn reset_all_targets(world: &mut World) {
+    let Some(registry) = world.get_resource::<BlueprintRegistry>() else {
+        return;
+    };
+    let registrations = registry.iter().cloned().collect::<Vec<_>>();
+    for registration in &registrations {
+        for prefab in &registration.prefabs {
+            (prefab.reset)(world);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{ecs::system::StaticSystemParam, prelude::*};
+
+    use super::*;
+    use crate::{AsChild, Blueprint, BlueprintPlugin, BlueprintsPlugin, FromBlueprint};
+
+    #[derive(Default, Reflect)]
+    struct Door {
+        width: f32,
+    }
+
+    #[derive(Component)]
+    struct Frame(f32);
+
+    // changed by gameplay
+    #[derive(Component)]
+    struct Open(bool);
+
+    impl FromBlueprint<Door> for (Frame, Open) {
+        type Params<'w, 's> = ();
+        fn from_blueprint(
+            blueprint: &Door,
+            _: &mut StaticSystemParam<Self::Params<'_, '_>>,
+        ) -> Self {
+            (Frame(blueprint.width), Open(false))
+        }
+    }
+
+    #[derive(Component)]
+    struct Knob;
+
+    impl FromBlueprint<Door> for Knob {
+        type Params<'w, 's> = ();
+        fn from_blueprint(_: &Door, _: &mut StaticSystemParam<Self::Params<'_, '_>>) -> Self {
+            Knob
+        }
+    }
+
+    fn knobs(app: &mut App) -> Vec<Entity> {
+        let mut query = app.world_mut().query_filtered::<Entity, With<Knob>>();
+        query.iter(app.world()).collect()
+    }
+
+    #[test]
+    fn targets_are_rebuilt_after_a_reset() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Door, (Frame, Open)>::default())
+            .add_plugins(BlueprintPlugin::<Door, Knob, AsChild>::default());
+        let door = app
+            .world_mut()
+            .spawn(Blueprint::new(Door { width: 2. }))
+            .id();
+        app.update();
+        app.world_mut().get_mut::<Open>(door).unwrap().0 = true;
+        let knob = knobs(&mut app)[0];
+
+        reset_all_targets(app.world_mut());
+        assert!(app.world().get::<Frame>(door).is_none());
+        assert!(app.world().get::<Open>(door).is_none());
+        assert!(knobs(&mut app).is_empty());
+        assert!(app.world().get::<Blueprint<Door>>(door).is_some());
+
+        app.update();
+        assert_eq!(app.world().get::<Frame>(door).unwrap().0, 2.);
+        assert!(!app.world().get::<Open>(door).unwrap().0);
+        let rebuilt = knobs(&mut app);
+        assert_eq!(rebuilt.len(), 1);
+        assert_ne!(rebuilt[0], knob);
+    }
+}
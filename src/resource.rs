@@ -0,0 +1,160 @@
+use std::marker::PhantomData;
+
+use bevy::{
+    ecs::system::StaticSystemParam, prelude::*, reflect::GetTypeRegistration, utils::get_short_name,
+};
+
+use crate::{blueprints_paused, Blueprint, BlueprintSet, BlueprintsPlugin, TryFromBlueprint};
+
+/// Marks the entity that `BlueprintResourcePlugin<B, _>` builds the `Blueprint<B>` resource on.
+///
+/// Spawn an entity with this marker to choose the singleton; otherwise one is spawned the first
+/// time the resource is built.
+#[derive(Debug, Component)]
+pub struct BlueprintSingleton<B: Send + Sync + 'static>(PhantomData<B>);
+
+impl<B: Send + Sync + 'static> Default for BlueprintSingleton<B> {
+    fn default() -> Self {
+        BlueprintSingleton(PhantomData)
+    }
+}
+
+/// Builds `P` on a singleton entity from a `Blueprint<B>` stored as a resource, for global
+/// configuration that is not tied to an entity.
+///
+/// The prefab is rebuilt whenever the resource changes, and removed (leaving the singleton
+/// entity) if the resource is removed.
+pub struct BlueprintResourcePlugin<B, P: Bundle + TryFromBlueprint<B>> {
+    blueprint_marker: PhantomData<B>,
+    prefab_marker: PhantomData<P>,
+}
+
+impl<B, P: Bundle + TryFromBlueprint<B>> Default for BlueprintResourcePlugin<B, P> {
+    fn default() -> Self {
+        Self {
+            blueprint_marker: PhantomData::<B>,
+            prefab_marker: PhantomData::<P>,
+        }
+    }
+}
+
+impl<B, P> BlueprintResourcePlugin<B, P>
+where
+    B: Default + Send + Sync + 'static,
+    P: Bundle + TryFromBlueprint<B>,
+{
+    fn sync_singleton(
+        mut commands: Commands,
+        blueprint: Option<Res<Blueprint<B>>>,
+        singleton_query: Query<Entity, With<BlueprintSingleton<B>>>,
+        mut system_params: StaticSystemParam<P::Params<'_, '_>>,
+    ) {
+        let singleton = singleton_query.iter().next();
+        let Some(blueprint) = blueprint else {
+            if let Some(singleton) = singleton {
+                commands.entity(singleton).remove::<P>();
+            }
+            return;
+        };
+        let mut entity_commands = match singleton {
+            Some(singleton) => commands.entity(singleton),
+            None => commands.spawn(BlueprintSingleton::<B>::default()),
+        };
+        entity_commands.remove::<P>();
+        match P::try_from_blueprint(&blueprint.0, &mut system_params) {
+            Ok(bundle) => {
+                entity_commands.insert(bundle);
+            }
+            Err(error) => error!(
+                "blueprint resource {} failed to build {}: {}",
+                get_short_name(std::any::type_name::<B>()),
+                get_short_name(std::any::type_name::<P>()),
+                error,
+            ),
+        }
+    }
+}
+
+impl<B, P> Plugin for BlueprintResourcePlugin<B, P>
+where
+    B: Default + GetTypeRegistration + FromReflect + TypePath + Send + Sync + 'static,
+    P: Bundle + TryFromBlueprint<B>,
+{
+    fn build(&self, app: &mut App) {
+        BlueprintsPlugin::add_once(app);
+        app.add_systems(
+            Update,
+            Self::sync_singleton.in_set(BlueprintSet::Sync).run_if(
+                not(blueprints_paused).and_then(resource_changed_or_removed::<Blueprint<B>>()),
+            ),
+        )
+        .register_type::<Blueprint<B>>()
+        .register_type::<B>();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{ecs::system::StaticSystemParam, prelude::*};
+
+    use super::*;
+    use crate::{BlueprintsPlugin, FromBlueprint};
+
+    #[derive(Default, Reflect)]
+    struct WorldSettings {
+        gravity: f32,
+    }
+
+    #[derive(Component)]
+    struct Gravity(f32);
+
+    impl FromBlueprint<WorldSettings> for Gravity {
+        type Params<'w, 's> = ();
+        fn from_blueprint(
+            blueprint: &WorldSettings,
+            _: &mut StaticSystemParam<Self::Params<'_, '_>>,
+        ) -> Self {
+            Gravity(blueprint.gravity)
+        }
+    }
+
+    fn gravity(app: &mut App) -> Vec<f32> {
+        app.world_mut()
+            .query_filtered::<&Gravity, With<BlueprintSingleton<WorldSettings>>>()
+            .iter(app.world())
+            .map(|gravity| gravity.0)
+            .collect()
+    }
+
+    #[test]
+    fn resource_blueprint_builds_singleton() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintResourcePlugin::<WorldSettings, Gravity>::default());
+        app.update();
+        assert!(gravity(&mut app).is_empty());
+
+        // the singleton is spawned on the first build
+        app.insert_resource(Blueprint::new(WorldSettings { gravity: 9.8 }));
+        app.update();
+        assert_eq!(gravity(&mut app), vec![9.8]);
+
+        app.world_mut()
+            .resource_mut::<Blueprint<WorldSettings>>()
+            .0
+            .gravity = 1.6;
+        app.update();
+        assert_eq!(gravity(&mut app), vec![1.6]);
+
+        app.world_mut()
+            .remove_resource::<Blueprint<WorldSettings>>();
+        app.update();
+        assert!(gravity(&mut app).is_empty());
+        let singletons = app
+            .world_mut()
+            .query_filtered::<(), With<BlueprintSingleton<WorldSettings>>>()
+            .iter(app.world())
+            .count();
+        assert_eq!(singletons, 1);
+    }
+}
@@ -0,0 +1,704 @@
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use bevy::{
+    ecs::{entity::EntityHashMap, reflect::AppTypeRegistry},
+    prelude::*,
+    scene::{DynamicEntity, SceneSpawnError},
+    utils::{get_short_name, HashMap, HashSet},
+};
+
+use crate::{blueprint_name_prefix, Blueprint, BlueprintRegistry, IsBlueprint};
+
+/// Blueprint data standing in for the blueprints of a scene entity that no `BlueprintPlugin`
+/// builds, e.g. those of a mod that is not installed, so that missing content is visible.
+///
+/// `write_blueprint_scene` only adds it when a `BlueprintPlugin` is registered for it, e.g. one
+/// building a magenta box labeled with `FallbackBlueprint::label`.
+#[derive(Clone, Debug, Default, PartialEq, Reflect)]
+pub struct FallbackBlueprint {
+    /// The type paths of the missing blueprint types, e.g.
+    /// `bevy_reactive_blueprints::Blueprint<my_mod::Tower>`, sorted.
+    pub type_paths: Vec<String>,
+}
+
+impl FallbackBlueprint {
+    /// The short names of the missing blueprint types, e.g. `Blueprint<Tower>`.
+    pub fn label(&self) -> String {
+        self.type_paths
+            .iter()
+            .map(|type_path| get_short_name(type_path))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+// blueprints are told apart from other components by the type path of `Blueprint<_>`, since
+// unregistered types have no type info to check
+fn is_blueprint_path(type_path: &str) -> bool {
+    type_path.starts_with(blueprint_name_prefix())
+}
+
+/// Writes a `DynamicScene`, e.g. one saved by an editor, to the world, like
+/// `DynamicScene::write_to_world`.
+///
+/// Each reflected `Blueprint<B>` becomes the typed component, so it is `Added` (or `Changed`,
+/// for an entity already in `entity_map`) and built in the next `Update`. Unlike
+/// `write_to_world`, components whose type is not registered as a component, such as the
+/// blueprints of a type without a `BlueprintPlugin`, are skipped with a warning rather than
+/// failing the whole scene.
+///
+/// If a `BlueprintPlugin` builds `FallbackBlueprint`, entities with blueprints that no plugin
+/// builds, whether their type is registered or not, also get a `FallbackBlueprint` naming them.
+pub fn write_blueprint_scene(
+    world: &mut World,
+    scene: &DynamicScene,
+    entity_map: &mut EntityHashMap<Entity>,
+) -> Result<(), SceneSpawnError> {
+    let registry = world.resource::<AppTypeRegistry>().clone();
+    let blueprints = world.get_resource::<BlueprintRegistry>();
+    let fallback =
+        blueprints.is_some_and(|blueprints| blueprints.get::<FallbackBlueprint>().is_some());
+    let mut missing = EntityHashMap::<Vec<String>>::default();
+    let filtered = {
+        let registry = registry.read();
+        let entities = scene
+            .entities
+            .iter()
+            .map(|scene_entity| DynamicEntity {
+                entity: scene_entity.entity,
+                components: scene_entity
+                    .components
+                    .iter()
+                    .filter(|component| {
+                        let registration = component
+                            .get_represented_type_info()
+                            .and_then(|info| registry.get(info.type_id()))
+                            .filter(|registration| {
+                                registration.data::<ReflectComponent>().is_some()
+                            });
+                        let type_path = component.reflect_type_path();
+                        if fallback
+                            && is_blueprint_path(type_path)
+                            && !registration.is_some_and(|registration| {
+                                blueprints.is_some_and(|blueprints| {
+                                    blueprints.covers_component(registration.type_id())
+                                })
+                            })
+                        {
+                            missing
+                                .entry(scene_entity.entity)
+                                .or_default()
+                                .push(type_path.to_string());
+                        }
+                        if registration.is_none() {
+                            warn!(
+                                "skipping the unregistered component {} of scene entity {}",
+                                type_path, scene_entity.entity,
+                            );
+                        }
+                        registration.is_some()
+                    })
+                    .map(|component| component.clone_value())
+                    .collect(),
+            })
+            .collect();
+        DynamicScene {
+            resources: scene
+                .resources
+                .iter()
+                .map(|resource| resource.clone_value())
+                .collect(),
+            entities,
+        }
+    };
+    filtered.write_to_world_with(world, entity_map, &registry)?;
+    for (scene_entity, mut type_paths) in missing {
+        let Some(mut entity_mut) = entity_map
+            .get(&scene_entity)
+            .and_then(|entity| world.get_entity_mut(*entity))
+        else {
+            continue;
+        };
+        type_paths.sort_unstable();
+        entity_mut.insert(Blueprint::new(FallbackBlueprint { type_paths }));
+    }
+    Ok(())
+}
+
+/// The identity of a blueprint entity across saves of a scene, so that `merge_blueprint_scene`
+/// can match the entities of a reloaded scene with those already in the world.
+///
+/// Ids are random, so the entities of different scenes are unlikely to share one. Within a scene,
+/// `assign_blueprint_scene_ids` gives duplicates (e.g. from copying an entity) fresh ids.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Component, Reflect)]
+#[reflect(Component)]
+pub struct BlueprintSceneId(pub u64);
+
+impl BlueprintSceneId {
+    /// A new random id.
+    pub fn generate() -> Self {
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_u64(COUNTER.fetch_add(1, Ordering::Relaxed));
+        BlueprintSceneId(hasher.finish())
+    }
+}
+
+/// Gives every blueprint entity without a `BlueprintSceneId`, or with one that another entity
+/// already has, a new id, e.g. before saving a scene.
+pub fn assign_blueprint_scene_ids(world: &mut World) {
+    let mut seen = HashSet::new();
+    let mut unassigned = Vec::new();
+    let mut query =
+        world.query_filtered::<(Entity, Option<&BlueprintSceneId>), With<IsBlueprint>>();
+    for (entity, id) in query.iter(world) {
+        if !id.is_some_and(|id| seen.insert(*id)) {
+            unassigned.push(entity);
+        }
+    }
+    for entity in unassigned {
+        world
+            .entity_mut(entity)
+            .insert(BlueprintSceneId::generate());
+    }
+}
+
+fn scene_id(scene_entity: &DynamicEntity) -> Option<BlueprintSceneId> {
+    scene_entity.components.iter().find_map(|component| {
+        (component.reflect_type_path() == BlueprintSceneId::type_path())
+            .then(|| BlueprintSceneId::from_reflect(component.as_ref()))
+            .flatten()
+    })
+}
+
+fn clone_entity(scene_entity: &DynamicEntity) -> DynamicEntity {
+    DynamicEntity {
+        entity: scene_entity.entity,
+        components: scene_entity
+            .components
+            .iter()
+            .map(|component| component.clone_value())
+            .collect(),
+    }
+}
+
+/// Writes a `DynamicScene` over the entities loaded from an earlier version of it, instead of
+/// despawning them first, e.g. to reload a scene while editing it.
+///
+/// Scene entities are matched with the world's by their `BlueprintSceneId`. For a matched entity,
+/// only the components that differ from the world's are written, so unchanged blueprints are not
+/// rebuilt and components added at runtime are kept; blueprints that the scene entity no longer
+/// has are removed. Scene entities without a match are spawned (those sharing an id with an
+/// earlier scene entity get a new one, with a warning), and world entities whose id is missing
+/// from the scene are despawned with their descendants. Entities without an id are left alone.
+///
+/// Components are written with `write_blueprint_scene`, so unregistered ones are skipped.
+pub fn merge_blueprint_scene(
+    world: &mut World,
+    scene: &DynamicScene,
+) -> Result<(), SceneSpawnError> {
+    let registry = world.resource::<AppTypeRegistry>().clone();
+    let mut existing = HashMap::new();
+    let mut query = world.query::<(Entity, &BlueprintSceneId)>();
+    for (entity, id) in query.iter(world) {
+        existing.entry(*id).or_insert(entity);
+    }
+
+    let mut scene_ids = HashSet::new();
+    let mut entity_map = EntityHashMap::default();
+    let mut duplicates = Vec::new();
+    let mut removed = Vec::new();
+    let filtered = {
+        let registry = registry.read();
+        let blueprints = world.get_resource::<BlueprintRegistry>();
+        let entities = scene
+            .entities
+            .iter()
+            .map(|scene_entity| {
+                let Some(id) = scene_id(scene_entity) else {
+                    return clone_entity(scene_entity);
+                };
+                if !scene_ids.insert(id) {
+                    warn!(
+                        "scene entity {} shares the id {} with another scene entity, and is spawned with a new id",
+                        scene_entity.entity, id.0,
+                    );
+                    duplicates.push(scene_entity.entity);
+                    return clone_entity(scene_entity);
+                }
+                let Some(entity_ref) = existing.get(&id).and_then(|entity| world.get_entity(*entity))
+                else {
+                    return clone_entity(scene_entity);
+                };
+                entity_map.insert(scene_entity.entity, entity_ref.id());
+                let components = scene_entity
+                    .components
+                    .iter()
+                    .filter(|component| {
+                        let Some(reflect_component) = component
+                            .get_represented_type_info()
+                            .and_then(|info| registry.get(info.type_id()))
+                            .and_then(|registration| registration.data::<ReflectComponent>())
+                        else {
+                            // skipped by `write_blueprint_scene`
+                            return true;
+                        };
+                        reflect_component
+                            .reflect(entity_ref)
+                            .and_then(|current| current.reflect_partial_eq(component.as_ref()))
+                            != Some(true)
+                    })
+                    .map(|component| component.clone_value())
+                    .collect();
+                // blueprints the scene entity no longer has
+                for registration in blueprints.into_iter().flat_map(|blueprints| blueprints.iter()) {
+                    let Some(type_registration) = registry.get(registration.component_type_id)
+                    else {
+                        continue;
+                    };
+                    let in_scene = scene_entity.components.iter().any(|component| {
+                        component.reflect_type_path()
+                            == type_registration.type_info().type_path()
+                    });
+                    if !in_scene
+                        && entity_ref.contains_type_id(registration.component_type_id)
+                    {
+                        if let Some(reflect_component) =
+                            type_registration.data::<ReflectComponent>()
+                        {
+                            removed.push((entity_ref.id(), reflect_component.clone()));
+                        }
+                    }
+                }
+                DynamicEntity {
+                    entity: scene_entity.entity,
+                    components,
+                }
+            })
+            .collect();
+        DynamicScene {
+            resources: scene
+                .resources
+                .iter()
+                .map(|resource| resource.clone_value())
+                .collect(),
+            entities,
+        }
+    };
+
+    for (id, entity) in existing {
+        if !scene_ids.contains(&id) {
+            if let Some(entity_mut) = world.get_entity_mut(entity) {
+                entity_mut.despawn_recursive();
+            }
+        }
+    }
+    for (entity, reflect_component) in removed {
+        reflect_component.remove(&mut world.entity_mut(entity));
+    }
+    write_blueprint_scene(world, &filtered, &mut entity_map)?;
+    for scene_entity in duplicates {
+        if let Some(mut entity_mut) = entity_map
+            .get(&scene_entity)
+            .and_then(|entity| world.get_entity_mut(*entity))
+        {
+            entity_mut.insert(BlueprintSceneId::generate());
+        }
+    }
+    Ok(())
+}
+
+/// Identifies a blueprint entity of a scene in a `SceneDiff`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SceneEntityKey {
+    /// The entity's `BlueprintSceneId`.
+    Id(BlueprintSceneId),
+    /// The position of an entity without an id among the entities without one, e.g. `0` for the
+    /// first.
+    Position(usize),
+}
+
+/// The blueprints of an entity that differ between two scenes, by type path (e.g.
+/// `bevy_reactive_blueprints::Blueprint<my_game::Tower>`).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChangedSceneEntity {
+    pub entity: SceneEntityKey,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub changed: Vec<String>,
+}
+
+/// The blueprint entities that differ between two scenes, found by `diff_scenes`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SceneDiff {
+    /// Entities only in the new scene, in its order.
+    pub added: Vec<SceneEntityKey>,
+    /// Entities only in the old scene, in its order.
+    pub removed: Vec<SceneEntityKey>,
+    /// Entities in both scenes whose blueprints differ, in the order of the new scene.
+    pub changed: Vec<ChangedSceneEntity>,
+}
+
+impl SceneDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+// the blueprint entities of a scene with their keys and blueprints, in order
+fn keyed_blueprints(scene: &DynamicScene) -> Vec<(SceneEntityKey, Vec<&dyn Reflect>)> {
+    let mut position = 0;
+    scene
+        .entities
+        .iter()
+        .filter_map(|scene_entity| {
+            let blueprints = scene_entity
+                .components
+                .iter()
+                .map(|component| component.as_ref())
+                .filter(|component| is_blueprint_path(component.reflect_type_path()))
+                .collect::<Vec<_>>();
+            if blueprints.is_empty() {
+                return None;
+            }
+            let key = match scene_id(scene_entity) {
+                Some(id) => SceneEntityKey::Id(id),
+                None => {
+                    position += 1;
+                    SceneEntityKey::Position(position - 1)
+                }
+            };
+            Some((key, blueprints))
+        })
+        .collect()
+}
+
+fn find_blueprint<'a>(blueprints: &[&'a dyn Reflect], type_path: &str) -> Option<&'a dyn Reflect> {
+    blueprints
+        .iter()
+        .find(|blueprint| blueprint.reflect_type_path() == type_path)
+        .copied()
+}
+
+/// Compares the blueprint entities of two scenes (such as two saved versions of a level), e.g. for
+/// a content review.
+///
+/// Entities are matched by their `BlueprintSceneId`. Entities without one are matched by their
+/// position among the entities without an id, so inserting such an entity in the middle of a
+/// scene shows up as changes to the ones after it; give entities ids (e.g. with
+/// `assign_blueprint_scene_ids`) for a meaningful diff. Blueprint values are compared with
+/// `Reflect::reflect_partial_eq`, and other components are ignored.
+pub fn diff_scenes(old: &DynamicScene, new: &DynamicScene) -> SceneDiff {
+    let old = keyed_blueprints(old);
+    let new = keyed_blueprints(new);
+    let old_keys = old.iter().map(|(key, _)| *key).collect::<HashSet<_>>();
+    let new_keys = new.iter().map(|(key, _)| *key).collect::<HashSet<_>>();
+    let mut diff = SceneDiff {
+        removed: old
+            .iter()
+            .map(|(key, _)| *key)
+            .filter(|key| !new_keys.contains(key))
+            .collect(),
+        ..default()
+    };
+    for (key, blueprints) in &new {
+        if !old_keys.contains(key) {
+            diff.added.push(*key);
+            continue;
+        }
+        let Some((_, old_blueprints)) = old.iter().find(|(old_key, _)| old_key == key) else {
+            continue;
+        };
+        let mut changes = ChangedSceneEntity {
+            entity: *key,
+            added: Vec::new(),
+            removed: Vec::new(),
+            changed: Vec::new(),
+        };
+        for blueprint in blueprints {
+            let type_path = blueprint.reflect_type_path();
+            match find_blueprint(old_blueprints, type_path) {
+                None => changes.added.push(type_path.to_string()),
+                Some(old_blueprint) => {
+                    if old_blueprint.reflect_partial_eq(*blueprint) != Some(true) {
+                        changes.changed.push(type_path.to_string());
+                    }
+                }
+            }
+        }
+        for old_blueprint in old_blueprints {
+            let type_path = old_blueprint.reflect_type_path();
+            if find_blueprint(blueprints, type_path).is_none() {
+                changes.removed.push(type_path.to_string());
+            }
+        }
+        if !(changes.added.is_empty() && changes.removed.is_empty() && changes.changed.is_empty()) {
+            diff.changed.push(changes);
+        }
+    }
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{ecs::system::StaticSystemParam, prelude::*, reflect::TypePath};
+
+    use super::*;
+    use crate::{BlueprintBuildStats, BlueprintPlugin, BlueprintsPlugin, FromBlueprint};
+
+    #[derive(Default, Reflect)]
+    struct Rect {
+        size: f32,
+    }
+
+    #[derive(Component)]
+    struct RectSize(f32);
+
+    impl FromBlueprint<Rect> for RectSize {
+        type Params<'w, 's> = ();
+        fn from_blueprint(
+            blueprint: &Rect,
+            _: &mut StaticSystemParam<Self::Params<'_, '_>>,
+        ) -> Self {
+            RectSize(blueprint.size)
+        }
+    }
+
+    // saved by a build with a plugin this app does not have
+    #[derive(Default, Reflect)]
+    struct Removed;
+
+    #[test]
+    fn scene_blueprints_are_built() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Rect, RectSize>::default());
+        let scene_entity = Entity::from_raw(0);
+        let scene = DynamicScene {
+            resources: vec![],
+            entities: vec![DynamicEntity {
+                entity: scene_entity,
+                components: vec![
+                    // dynamic, like a deserialized component
+                    Blueprint::new(Rect { size: 2. }).clone_value(),
+                    Box::new(Blueprint::new(Removed)),
+                ],
+            }],
+        };
+        let mut entity_map = EntityHashMap::default();
+        write_blueprint_scene(app.world_mut(), &scene, &mut entity_map).unwrap();
+        app.update();
+        let entity = entity_map[&scene_entity];
+        assert_eq!(app.world().get::<RectSize>(entity).unwrap().0, 2.);
+        assert!(app.world().get::<Blueprint<Removed>>(entity).is_none());
+
+        // loading the scene again over the same entities rebuilds them
+        let scene = DynamicScene {
+            resources: vec![],
+            entities: vec![DynamicEntity {
+                entity: scene_entity,
+                components: vec![Box::new(Blueprint::new(Rect { size: 3. }))],
+            }],
+        };
+        write_blueprint_scene(app.world_mut(), &scene, &mut entity_map).unwrap();
+        app.update();
+        assert_eq!(app.world().get::<RectSize>(entity).unwrap().0, 3.);
+    }
+
+    // registered for reflection, but without a plugin
+    #[derive(Default, Reflect)]
+    struct Unplugged;
+
+    #[derive(Component)]
+    struct Placeholder(String);
+
+    impl FromBlueprint<FallbackBlueprint> for Placeholder {
+        type Params<'w, 's> = ();
+        fn from_blueprint(
+            blueprint: &FallbackBlueprint,
+            _: &mut StaticSystemParam<Self::Params<'_, '_>>,
+        ) -> Self {
+            Placeholder(blueprint.label())
+        }
+    }
+
+    #[test]
+    fn unknown_blueprints_get_a_fallback() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Rect, RectSize>::default())
+            .add_plugins(BlueprintPlugin::<FallbackBlueprint, Placeholder>::default())
+            .register_type::<Blueprint<Unplugged>>();
+        let (known, unknown) = (Entity::from_raw(0), Entity::from_raw(1));
+        let scene = DynamicScene {
+            resources: vec![],
+            entities: vec![
+                DynamicEntity {
+                    entity: known,
+                    components: vec![Box::new(Blueprint::new(Rect { size: 2. }))],
+                },
+                DynamicEntity {
+                    entity: unknown,
+                    components: vec![
+                        Box::new(Blueprint::new(Removed)),
+                        Box::new(Blueprint::new(Unplugged)),
+                        // not a blueprint
+                        Box::new(Name::new("tower")),
+                    ],
+                },
+            ],
+        };
+        let mut entity_map = EntityHashMap::default();
+        write_blueprint_scene(app.world_mut(), &scene, &mut entity_map).unwrap();
+        app.update();
+        assert!(app.world().get::<Placeholder>(entity_map[&known]).is_none());
+        assert_eq!(
+            app.world()
+                .get::<Placeholder>(entity_map[&unknown])
+                .unwrap()
+                .0,
+            "Blueprint<Removed>, Blueprint<Unplugged>"
+        );
+    }
+
+    #[derive(Component)]
+    struct Health(u32);
+
+    fn rect(id: u32, size: f32) -> DynamicEntity {
+        DynamicEntity {
+            entity: Entity::from_raw(id),
+            components: vec![
+                Box::new(Blueprint::new(Rect { size })),
+                Box::new(BlueprintSceneId(id.into())),
+            ],
+        }
+    }
+
+    fn find(app: &mut App, id: u64) -> Vec<Entity> {
+        let mut query = app.world_mut().query::<(Entity, &BlueprintSceneId)>();
+        query
+            .iter(app.world())
+            .filter(|(_, scene_id)| scene_id.0 == id)
+            .map(|(entity, _)| entity)
+            .collect()
+    }
+
+    fn builds(app: &App, entity: Entity) -> u32 {
+        app.world()
+            .get::<BlueprintBuildStats>(entity)
+            .unwrap()
+            .count
+    }
+
+    #[test]
+    fn merged_scenes_only_touch_changed_blueprints() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Rect, RectSize>::default());
+        let scene = |entities| DynamicScene {
+            resources: vec![],
+            entities,
+        };
+        merge_blueprint_scene(
+            app.world_mut(),
+            &scene(vec![rect(1, 1.), rect(2, 1.), rect(3, 1.)]),
+        )
+        .unwrap();
+        app.update();
+        let (kept, edited, removed) = (
+            find(&mut app, 1)[0],
+            find(&mut app, 2)[0],
+            find(&mut app, 3)[0],
+        );
+        app.world_mut().entity_mut(kept).insert(Health(5));
+
+        // a lightly edited scene, with a copied entity
+        merge_blueprint_scene(
+            app.world_mut(),
+            &scene(vec![
+                rect(1, 1.),
+                rect(2, 2.),
+                rect(4, 1.),
+                DynamicEntity {
+                    entity: Entity::from_raw(5),
+                    ..rect(4, 1.)
+                },
+            ]),
+        )
+        .unwrap();
+        app.update();
+        assert_eq!(builds(&app, kept), 1);
+        assert_eq!(app.world().get::<Health>(kept).unwrap().0, 5);
+        assert_eq!(builds(&app, edited), 2);
+        assert_eq!(app.world().get::<RectSize>(edited).unwrap().0, 2.);
+        assert!(app.world().get_entity(removed).is_none());
+        let added = find(&mut app, 4);
+        assert_eq!(added.len(), 1);
+        assert_eq!(app.world().get::<RectSize>(added[0]).unwrap().0, 1.);
+        assert_eq!(
+            app.world_mut()
+                .query::<&RectSize>()
+                .iter(app.world())
+                .count(),
+            4
+        );
+    }
+
+    #[test]
+    fn scenes_are_diffed_by_id() {
+        let unidentified = |size: f32, removed: bool| {
+            let mut components: Vec<Box<dyn Reflect>> =
+                vec![Box::new(Blueprint::new(Rect { size }))];
+            if removed {
+                components.push(Box::new(Blueprint::new(Removed)));
+            }
+            DynamicEntity {
+                entity: Entity::from_raw(10),
+                components,
+            }
+        };
+        let scene = |entities| DynamicScene {
+            resources: vec![],
+            entities,
+        };
+        let old = scene(vec![
+            rect(1, 1.),
+            rect(2, 1.),
+            rect(3, 1.),
+            unidentified(5., false),
+        ]);
+        let new = scene(vec![
+            rect(1, 1.),
+            rect(2, 2.),
+            rect(4, 1.),
+            unidentified(6., true),
+        ]);
+        let rect_path = Blueprint::<Rect>::type_path().to_string();
+        assert_eq!(
+            diff_scenes(&old, &new),
+            SceneDiff {
+                added: vec![SceneEntityKey::Id(BlueprintSceneId(4))],
+                removed: vec![SceneEntityKey::Id(BlueprintSceneId(3))],
+                changed: vec![
+                    ChangedSceneEntity {
+                        entity: SceneEntityKey::Id(BlueprintSceneId(2)),
+                        added: vec![],
+                        removed: vec![],
+                        changed: vec![rect_path.clone()],
+                    },
+                    ChangedSceneEntity {
+                        entity: SceneEntityKey::Position(0),
+                        added: vec![Blueprint::<Removed>::type_path().to_string()],
+                        removed: vec![],
+                        changed: vec![rect_path],
+                    },
+                ],
+            }
+        );
+        assert!(diff_scenes(&old, &old).is_empty());
+    }
+}
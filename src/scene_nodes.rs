@@ -0,0 +1,155 @@
+use bevy::{
+    prelude::*,
+    scene::{SceneInstance, SceneSpawner},
+};
+
+use crate::{Blueprint, BlueprintsPlugin, BlueprintsSet};
+
+type InsertBlueprint = Box<dyn Fn(&mut EntityWorldMut) + Send + Sync>;
+
+/// Blueprints to insert on the nodes of a spawned scene by their `Name`, e.g. a `Blueprint<Spawner>`
+/// on the node named "SpawnPoint" of a glTF scene. Put it on the entity holding the scene's
+/// `SceneBundle`.
+///
+/// With `SceneNodeBlueprintsPlugin`, the blueprints are inserted on every matching descendant
+/// once the `SceneInstance` is ready, and the component is removed.
+#[derive(Component, Default)]
+pub struct SceneNodeBlueprints(Vec<(String, InsertBlueprint)>);
+
+impl SceneNodeBlueprints {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts `blueprint` on the nodes named `name` (a copy on each, if several match).
+    pub fn with<B>(mut self, name: impl Into<String>, blueprint: B) -> Self
+    where
+        B: Clone + Default + Send + Sync + 'static,
+    {
+        self.0.push((
+            name.into(),
+            Box::new(move |entity| {
+                entity.insert(Blueprint::new(blueprint.clone()));
+            }),
+        ));
+        self
+    }
+}
+
+/// Inserts the blueprints of `SceneNodeBlueprints` on the named nodes of their scenes, before
+/// `BlueprintsSet`, so they are built in the same frame.
+pub struct SceneNodeBlueprintsPlugin;
+
+impl Plugin for SceneNodeBlueprintsPlugin {
+    fn build(&self, app: &mut App) {
+        BlueprintsPlugin::add_once(app);
+        app.add_systems(Update, insert_scene_node_blueprints.before(BlueprintsSet));
+    }
+}
+
+fn insert_scene_node_blueprints(world: &mut World) {
+    let mut query = world.query_filtered::<(Entity, &SceneInstance), With<SceneNodeBlueprints>>();
+    let ready = {
+        let spawner = world.resource::<SceneSpawner>();
+        query
+            .iter(world)
+            // not spawned yet, so tried again next frame
+            .filter(|(_, instance)| spawner.instance_is_ready(***instance))
+            .map(|(entity, _)| entity)
+            .collect::<Vec<_>>()
+    };
+    for root in ready {
+        let Some(SceneNodeBlueprints(blueprints)) =
+            world.entity_mut(root).take::<SceneNodeBlueprints>()
+        else {
+            continue;
+        };
+        let mut stack = vec![root];
+        while let Some(parent) = stack.pop() {
+            let Some(children) = world.get::<Children>(parent) else {
+                continue;
+            };
+            let children = children.to_vec();
+            for child in children {
+                stack.push(child);
+                let Some(name) = world
+                    .get::<Name>(child)
+                    .map(|name| name.as_str().to_owned())
+                else {
+                    continue;
+                };
+                let mut entity_mut = world.entity_mut(child);
+                for (_, insert) in blueprints.iter().filter(|(node, _)| *node == name) {
+                    insert(&mut entity_mut);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{ecs::system::StaticSystemParam, prelude::*, scene::ScenePlugin};
+
+    use super::*;
+    use crate::{BlueprintPlugin, FromBlueprint};
+
+    #[derive(Clone, Default, Reflect)]
+    struct Spawner {
+        rate: f32,
+    }
+
+    #[derive(Component)]
+    struct SpawnTimer(f32);
+
+    impl FromBlueprint<Spawner> for SpawnTimer {
+        type Params<'w, 's> = ();
+        fn from_blueprint(
+            blueprint: &Spawner,
+            _: &mut StaticSystemParam<Self::Params<'_, '_>>,
+        ) -> Self {
+            SpawnTimer(blueprint.rate)
+        }
+    }
+
+    #[test]
+    fn named_nodes_get_blueprints() {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            HierarchyPlugin,
+            AssetPlugin::default(),
+            ScenePlugin,
+        ))
+        .add_plugins(SceneNodeBlueprintsPlugin)
+        .add_plugins(BlueprintPlugin::<Spawner, SpawnTimer>::default());
+        // like a loaded glTF scene
+        let mut scene = World::new();
+        scene.spawn(Name::new("Ground")).with_children(|ground| {
+            ground.spawn(Name::new("SpawnPoint"));
+            ground.spawn(Name::new("SpawnPoint"));
+        });
+        let scene = app
+            .world_mut()
+            .resource_mut::<Assets<Scene>>()
+            .add(Scene::new(scene));
+        app.world_mut().spawn((
+            SceneBundle { scene, ..default() },
+            SceneNodeBlueprints::new().with("SpawnPoint", Spawner { rate: 2. }),
+        ));
+        app.update();
+        app.update();
+
+        let mut query = app.world_mut().query::<(&Name, Option<&SpawnTimer>)>();
+        let nodes = query
+            .iter(app.world())
+            .map(|(name, timer)| (name.as_str(), timer.map(|timer| timer.0)))
+            .collect::<Vec<_>>();
+        assert_eq!(nodes.len(), 3);
+        for (name, rate) in nodes {
+            assert_eq!(rate, (name == "SpawnPoint").then_some(2.));
+        }
+        let mut query = app.world_mut().query::<&SceneNodeBlueprints>();
+        assert_eq!(query.iter(app.world()).count(), 0);
+    }
+}
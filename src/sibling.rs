@@ -0,0 +1,225 @@
+use std::marker::PhantomData;
+
+use bevy::{
+    ecs::{
+        component::{ComponentHooks, StorageType},
+        system::EntityCommands,
+    },
+    prelude::*,
+};
+
+use crate::{BlueprintSource, BlueprintTarget, IsBlueprint, TargetKind, TryFromBlueprint};
+
+/// Builds the prefab on a new top-level entity rather than on (or under) the blueprint entity,
+/// e.g. for a projectile trail that should not follow the projectile's transform.
+///
+/// The sibling is linked back to the blueprint entity with a `BlueprintSource`, and despawned when
+/// it is rebuilt, when the blueprint is removed, or when the blueprint entity is despawned, unless
+/// it has `PersistSibling`.
+pub struct AsSibling;
+
+/// Like `AsSibling`, but spawns the sibling as a child of the first entity with the component `R`
+/// (or as a top-level entity while there is none).
+pub struct AsSiblingUnder<R>(PhantomData<R>);
+
+/// Keeps a sibling spawned by `AsSibling` when it would be despawned, e.g. to let a trail fade out
+/// after its projectile is gone. Its `BlueprintSource` is removed instead, detaching it from the
+/// blueprint entity, and despawning it is then up to you.
+#[derive(Clone, Copy, Debug, Default, Component, Reflect)]
+#[reflect(Component)]
+pub struct PersistSibling;
+
+/// Records the sibling that the `AsSibling` prefab `P` of blueprint `B` was built on.
+///
+/// Removing this component, or despawning the blueprint entity, despawns the sibling.
+#[derive(Debug)]
+pub struct PrefabSibling<B, P> {
+    sibling: Entity,
+    marker: PhantomData<(B, P)>,
+}
+
+impl<B, P> PrefabSibling<B, P> {
+    pub fn sibling(&self) -> Entity {
+        self.sibling
+    }
+}
+
+impl<B: Send + Sync + 'static, P: Send + Sync + 'static> Component for PrefabSibling<B, P> {
+    const STORAGE_TYPE: StorageType = StorageType::Table;
+
+    fn register_component_hooks(hooks: &mut ComponentHooks) {
+        hooks.on_remove(|mut world, entity, _| {
+            let sibling = world.get::<Self>(entity).unwrap().sibling;
+            world.commands().add(move |world: &mut World| {
+                let Some(mut sibling) = world.get_entity_mut(sibling) else {
+                    return;
+                };
+                if sibling.contains::<PersistSibling>() {
+                    sibling.remove::<BlueprintSource>();
+                } else {
+                    sibling.despawn_recursive();
+                }
+            });
+        });
+    }
+}
+
+fn attach_sibling<T: Send + Sync + 'static, P: Bundle>(
+    entity: &mut EntityCommands,
+    bundle: P,
+) -> Entity {
+    let source = entity.id();
+    let sibling = entity
+        .commands()
+        .spawn((bundle, BlueprintSource(source)))
+        .id();
+    entity.insert((
+        IsBlueprint,
+        PrefabSibling::<T, P> {
+            sibling,
+            marker: PhantomData,
+        },
+    ));
+    sibling
+}
+
+impl BlueprintTarget for AsSibling {
+    const KIND: TargetKind = TargetKind::AsSibling;
+
+    fn remove_prefab<T: Send + Sync + 'static, P: Bundle + TryFromBlueprint<T>>(
+        entity: &mut EntityCommands,
+    ) {
+        entity.remove::<(IsBlueprint, PrefabSibling<T, P>)>();
+    }
+
+    fn attach_prefab<T: Send + Sync + 'static, P: Bundle + TryFromBlueprint<T>>(
+        entity: &mut EntityCommands,
+        bundle: P,
+    ) -> Option<Entity> {
+        Some(attach_sibling::<T, P>(entity, bundle))
+    }
+}
+
+impl<R: Component> BlueprintTarget for AsSiblingUnder<R> {
+    const KIND: TargetKind = TargetKind::AsSibling;
+
+    fn remove_prefab<T: Send + Sync + 'static, P: Bundle + TryFromBlueprint<T>>(
+        entity: &mut EntityCommands,
+    ) {
+        AsSibling::remove_prefab::<T, P>(entity);
+    }
+
+    fn attach_prefab<T: Send + Sync + 'static, P: Bundle + TryFromBlueprint<T>>(
+        entity: &mut EntityCommands,
+        bundle: P,
+    ) -> Option<Entity> {
+        let sibling = attach_sibling::<T, P>(entity, bundle);
+        entity.commands().add(move |world: &mut World| {
+            let root = world.query_filtered::<Entity, With<R>>().iter(world).next();
+            if let Some(root) = root {
+                world.entity_mut(sibling).set_parent(root);
+            }
+        });
+        Some(sibling)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{ecs::system::StaticSystemParam, prelude::*};
+
+    use super::*;
+    use crate::{Blueprint, BlueprintPlugin, BlueprintsPlugin, FromBlueprint};
+
+    #[derive(Default, Reflect)]
+    struct Projectile {
+        width: f32,
+    }
+
+    #[derive(Component)]
+    struct Trail(f32);
+
+    impl FromBlueprint<Projectile> for Trail {
+        type Params<'w, 's> = ();
+        fn from_blueprint(
+            blueprint: &Projectile,
+            _: &mut StaticSystemParam<Self::Params<'_, '_>>,
+        ) -> Self {
+            Trail(blueprint.width)
+        }
+    }
+
+    #[derive(Component)]
+    struct Effects;
+
+    fn trails(app: &mut App) -> Vec<(Entity, Option<Entity>)> {
+        app.world_mut()
+            .query_filtered::<(Entity, Option<&Parent>), With<Trail>>()
+            .iter(app.world())
+            .map(|(entity, parent)| (entity, parent.map(Parent::get)))
+            .collect()
+    }
+
+    #[test]
+    fn siblings_are_spawned_and_cleaned_up() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Projectile, Trail, AsSibling>::default());
+        let projectile = app
+            .world_mut()
+            .spawn(Blueprint::new(Projectile { width: 1. }))
+            .id();
+        app.update();
+        let [(trail, None)] = trails(&mut app)[..] else {
+            panic!("expected one top-level trail");
+        };
+        assert_eq!(
+            app.world().get::<BlueprintSource>(trail),
+            Some(&BlueprintSource(projectile))
+        );
+
+        // rebuilding replaces the sibling
+        app.world_mut()
+            .get_mut::<Blueprint<Projectile>>(projectile)
+            .unwrap()
+            .0
+            .width = 2.;
+        app.update();
+        let [(rebuilt, None)] = trails(&mut app)[..] else {
+            panic!("expected one top-level trail");
+        };
+        assert_ne!(rebuilt, trail);
+        assert_eq!(app.world().get::<Trail>(rebuilt).unwrap().0, 2.);
+
+        app.world_mut().despawn(projectile);
+        app.update();
+        assert!(trails(&mut app).is_empty());
+    }
+
+    #[test]
+    fn persisted_siblings_outlive_the_blueprint() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(
+                BlueprintPlugin::<Projectile, Trail, AsSiblingUnder<Effects>>::default()
+                    .with_child(|trail| {
+                        trail.insert(PersistSibling);
+                    }),
+            );
+        let effects = app.world_mut().spawn(Effects).id();
+        let projectile = app
+            .world_mut()
+            .spawn(Blueprint::new(Projectile::default()))
+            .id();
+        app.update();
+        let [(trail, Some(parent))] = trails(&mut app)[..] else {
+            panic!("expected one trail under the root");
+        };
+        assert_eq!(parent, effects);
+
+        app.world_mut().despawn(projectile);
+        app.update();
+        assert_eq!(trails(&mut app), vec![(trail, Some(effects))]);
+        assert!(app.world().get::<BlueprintSource>(trail).is_none());
+    }
+}
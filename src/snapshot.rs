@@ -0,0 +1,191 @@
+use bevy::{
+    asset::ron,
+    prelude::*,
+    reflect::{serde::TypedReflectSerializer, ReflectRef},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{blueprint_component_type_id, parse_raw_blueprint, BlueprintRegistry, RawBlueprint};
+
+/// The blueprints of every entity in a world, e.g. for a save game, taken with
+/// `snapshot_blueprints` and spawned again with `restore_blueprints`.
+///
+/// Only the `Blueprint<B>` components of types with a `BlueprintPlugin` are kept, as RON, so the
+/// snapshot can be serialized (e.g. with `ron::to_string`) without the rest of the world.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlueprintSnapshot {
+    /// The blueprints of each entity that has any.
+    pub entities: Vec<Vec<RawBlueprint>>,
+}
+
+/// Takes a snapshot of the blueprints of every entity, for all the registered blueprint types.
+///
+/// Blueprints that do not serialize (e.g. because a field type is not registered for reflection)
+/// are left out, with a warning.
+pub fn snapshot_blueprints(world: &World) -> BlueprintSnapshot {
+    let Some(registry) = world.get_resource::<BlueprintRegistry>() else {
+        return BlueprintSnapshot::default();
+    };
+    let type_registry = world.resource::<AppTypeRegistry>().read();
+    let registrations = registry
+        .iter_sorted()
+        .filter_map(|registration| {
+            let reflect_component =
+                type_registry.get_type_data::<ReflectComponent>(registration.component_type_id)?;
+            Some((registration.type_path, reflect_component))
+        })
+        .collect::<Vec<_>>();
+    let entities = world
+        .iter_entities()
+        .map(|entity_ref| {
+            registrations
+                .iter()
+                .filter_map(|(type_path, reflect_component)| {
+                    let component = reflect_component.reflect(entity_ref)?;
+                    let ReflectRef::TupleStruct(blueprint) = component.reflect_ref() else {
+                        return None;
+                    };
+                    let serializer =
+                        TypedReflectSerializer::new(blueprint.field(0)?, &type_registry);
+                    match ron::to_string(&serializer) {
+                        Ok(ron) => Some(RawBlueprint::new(*type_path, ron)),
+                        Err(error) => {
+                            warn!(
+                                "leaving the blueprint {} of entity {} out of the snapshot: {}",
+                                type_path,
+                                entity_ref.id(),
+                                error,
+                            );
+                            None
+                        }
+                    }
+                })
+                .collect::<Vec<_>>()
+        })
+        .filter(|blueprints| !blueprints.is_empty())
+        .collect();
+    BlueprintSnapshot { entities }
+}
+
+/// Spawns an entity for each entity of the snapshot, with its blueprints, which are built in the
+/// next `Update`. Returns the spawned entities, in the order of `BlueprintSnapshot::entities`.
+///
+/// Blueprints of types without a `BlueprintPlugin` in this world (e.g. from a save game of an
+/// older version) are skipped with a warning, as are those whose RON does not parse.
+pub fn restore_blueprints(world: &mut World, snapshot: &BlueprintSnapshot) -> Vec<Entity> {
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+    snapshot
+        .entities
+        .iter()
+        .map(|blueprints| {
+            let entity = world.spawn_empty().id();
+            for raw in blueprints {
+                let Some(reflect_component) = blueprint_component_type_id(world, &raw.type_path)
+                    .and_then(|component_type_id| {
+                        type_registry.get_type_data::<ReflectComponent>(component_type_id)
+                    })
+                else {
+                    warn!(
+                        "skipping the blueprint {} of the snapshot, which has no BlueprintPlugin",
+                        raw.type_path,
+                    );
+                    continue;
+                };
+                match parse_raw_blueprint(raw, &type_registry) {
+                    Ok(blueprint) => {
+                        reflect_component.insert(
+                            &mut world.entity_mut(entity),
+                            &blueprint,
+                            &type_registry,
+                        );
+                    }
+                    Err(error) => {
+                        warn!(
+                            "skipping the blueprint {} of the snapshot: {}",
+                            raw.type_path, error,
+                        );
+                    }
+                }
+            }
+            entity
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{ecs::system::StaticSystemParam, prelude::*};
+
+    use super::*;
+    use crate::{Blueprint, BlueprintPlugin, BlueprintsPlugin, FromBlueprint};
+
+    #[derive(Default, Reflect)]
+    struct Rect {
+        size: f32,
+    }
+
+    #[derive(Component)]
+    struct RectSize(f32);
+
+    impl FromBlueprint<Rect> for RectSize {
+        type Params<'w, 's> = ();
+        fn from_blueprint(
+            blueprint: &Rect,
+            _: &mut StaticSystemParam<Self::Params<'_, '_>>,
+        ) -> Self {
+            RectSize(blueprint.size)
+        }
+    }
+
+    #[derive(Default, Reflect)]
+    struct Light {
+        brightness: f32,
+    }
+
+    #[derive(Component)]
+    struct Glow;
+
+    impl FromBlueprint<Light> for Glow {
+        type Params<'w, 's> = ();
+        fn from_blueprint(_: &Light, _: &mut StaticSystemParam<Self::Params<'_, '_>>) -> Self {
+            Glow
+        }
+    }
+
+    #[test]
+    fn snapshots_restore_into_a_fresh_world() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Rect, RectSize>::default())
+            .add_plugins(BlueprintPlugin::<Light, Glow>::default());
+        app.world_mut().spawn((
+            Blueprint::new(Rect { size: 2. }),
+            Blueprint::new(Light { brightness: 0.5 }),
+        ));
+        app.world_mut().spawn(Blueprint::new(Rect { size: 3. }));
+        app.update();
+        let snapshot = snapshot_blueprints(app.world());
+        assert_eq!(snapshot.entities.len(), 2);
+        // e.g. written to a save file
+        let saved = ron::to_string(&snapshot).unwrap();
+
+        // an older version of the game, without lights
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Rect, RectSize>::default());
+        let snapshot = ron::from_str::<BlueprintSnapshot>(&saved).unwrap();
+        let entities = restore_blueprints(app.world_mut(), &snapshot);
+        app.update();
+        let mut sizes = entities
+            .iter()
+            .map(|entity| app.world().get::<RectSize>(*entity).unwrap().0)
+            .collect::<Vec<_>>();
+        sizes.sort_by(f32::total_cmp);
+        assert_eq!(sizes, vec![2., 3.]);
+        assert_eq!(
+            app.world_mut().query::<&Glow>().iter(app.world()).count(),
+            0
+        );
+    }
+}
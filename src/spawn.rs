@@ -0,0 +1,113 @@
+use bevy::prelude::*;
+
+use crate::{Blueprint, BlueprintsPlugin, BlueprintsSet};
+
+/// Spawns a blueprint entity with a `BlueprintBundle` of `data` at `transform`, for apps that
+/// called `add_blueprint_spawn_events::<B>()`, so that gameplay code can spawn blueprints
+/// without `Commands`.
+#[derive(Debug, Event)]
+pub struct SpawnBlueprint<B: Send + Sync + 'static> {
+    pub data: B,
+    pub transform: Transform,
+}
+
+impl<B: Send + Sync + 'static> SpawnBlueprint<B> {
+    pub fn new(data: B) -> Self {
+        SpawnBlueprint {
+            data,
+            transform: Transform::default(),
+        }
+    }
+
+    pub fn with_transform(mut self, transform: Transform) -> Self {
+        self.transform = transform;
+        self
+    }
+}
+
+pub trait BlueprintSpawnEventsExt {
+    /// Adds the `SpawnBlueprint<B>` event, and spawns the blueprint of every event sent since the
+    /// last frame right before `BlueprintsSet`, so that they are built in the same `Update`.
+    fn add_blueprint_spawn_events<B>(&mut self) -> &mut Self
+    where
+        B: Default + Send + Sync + 'static;
+}
+
+impl BlueprintSpawnEventsExt for App {
+    fn add_blueprint_spawn_events<B>(&mut self) -> &mut Self
+    where
+        B: Default + Send + Sync + 'static,
+    {
+        BlueprintsPlugin::add_once(self);
+        self.add_event::<SpawnBlueprint<B>>()
+            .add_systems(Update, spawn_blueprints::<B>.before(BlueprintsSet))
+    }
+}
+
+// drains the events, so that the data is moved into the blueprints rather than cloned
+fn spawn_blueprints<B: Default + Send + Sync + 'static>(
+    mut commands: Commands,
+    mut events: ResMut<Events<SpawnBlueprint<B>>>,
+) {
+    for SpawnBlueprint { data, transform } in events.drain() {
+        commands.spawn(Blueprint::bundle(data).with_transform(transform));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{ecs::system::StaticSystemParam, prelude::*};
+
+    use super::*;
+    use crate::{BlueprintPlugin, FromBlueprint};
+
+    #[derive(Default, Reflect)]
+    struct Rock {
+        size: f32,
+    }
+
+    #[derive(Component)]
+    struct RockSize(f32);
+
+    impl FromBlueprint<Rock> for RockSize {
+        type Params<'w, 's> = ();
+        fn from_blueprint(
+            blueprint: &Rock,
+            _: &mut StaticSystemParam<Self::Params<'_, '_>>,
+        ) -> Self {
+            RockSize(blueprint.size)
+        }
+    }
+
+    #[test]
+    fn spawn_events_spawn_blueprints() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_blueprint_spawn_events::<Rock>()
+            .add_plugins(BlueprintPlugin::<Rock, RockSize>::default());
+        for size in [1., 2., 3.] {
+            app.world_mut().send_event(
+                SpawnBlueprint::new(Rock { size })
+                    .with_transform(Transform::from_xyz(size, 0., 0.)),
+            );
+        }
+        app.update();
+        let mut rocks = app
+            .world_mut()
+            .query::<(&RockSize, &Transform)>()
+            .iter(app.world())
+            .map(|(size, transform)| (size.0, transform.translation.x))
+            .collect::<Vec<_>>();
+        rocks.sort_by(|a, b| a.0.total_cmp(&b.0));
+        assert_eq!(rocks, vec![(1., 1.), (2., 2.), (3., 3.)]);
+
+        // each event spawns once
+        app.update();
+        let count = app
+            .world_mut()
+            .query::<&RockSize>()
+            .iter(app.world())
+            .count();
+        assert_eq!(count, 3);
+    }
+}
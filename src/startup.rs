@@ -0,0 +1,129 @@
+use bevy::{app::MainScheduleOrder, ecs::schedule::ScheduleLabel, prelude::*};
+
+use crate::{
+    apply_blueprint_transactions, BlueprintRegistry, BlueprintsBaked, BlueprintsPaused,
+    BlueprintsPlugin,
+};
+
+/// Builds every blueprint that changed since it was last built, right away rather than in the next
+/// `Update`, e.g. so that the blueprints spawned at startup are complete in the first frame.
+///
+/// The blueprints built here are not built again in `Update`, unless they change in the meantime.
+/// Plugins with `BlueprintPlugin::only_build_in_update` are left out, and blueprints spawned by
+/// the prefabs built here are built in `Update` as usual. Does nothing while blueprints are paused
+/// or baked.
+pub fn build_blueprints_now(world: &mut World) {
+    let paused = world
+        .get_resource::<BlueprintsPaused>()
+        .is_some_and(|paused| paused.0);
+    if paused || world.contains_resource::<BlueprintsBaked>() {
+        return;
+    }
+    let Some(registry) = world.get_resource::<BlueprintRegistry>() else {
+        return;
+    };
+    let build_nows = registry
+        .iter()
+        .flat_map(|registration| &registration.prefabs)
+        .filter_map(|prefab| prefab.build_now)
+        .collect::<Vec<_>>();
+    for build_now in build_nows {
+        build_now(world);
+    }
+    apply_blueprint_transactions(world);
+}
+
+/// The startup schedule added by `BlueprintStartupPlugin`, which runs `build_blueprints_now`
+/// between `Startup` and `PostStartup`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, ScheduleLabel)]
+pub struct BlueprintStartup;
+
+/// Builds the blueprints spawned during `Startup` before `PostStartup`, so that they are complete
+/// in the first frame instead of being built in the first `Update`.
+pub struct BlueprintStartupPlugin;
+
+impl Plugin for BlueprintStartupPlugin {
+    fn build(&self, app: &mut App) {
+        BlueprintsPlugin::add_once(app);
+        app.add_systems(BlueprintStartup, build_blueprints_now);
+        app.world_mut()
+            .resource_mut::<MainScheduleOrder>()
+            .insert_startup_after(Startup, BlueprintStartup);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{ecs::system::StaticSystemParam, prelude::*};
+
+    use super::*;
+    use crate::{Blueprint, BlueprintPlugin, FromBlueprint};
+
+    #[derive(Default, Reflect)]
+    struct Rect {
+        size: f32,
+    }
+
+    #[derive(Component)]
+    struct RectSize(f32);
+
+    impl FromBlueprint<Rect> for RectSize {
+        type Params<'w, 's> = ResMut<'w, BuildCount>;
+        fn from_blueprint(
+            blueprint: &Rect,
+            count: &mut StaticSystemParam<Self::Params<'_, '_>>,
+        ) -> Self {
+            count.0 += 1;
+            RectSize(blueprint.size)
+        }
+    }
+
+    // only inserted once the app is running
+    #[derive(Resource)]
+    struct Theme(Color);
+
+    #[derive(Component)]
+    struct RectColor(Color);
+
+    impl FromBlueprint<Rect> for RectColor {
+        type Params<'w, 's> = Res<'w, Theme>;
+        fn from_blueprint(_: &Rect, theme: &mut StaticSystemParam<Self::Params<'_, '_>>) -> Self {
+            RectColor(theme.0)
+        }
+    }
+
+    #[derive(Default, Resource)]
+    struct BuildCount(usize);
+
+    #[derive(Default, Resource)]
+    struct BuiltAtPostStartup(bool);
+
+    #[test]
+    fn startup_blueprints_build_before_post_startup() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintStartupPlugin))
+            .add_plugins(BlueprintPlugin::<Rect, RectSize>::default())
+            .add_plugins(BlueprintPlugin::<Rect, RectColor>::default().only_build_in_update())
+            .init_resource::<BuildCount>()
+            .init_resource::<BuiltAtPostStartup>()
+            .add_systems(Startup, |mut commands: Commands| {
+                commands.spawn(Blueprint::new(Rect { size: 2. }));
+            })
+            .add_systems(
+                PostStartup,
+                |query: Query<&RectSize>, mut built: ResMut<BuiltAtPostStartup>| {
+                    built.0 = query.iter().any(|size| size.0 == 2.);
+                },
+            )
+            .add_systems(PreUpdate, |mut commands: Commands| {
+                commands.insert_resource(Theme(Color::BLACK));
+            });
+        app.update();
+        assert!(app.world().resource::<BuiltAtPostStartup>().0);
+
+        // not built again in `Update`, where the prefab left out of the startup build is built
+        assert_eq!(app.world().resource::<BuildCount>().0, 1);
+        let mut colors = app.world_mut().query::<&RectColor>();
+        assert_eq!(colors.single(app.world()).0, Color::BLACK);
+    }
+}
@@ -0,0 +1,432 @@
+use std::time::Duration;
+
+use bevy::{
+    diagnostic::DiagnosticPath,
+    ecs::{
+        entity::Entities,
+        system::{Deferred, SystemBuffer, SystemMeta, SystemParam},
+        world::CommandQueue,
+    },
+    prelude::*,
+    utils::HashMap,
+};
+
+use crate::{BlueprintRegistry, SyncTime};
+
+/// Groups blueprint entities that must be built in the same frame: the targets of every member
+/// appear together, or not at all.
+///
+/// If a member fails to build, the whole transaction is retried on the next frame, and while a
+/// member has not been built yet (e.g. it is gated, or waits for a dependency) the builds of the
+/// others are held. Once it has been retried or held for `BlueprintTransactions::timeout`, the
+/// members that did build are committed with a warning.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Component, Reflect)]
+#[reflect(Component)]
+pub struct BlueprintTransaction(pub u64);
+
+/// The transactions waiting for all of their members to build.
+#[derive(Resource)]
+pub struct BlueprintTransactions {
+    /// How long to retry or hold an incomplete transaction before committing the members that
+    /// built.
+    pub timeout: Duration,
+    pending: HashMap<BlueprintTransaction, PendingTransaction>,
+}
+
+impl Default for BlueprintTransactions {
+    fn default() -> Self {
+        BlueprintTransactions {
+            timeout: Duration::from_secs(1),
+            pending: HashMap::default(),
+        }
+    }
+}
+
+// builds the blueprint of an entity again, for the plugin that attempted it (`rebuild_prefabs`
+// for a single prefab)
+type RebuildFn = fn(&mut World, Entity);
+
+#[derive(Default)]
+struct PendingTransaction {
+    // the build commands of the members, held until every member has built
+    commands: CommandQueue,
+    // with the request to build the member again from the plugin that attempted it
+    attempted: Vec<(Entity, RebuildFn)>,
+    failed: Vec<Entity>,
+    since: Option<Duration>,
+}
+
+impl PendingTransaction {
+    fn merge(&mut self, mut other: PendingTransaction) {
+        self.commands.append(&mut other.commands);
+        self.attempted.append(&mut other.attempted);
+        self.failed.append(&mut other.failed);
+    }
+}
+
+// the builds of transaction members in one sync system, moved to `BlueprintTransactions` when
+// commands are applied (so that sync systems do not all need access to the resource)
+#[derive(Default)]
+struct TransactionBuffer(HashMap<BlueprintTransaction, PendingTransaction>);
+
+impl SystemBuffer for TransactionBuffer {
+    fn apply(&mut self, _: &SystemMeta, world: &mut World) {
+        if self.0.is_empty() {
+            return;
+        }
+        let mut transactions = world.resource_mut::<BlueprintTransactions>();
+        for (transaction, pending) in self.0.drain() {
+            transactions
+                .pending
+                .entry(transaction)
+                .or_default()
+                .merge(pending);
+        }
+    }
+}
+
+// the commands used to build a blueprint entity, which are held back for transaction members
+#[derive(SystemParam)]
+pub(crate) struct SyncCommands<'w, 's> {
+    commands: Commands<'w, 's>,
+    entities: &'w Entities,
+    buffer: Deferred<'s, TransactionBuffer>,
+    members: Query<'w, 's, &'static BlueprintTransaction>,
+    sync_time: Deferred<'s, SyncTime>,
+}
+
+impl<'w, 's> SyncCommands<'w, 's> {
+    pub(crate) fn commands(&mut self) -> Commands<'w, '_> {
+        self.commands.reborrow()
+    }
+
+    pub(crate) fn is_member(&self, entity: Entity) -> bool {
+        self.members.contains(entity)
+    }
+
+    // whether the builds of the entity are held until its transaction commits, which they are not
+    // when it is built `alone` (by `materialize_blueprint_entity`)
+    pub(crate) fn is_held(&self, entity: Entity, alone: bool) -> bool {
+        !alone && self.is_member(entity)
+    }
+
+    pub(crate) fn exists(&self, entity: Entity) -> bool {
+        self.entities.contains(entity)
+    }
+
+    // runs `build` with the commands of the sync system, or holds it back until the transaction of
+    // a member commits: its entities are only reserved then, so a retry that discards the builds
+    // leaves none behind. `rebuild` requests the build again if the transaction is retried
+    pub(crate) fn build(
+        &mut self,
+        entity: Entity,
+        alone: bool,
+        rebuild: RebuildFn,
+        build: impl FnOnce(&mut Commands) + Send + 'static,
+    ) {
+        let transaction = self.members.get(entity).ok().filter(|_| !alone);
+        let Some(transaction) = transaction else {
+            build(&mut self.commands);
+            return;
+        };
+        let pending = self.buffer.0.entry(*transaction).or_default();
+        pending.attempted.push((entity, rebuild));
+        pending.commands.push(move |world: &mut World| {
+            // the member may have been despawned while its build was held
+            if world.get_entity(entity).is_none() {
+                return;
+            }
+            let mut queue = CommandQueue::default();
+            build(&mut Commands::new(&mut queue, world));
+            queue.apply(world);
+        });
+    }
+
+    pub(crate) fn build_failed(&mut self, entity: Entity, alone: bool) {
+        if let Some(transaction) = self.members.get(entity).ok().filter(|_| !alone) {
+            let pending = self.buffer.0.entry(*transaction).or_default();
+            pending.failed.push(entity);
+        }
+    }
+
+    // measured once the commands are applied, see `BlueprintsPlugin::sync_diagnostic`
+    pub(crate) fn record_sync_time(&mut self, path: &DiagnosticPath, elapsed: Duration) {
+        self.sync_time.0 = Some((path.clone(), elapsed));
+    }
+}
+
+// the members of each transaction that have a blueprint left to build
+fn unbuilt_members(world: &mut World) -> HashMap<BlueprintTransaction, Vec<Entity>> {
+    let pending = world
+        .get_resource::<BlueprintRegistry>()
+        .map(|registry| {
+            registry
+                .iter()
+                .map(|registration| registration.pending)
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    let mut unbuilt = HashMap::<BlueprintTransaction, Vec<Entity>>::default();
+    let mut members = world.query::<(Entity, &BlueprintTransaction)>();
+    for (entity, transaction) in members.iter(world) {
+        if pending.iter().any(|pending| pending(world, entity)) {
+            unbuilt.entry(*transaction).or_default().push(entity);
+        }
+    }
+    unbuilt
+}
+
+/// Commits the transactions whose members have all built, retries those with a failed member,
+/// and holds the others until their last members build.
+pub(crate) fn apply_blueprint_transactions(world: &mut World) {
+    let now = world
+        .get_resource::<Time>()
+        .map(Time::elapsed)
+        .unwrap_or_default();
+    let mut unbuilt = unbuilt_members(world);
+    let mut transactions = world.resource_mut::<BlueprintTransactions>();
+    let timeout = transactions.timeout;
+    let mut committed = Vec::new();
+    let mut retried = Vec::new();
+    transactions.pending.retain(|transaction, pending| {
+        // the members are gone, or have not been rebuilt
+        if pending.attempted.is_empty() {
+            return false;
+        }
+        let since = *pending.since.get_or_insert(now);
+        // members that were built in a previous transaction are complete, and members whose build
+        // is held here are not built yet
+        let mut waiting = unbuilt.remove(transaction).unwrap_or_default();
+        waiting.retain(|entity| !pending.attempted.iter().any(|(member, _)| member == entity));
+        if pending.failed.is_empty() && waiting.is_empty() {
+            committed.push(std::mem::take(&mut pending.commands));
+            return false;
+        }
+        if now.saturating_sub(since) >= timeout {
+            warn!(
+                "committing {:?} without the members that failed to build: {:?}",
+                transaction,
+                pending.failed.iter().chain(&waiting).collect::<Vec<_>>()
+            );
+            committed.push(std::mem::take(&mut pending.commands));
+            return false;
+        }
+        // held until the waiting members build
+        if pending.failed.is_empty() {
+            return true;
+        }
+        // dropping the commands discards the builds, which have not spawned anything yet
+        pending.commands = CommandQueue::default();
+        pending.failed.clear();
+        retried.append(&mut pending.attempted);
+        true
+    });
+
+    for mut commands in committed {
+        commands.apply(world);
+    }
+    for (entity, rebuild) in retried {
+        rebuild(world, entity);
+    }
+}
+
+/// Applies every pending transaction, whether or not all of its members built.
+pub(crate) fn commit_blueprint_transactions(world: &mut World) {
+    let Some(mut transactions) = world.get_resource_mut::<BlueprintTransactions>() else {
+        return;
+    };
+    let pending = std::mem::take(&mut transactions.pending);
+    for (_, mut pending) in pending {
+        pending.commands.apply(world);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{ecs::system::StaticSystemParam, prelude::*, time::TimeUpdateStrategy};
+
+    use super::*;
+    use crate::{AsChild, Blueprint, BlueprintPlugin, BlueprintsPlugin, TryFromBlueprint};
+
+    #[derive(Default, Reflect)]
+    struct Soldier {
+        rank: u32,
+    }
+
+    #[derive(Resource)]
+    struct ReadyRank(u32);
+
+    #[derive(Component)]
+    struct SoldierMesh;
+
+    // soldiers can only be built once their rank is ready
+    impl TryFromBlueprint<Soldier> for SoldierMesh {
+        type Params<'w, 's> = Res<'w, ReadyRank>;
+        type Error = &'static str;
+        fn try_from_blueprint(
+            blueprint: &Soldier,
+            ready: &mut StaticSystemParam<Self::Params<'_, '_>>,
+        ) -> Result<Self, Self::Error> {
+            if blueprint.rank <= ready.0 {
+                Ok(SoldierMesh)
+            } else {
+                Err("rank not ready")
+            }
+        }
+    }
+
+    #[derive(Component)]
+    struct SoldierBadge;
+
+    // spawned as a child, once the rank is ready
+    impl TryFromBlueprint<Soldier> for SoldierBadge {
+        type Params<'w, 's> = Res<'w, ReadyRank>;
+        type Error = &'static str;
+        fn try_from_blueprint(
+            blueprint: &Soldier,
+            ready: &mut StaticSystemParam<Self::Params<'_, '_>>,
+        ) -> Result<Self, Self::Error> {
+            SoldierMesh::try_from_blueprint(blueprint, ready).map(|_| SoldierBadge)
+        }
+    }
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Soldier, SoldierMesh>::default())
+            .insert_resource(ReadyRank(0))
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+                250,
+            )));
+        app
+    }
+
+    fn spawn_squad(app: &mut App) -> Vec<Entity> {
+        (0..3)
+            .map(|rank| {
+                app.world_mut()
+                    .spawn((Blueprint::new(Soldier { rank }), BlueprintTransaction(1)))
+                    .id()
+            })
+            .collect()
+    }
+
+    fn built(app: &App, squad: &[Entity]) -> Vec<bool> {
+        squad
+            .iter()
+            .map(|entity| app.world().entity(*entity).contains::<SoldierMesh>())
+            .collect()
+    }
+
+    #[test]
+    fn transaction_builds_together() {
+        let mut app = app();
+        let squad = spawn_squad(&mut app);
+        let loner = app
+            .world_mut()
+            .spawn(Blueprint::new(Soldier { rank: 0 }))
+            .id();
+        app.update();
+        assert_eq!(built(&app, &squad), vec![false; 3]);
+        assert!(app.world().entity(loner).contains::<SoldierMesh>());
+
+        app.world_mut().resource_mut::<ReadyRank>().0 = 1;
+        app.update();
+        assert_eq!(built(&app, &squad), vec![false; 3]);
+
+        app.world_mut().resource_mut::<ReadyRank>().0 = 2;
+        app.update();
+        assert_eq!(built(&app, &squad), vec![true; 3]);
+    }
+
+    #[test]
+    fn retried_transactions_spawn_no_entities() {
+        let mut app = app();
+        app.add_plugins(BlueprintPlugin::<Soldier, SoldierBadge, AsChild>::default());
+        let squad = spawn_squad(&mut app);
+        let entities = app.world().entities().len();
+        // the badge of the first soldier is built, and discarded, in every retry
+        for _ in 0..3 {
+            app.update();
+            assert_eq!(app.world().entities().len(), entities);
+        }
+
+        app.world_mut().resource_mut::<ReadyRank>().0 = 2;
+        app.update();
+        assert_eq!(built(&app, &squad), vec![true; 3]);
+        let mut badges = app
+            .world_mut()
+            .query_filtered::<&Parent, With<SoldierBadge>>();
+        assert_eq!(badges.iter(app.world()).count(), 3);
+        assert_eq!(app.world().entities().len(), entities + 3);
+    }
+
+    #[test]
+    fn added_only_transactions_are_retried() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Soldier, SoldierMesh>::default().added_only())
+            .insert_resource(ReadyRank(0))
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+                250,
+            )));
+        let squad = spawn_squad(&mut app);
+        app.update();
+        assert_eq!(built(&app, &squad), vec![false; 3]);
+
+        app.world_mut().resource_mut::<ReadyRank>().0 = 2;
+        app.update();
+        assert_eq!(built(&app, &squad), vec![true; 3]);
+    }
+
+    #[test]
+    fn transactions_wait_for_unbuilt_members() {
+        #[derive(Component)]
+        struct Deployed;
+
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Soldier, SoldierMesh>::default().gated_by::<Deployed>())
+            .insert_resource(ReadyRank(2))
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+                250,
+            )));
+        let squad = spawn_squad(&mut app);
+        for &soldier in &squad[..2] {
+            app.world_mut().entity_mut(soldier).insert(Deployed);
+        }
+        app.update();
+        app.update();
+        assert_eq!(built(&app, &squad), vec![false; 3]);
+
+        // the held builds are committed with the last member
+        app.world_mut().entity_mut(squad[2]).insert(Deployed);
+        app.update();
+        assert_eq!(built(&app, &squad), vec![true; 3]);
+
+        // a member that never builds holds the others until the timeout
+        let squad = spawn_squad(&mut app);
+        for &soldier in &squad[..2] {
+            app.world_mut().entity_mut(soldier).insert(Deployed);
+        }
+        for _ in 0..4 {
+            app.update();
+            assert_eq!(built(&app, &squad), vec![false; 3]);
+        }
+        app.update();
+        assert_eq!(built(&app, &squad), vec![true, true, false]);
+    }
+
+    #[test]
+    fn incomplete_transaction_times_out() {
+        let mut app = app();
+        app.world_mut().resource_mut::<ReadyRank>().0 = 1;
+        let squad = spawn_squad(&mut app);
+        for _ in 0..4 {
+            app.update();
+            assert_eq!(built(&app, &squad), vec![false; 3]);
+        }
+        app.update();
+        assert_eq!(built(&app, &squad), vec![true, true, false]);
+    }
+}
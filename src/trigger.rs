@@ -0,0 +1,99 @@
+use bevy::{ecs::query::QueryFilter, prelude::*};
+
+use crate::Blueprint;
+
+/// Decides which blueprints a `BlueprintPlugin` builds, with `BlueprintPlugin::trigger`: every
+/// blueprint whose entity matches `Filter` is (re)built.
+///
+/// `ChangedTrigger` is the default. Cleanup does not depend on the trigger: removing the blueprint
+/// (or despawning its entity) always cleans up its target.
+pub trait BlueprintTrigger<B>: Send + Sync + 'static {
+    type Filter: QueryFilter + 'static;
+}
+
+/// Builds blueprints whenever they change, including when they are added.
+pub struct ChangedTrigger;
+
+impl<B: Default + Send + Sync + 'static> BlueprintTrigger<B> for ChangedTrigger {
+    type Filter = Changed<Blueprint<B>>;
+}
+
+/// Builds blueprints only when they are added, as with `BlueprintPlugin::added_only`.
+pub struct AddedTrigger;
+
+impl<B: Default + Send + Sync + 'static> BlueprintTrigger<B> for AddedTrigger {
+    type Filter = Added<Blueprint<B>>;
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{ecs::system::StaticSystemParam, prelude::*};
+
+    use crate::{BlueprintPlugin, BlueprintsPlugin, FromBlueprint};
+
+    use super::*;
+
+    #[derive(Default, Reflect)]
+    struct Lamp {
+        brightness: f32,
+    }
+
+    #[derive(Component)]
+    struct Light(f32);
+
+    impl FromBlueprint<Lamp> for Light {
+        type Params<'w, 's> = ();
+        fn from_blueprint(
+            blueprint: &Lamp,
+            _: &mut StaticSystemParam<Self::Params<'_, '_>>,
+        ) -> Self {
+            Light(blueprint.brightness)
+        }
+    }
+
+    // lamps are only rebuilt when marked dirty
+    #[derive(Component)]
+    struct Dirty;
+
+    struct DirtyTrigger;
+
+    impl BlueprintTrigger<Lamp> for DirtyTrigger {
+        type Filter = Or<(Added<Blueprint<Lamp>>, Added<Dirty>)>;
+    }
+
+    fn brightness(app: &App, entity: Entity) -> Option<f32> {
+        app.world().get::<Light>(entity).map(|light| light.0)
+    }
+
+    #[test]
+    fn custom_trigger() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Lamp, Light>::default().trigger::<DirtyTrigger>());
+        let entity = app
+            .world_mut()
+            .spawn(Blueprint::new(Lamp { brightness: 1. }))
+            .id();
+        app.update();
+        assert_eq!(brightness(&app, entity), Some(1.));
+
+        app.world_mut()
+            .get_mut::<Blueprint<Lamp>>(entity)
+            .unwrap()
+            .0
+            .brightness = 2.;
+        app.update();
+        assert_eq!(brightness(&app, entity), Some(1.));
+
+        app.world_mut().entity_mut(entity).insert(Dirty);
+        app.update();
+        assert_eq!(brightness(&app, entity), Some(2.));
+
+        // cleaned up regardless of the trigger
+        app.world_mut()
+            .entity_mut(entity)
+            .remove::<Blueprint<Lamp>>();
+        app.update();
+        assert_eq!(brightness(&app, entity), None);
+    }
+}
@@ -0,0 +1,59 @@
+use std::panic::AssertUnwindSafe;
+
+use bevy::{ecs::system::StaticSystemParam, prelude::*, utils::get_short_name};
+
+use crate::{PrefabArgs, TryFromBlueprint};
+
+// builds the prefab, logging which blueprint and entity it was built for if it panics, before
+// the panic continues; the params need not be unwind safe, since nothing recovers from it
+pub(crate) fn build_prefab<B, P: TryFromBlueprint<B>, Ar: PrefabArgs<B, P>>(
+    entity: Entity,
+    blueprint: &B,
+    args: Option<&Ar::Args>,
+    system_params: &mut StaticSystemParam<P::Params<'_, '_>>,
+) -> Result<P, P::Error> {
+    std::panic::catch_unwind(AssertUnwindSafe(|| {
+        Ar::build(blueprint, args, system_params)
+    }))
+    .unwrap_or_else(|payload| {
+        error!(
+            "blueprint {} on entity {} panicked while building {}; return an error from \
+             `TryFromBlueprint` instead to keep the app running",
+            get_short_name(std::any::type_name::<B>()),
+            entity,
+            get_short_name(std::any::type_name::<P>()),
+        );
+        std::panic::resume_unwind(payload)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{ecs::system::StaticSystemParam, prelude::*};
+
+    use crate::{Blueprint, BlueprintPlugin, BlueprintsPlugin, FromBlueprint};
+
+    #[derive(Default, Reflect)]
+    struct Rect;
+
+    #[test]
+    #[should_panic(expected = "missing mesh")]
+    fn build_panics_continue() {
+        #[derive(Component)]
+        struct MissingMesh;
+
+        impl FromBlueprint<Rect> for MissingMesh {
+            type Params<'w, 's> = ();
+            fn from_blueprint(_: &Rect, _: &mut StaticSystemParam<Self::Params<'_, '_>>) -> Self {
+                panic!("missing mesh");
+            }
+        }
+
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+            .add_plugins(BlueprintPlugin::<Rect, MissingMesh>::default());
+        app.world_mut().spawn(Blueprint::<Rect>::default());
+        // logs the blueprint and entity, then panics with the prefab's message
+        app.update();
+    }
+}
@@ -0,0 +1,182 @@
+//! The sync systems of blueprint types are not ordered relative to each other, so they run
+//! concurrently unless their params conflict.
+//!
+//! This checks the conflicts that the `Update` schedule found between the sync systems rather than
+//! running them, so it does not depend on the executor or the number of threads.
+
+use bevy::{ecs::system::StaticSystemParam, prelude::*, reflect::GetTypeRegistration};
+use bevy_reactive_blueprints::prelude::*;
+
+#[derive(Default, Resource)]
+struct Terrain;
+
+#[derive(Default, Reflect)]
+struct Tree;
+
+#[derive(Default, Reflect)]
+struct Rock;
+
+// read the terrain, so they can sync concurrently
+#[derive(Component)]
+struct TreeMesh;
+
+#[derive(Component)]
+struct RockMesh;
+
+// write the terrain, so they cannot
+#[derive(Component)]
+struct TreeCrater;
+
+#[derive(Component)]
+struct RockCrater;
+
+impl FromBlueprint<Tree> for TreeMesh {
+    type Params<'w, 's> = Res<'w, Terrain>;
+
+    fn from_blueprint(_: &Tree, _: &mut StaticSystemParam<Self::Params<'_, '_>>) -> Self {
+        TreeMesh
+    }
+}
+
+impl FromBlueprint<Rock> for RockMesh {
+    type Params<'w, 's> = Res<'w, Terrain>;
+
+    fn from_blueprint(_: &Rock, _: &mut StaticSystemParam<Self::Params<'_, '_>>) -> Self {
+        RockMesh
+    }
+}
+
+impl FromBlueprint<Tree> for TreeCrater {
+    type Params<'w, 's> = ResMut<'w, Terrain>;
+
+    fn from_blueprint(_: &Tree, _: &mut StaticSystemParam<Self::Params<'_, '_>>) -> Self {
+        TreeCrater
+    }
+}
+
+impl FromBlueprint<Rock> for RockCrater {
+    type Params<'w, 's> = ResMut<'w, Terrain>;
+
+    fn from_blueprint(_: &Rock, _: &mut StaticSystemParam<Self::Params<'_, '_>>) -> Self {
+        RockCrater
+    }
+}
+
+// adds the plugins of `B` and `P`, and spawns a blueprint for it
+fn add_blueprint<B, P>(app: &mut App)
+where
+    B: Default + GetTypeRegistration + FromReflect + TypePath,
+    P: Bundle + FromBlueprint<B>,
+{
+    app.add_plugins(BlueprintPlugin::<B, P>::default());
+    app.world_mut().spawn(Blueprint::new(B::default()));
+}
+
+// builds the blueprints added by `setup`, and returns the app with the number of sync systems and
+// the names of the components and resources that any two of them conflict on
+fn sync_conflicts(setup: impl FnOnce(&mut App)) -> (App, usize, Vec<String>) {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, BlueprintsPlugin))
+        .init_resource::<Terrain>();
+    setup(&mut app);
+    app.update();
+
+    let schedule = app.world().resource::<Schedules>().get(Update).unwrap();
+    let sync_systems = schedule
+        .systems()
+        .unwrap()
+        .filter(|(_, system)| system.name().contains("sync_blueprint_prefab"))
+        .map(|(id, _)| id)
+        .collect::<Vec<_>>();
+    let conflicts = schedule
+        .graph()
+        .conflicting_systems()
+        .iter()
+        .filter(|(a, b, _)| sync_systems.contains(a) && sync_systems.contains(b))
+        .flat_map(|(.., conflicts)| conflicts)
+        .map(|id| {
+            let info = app.world().components().get_info(*id).unwrap();
+            info.name().to_string()
+        })
+        .collect();
+    (app, sync_systems.len(), conflicts)
+}
+
+#[test]
+fn blueprints_sync_concurrently_unless_their_params_conflict() {
+    let (_, systems, conflicts) = sync_conflicts(|app| {
+        add_blueprint::<Tree, TreeMesh>(app);
+        add_blueprint::<Rock, RockMesh>(app);
+    });
+    assert_eq!((systems, conflicts), (2, vec![]));
+
+    // the same plugins are unordered, so only their params keep them apart
+    let (_, systems, conflicts) = sync_conflicts(|app| {
+        add_blueprint::<Tree, TreeCrater>(app);
+        add_blueprint::<Rock, RockCrater>(app);
+    });
+    assert_eq!(
+        (systems, conflicts),
+        (2, vec![std::any::type_name::<Terrain>().to_string()])
+    );
+
+    // `BlueprintAssets` only reads `Assets<A>`, so the many prefabs creating the same kind of
+    // asset do not conflict, unlike with `ResMut<Assets<A>>`
+    #[cfg(feature = "asset")]
+    {
+        use assets::{add_shape_blueprints, Shape, ShapeHandle};
+
+        let (mut app, systems, conflicts) = sync_conflicts(add_shape_blueprints);
+        assert_eq!((systems, conflicts), (10, vec![]));
+        let handles = app
+            .world_mut()
+            .query::<&ShapeHandle>()
+            .iter(app.world())
+            .map(|handle| handle.0.clone())
+            .collect::<Vec<_>>();
+        let shapes = app.world().resource::<Assets<Shape>>();
+        assert_eq!(shapes.len(), 10);
+        assert!(handles
+            .iter()
+            .all(|handle| shapes.get(handle).is_some_and(|shape| shape.0 == 0.)));
+    }
+}
+
+#[cfg(feature = "asset")]
+mod assets {
+    use super::*;
+
+    #[derive(Asset, TypePath)]
+    pub struct Shape(pub f32);
+
+    #[derive(Component)]
+    pub struct ShapeHandle(pub Handle<Shape>);
+
+    // ten blueprint types whose prefabs all add `Shape` assets
+    macro_rules! shape_blueprints {
+        ($($blueprint:ident),*) => {
+            $(
+                #[derive(Default, Reflect)]
+                struct $blueprint(f32);
+
+                impl FromBlueprint<$blueprint> for ShapeHandle {
+                    type Params<'w, 's> = BlueprintAssets<'w, 's, Shape>;
+
+                    fn from_blueprint(
+                        blueprint: &$blueprint,
+                        shapes: &mut StaticSystemParam<Self::Params<'_, '_>>,
+                    ) -> Self {
+                        ShapeHandle(shapes.add(Shape(blueprint.0)))
+                    }
+                }
+            )*
+
+            pub fn add_shape_blueprints(app: &mut App) {
+                app.add_plugins(AssetPlugin::default()).init_asset::<Shape>();
+                $(add_blueprint::<$blueprint, ShapeHandle>(app);)*
+            }
+        };
+    }
+
+    shape_blueprints!(S0, S1, S2, S3, S4, S5, S6, S7, S8, S9);
+}